@@ -91,6 +91,7 @@ export default defineConfig({
             { text: 'Trigonometry', link: '/api/trig' },
             { text: 'Polynomials', link: '/api/poly' },
             { text: 'Regression', link: '/api/regression' },
+            { text: 'Machine Learning', link: '/api/ml' },
             { text: 'Complex Numbers', link: '/api/complex' },
             { text: 'Calculus', link: '/api/calculus' },
             { text: 'Unit Conversions', link: '/api/units' }
@@ -1,3 +1,10 @@
+//! # Spectral Analysis
+//! 
+//! Smoothing, peak detection, baseline removal, deconvolution and filtering.
+//! The slice-based wrappers below do not depend on `SciEngine`, so they are
+//! available in the default (non-`threads`) build.
+
+use rayon::prelude::*;
 use wasm_bindgen::prelude::*;
 
 pub mod smooth_sg;
@@ -8,12 +15,13 @@ pub mod filters;
 pub mod snr;
 
 pub use smooth_sg::smooth_savitzky_golay;
-pub use peak_detection::find_peaks;
+pub use peak_detection::{find_peaks, find_peaks_2d, peak_prominence, filter_min_distance};
 pub use baseline::{remove_baseline, remove_baseline_iterative};
 pub use deconvolve::deconvolve_rl;
 pub use filters::butterworth_lowpass;
-pub use snr::estimate_snr;
+pub use snr::{estimate_snr, estimate_snr_with, snr_powers, NoiseMethod};
 
+/// Savitzky-Golay smoothing of a signal.
 #[wasm_bindgen(js_name = smoothSG)]
 pub fn smooth_sg_wasm(data: &[f64], window: usize, degree: usize) -> Vec<f64> {
     let mut out = vec![0.0; data.len()];
@@ -21,11 +29,69 @@ pub fn smooth_sg_wasm(data: &[f64], window: usize, degree: usize) -> Vec<f64> {
     out
 }
 
+/// Separable Savitzky-Golay smoothing of a row-major `rows x cols` image.
+#[wasm_bindgen(js_name = smoothSG2D)]
+pub fn smooth_sg_2d_wasm(data: &[f64], rows: usize, cols: usize, window: usize, degree: usize) -> Result<Vec<f64>, JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Data length must equal rows * cols"));
+    }
+    if window % 2 == 0 || window < 3 {
+        return Err(JsValue::from_str("Window must be odd and at least 3"));
+    }
+    if window <= degree {
+        return Err(JsValue::from_str("Window must be larger than the polynomial degree"));
+    }
+    Ok(smooth_sg::savitzky_golay_2d(data, rows, cols, window, degree))
+}
+
+/// Peak detection with threshold and optional prominence.
 #[wasm_bindgen(js_name = findPeaks)]
 pub fn find_peaks_wasm(data: &[f64], threshold: f64, prominence: Option<f64>) -> Vec<u32> {
     find_peaks(data, threshold, prominence.unwrap_or(0.0))
 }
 
+/// 2D peak detection on a row-major image; returns interleaved `[row, col, ...]`.
+#[wasm_bindgen(js_name = findPeaks2D)]
+pub fn find_peaks_2d_wasm(data: &[f64], rows: usize, cols: usize, threshold: f64, min_distance: usize) -> Vec<u32> {
+    find_peaks_2d(data, rows, cols, threshold, min_distance)
+}
+
+/// Integrated area of each peak between its surrounding valleys - Parallel
+///
+/// From each peak index the signal is followed downhill on both sides to the nearest local
+/// minimum. With `baseline`, the straight line joining the two valleys is subtracted before
+/// trapezoidal integration over `x`. Returns one area per peak.
+#[wasm_bindgen(js_name = integratePeaks)]
+pub fn integrate_peaks(x: &[f64], y: &[f64], peak_indices: &[u32], baseline: bool) -> Result<Vec<f64>, JsValue> {
+    let n = y.len();
+    if x.len() != n {
+        return Err(JsValue::from_str("x and y must have the same length"));
+    }
+    if peak_indices.iter().any(|&p| p as usize >= n) {
+        return Err(JsValue::from_str("Peak index out of range"));
+    }
+
+    Ok(peak_indices.par_iter()
+        .map(|&p| {
+            let p = p as usize;
+            let mut lo = p;
+            while lo > 0 && y[lo - 1] <= y[lo] { lo -= 1; }
+            let mut hi = p;
+            while hi + 1 < n && y[hi + 1] <= y[hi] { hi += 1; }
+
+            let slope = if baseline && x[hi] != x[lo] { (y[hi] - y[lo]) / (x[hi] - x[lo]) } else { 0.0 };
+            let base = |i: usize| if baseline { y[lo] + slope * (x[i] - x[lo]) } else { 0.0 };
+
+            (lo..hi).map(|i| {
+                let a = y[i] - base(i);
+                let b = y[i + 1] - base(i + 1);
+                0.5 * (a + b) * (x[i + 1] - x[i])
+            }).sum()
+        })
+        .collect())
+}
+
+/// Iterative polynomial baseline removal.
 #[wasm_bindgen(js_name = removeBaselineIterative)]
 pub fn baseline_iterative_wasm(data: &[f64], x: &[f64], order: usize, iters: usize) -> Vec<f64> {
     let mut out = vec![0.0; data.len()];
@@ -33,6 +99,7 @@ pub fn baseline_iterative_wasm(data: &[f64], x: &[f64], order: usize, iters: usi
     out
 }
 
+/// Polynomial baseline removal.
 #[wasm_bindgen(js_name = removeBaseline)]
 pub fn baseline_remove_wasm(data: &[f64], x: &[f64], order: usize) -> Vec<f64> {
     let mut out = vec![0.0; data.len()];
@@ -40,13 +107,79 @@ pub fn baseline_remove_wasm(data: &[f64], x: &[f64], order: usize) -> Vec<f64> {
     out
 }
 
+/// Richardson-Lucy deconvolution.
+/// `progress`, if given, is called with the completed fraction in [0, 1] as iterations finish.
+/// Setting `abort` stops after the current iteration and returns the estimate so far.
 #[wasm_bindgen(js_name = deconvolveRL)]
-pub fn deconvolve_rl_wasm(data: &[f64], kernel: &[f64], iterations: u32) -> Vec<f64> {
+pub fn deconvolve_rl_wasm(
+    data: &[f64],
+    kernel: &[f64],
+    iterations: u32,
+    progress: Option<js_sys::Function>,
+    abort: Option<crate::utils::AbortFlag>,
+) -> Result<Vec<f64>, JsValue> {
     let mut out = vec![0.0; data.len()];
-    deconvolve_rl(data, kernel, iterations, &mut out);
-    out
+    let control = crate::utils::iteration_control(progress.as_ref(), abort.as_ref(), iterations as usize);
+    deconvolve::deconvolve_rl_with_progress(data, kernel, iterations, &mut out, control)?;
+    Ok(out)
 }
 
+/// Output of [`deconvolve_rl_reg_wasm`]: the restored signal and the iterations actually run.
+#[wasm_bindgen]
+pub struct DeconvolutionResult {
+    data: Vec<f64>,
+    pub iterations: u32,
+}
+
+#[wasm_bindgen]
+impl DeconvolutionResult {
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<f64> {
+        self.data.clone()
+    }
+}
+
+/// Richardson-Lucy deconvolution with total-variation regularization and early stopping.
+/// `reg_weight` must be in [0, 0.5) (0 disables the TV term); iteration stops once the
+/// relative L1 change between estimates falls below `tol`, or after `max_iters`.
+#[wasm_bindgen(js_name = deconvolveRLReg)]
+pub fn deconvolve_rl_reg_wasm(
+    data: &[f64],
+    kernel: &[f64],
+    max_iters: u32,
+    tol: f64,
+    reg_weight: f64,
+) -> Result<DeconvolutionResult, JsValue> {
+    if !(0.0..0.5).contains(&reg_weight) {
+        return Err(JsValue::from_str("reg_weight must be in [0, 0.5)"));
+    }
+    let mut out = vec![0.0; data.len()];
+    let iterations = deconvolve::deconvolve_rl_reg(data, kernel, max_iters, tol, reg_weight, &mut out);
+    Ok(DeconvolutionResult { data: out, iterations })
+}
+
+/// 2D Richardson-Lucy deconvolution of a row-major `rows x cols` image with a row-major
+/// `psf_rows x psf_cols` point spread function.
+#[wasm_bindgen(js_name = deconvolveRL2D)]
+pub fn deconvolve_rl_2d_wasm(
+    data: &[f64],
+    rows: usize,
+    cols: usize,
+    psf: &[f64],
+    psf_rows: usize,
+    psf_cols: usize,
+    iterations: u32,
+) -> Result<Vec<f64>, JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Data length must equal rows * cols"));
+    }
+    if psf.len() != psf_rows * psf_cols || psf.is_empty() {
+        return Err(JsValue::from_str("PSF length must equal psf_rows * psf_cols and be non-zero"));
+    }
+    Ok(deconvolve::deconvolve_rl_2d(data, rows, cols, psf, psf_rows, psf_cols, iterations))
+}
+
+/// 2nd order Butterworth low-pass filter.
 #[wasm_bindgen(js_name = butterworthLowpass)]
 pub fn butterworth_filter_wasm(data: &[f64], cutoff: f64, fs: f64) -> Vec<f64> {
     let mut out = vec![0.0; data.len()];
@@ -54,9 +187,28 @@ pub fn butterworth_filter_wasm(data: &[f64], cutoff: f64, fs: f64) -> Vec<f64> {
     out
 }
 
+/// Signal-to-noise ratio estimate in dB.
+///
+/// `method` selects the noise model: `"mad_diff"` (default), `"detrend_std"` or `"highpass"`.
+/// Returns `Infinity` when the estimated noise power is zero.
 #[wasm_bindgen(js_name = estimateSNR)]
-pub fn snr_estimate_wasm(data: &[f64]) -> f64 {
-    estimate_snr(data)
+pub fn snr_estimate_wasm(data: &[f64], method: Option<String>) -> Result<f64, JsValue> {
+    Ok(estimate_snr_with(data, noise_method(method)?))
+}
+
+/// Raw `[signal_power, noise_power]` behind `estimateSNR`, for computing custom ratios.
+#[wasm_bindgen(js_name = snrPowers)]
+pub fn snr_powers_wasm(data: &[f64], method: Option<String>) -> Result<Vec<f64>, JsValue> {
+    let (signal, noise) = snr_powers(data, noise_method(method)?);
+    Ok(vec![signal, noise])
+}
+
+fn noise_method(method: Option<String>) -> Result<NoiseMethod, JsValue> {
+    match method {
+        None => Ok(NoiseMethod::MadDiff),
+        Some(name) => NoiseMethod::parse(&name)
+            .ok_or_else(|| JsValue::from_str("Noise method must be \"mad_diff\", \"detrend_std\" or \"highpass\"")),
+    }
 }
 
 /// Downsamples the signal by keeping every `n`-th sample.
@@ -87,3 +239,116 @@ pub fn resample_linear(data: &[f64], new_len: usize) -> Vec<f64> {
         }
     }).collect()
 }
+
+/// Replaces `NaN`s by linear interpolation between the nearest non-`NaN` neighbors - Parallel
+///
+/// Leading and trailing `NaN` runs are held at the nearest valid value. An all-`NaN`
+/// input is returned unchanged.
+#[wasm_bindgen(js_name = fillNanLinear)]
+pub fn fill_nan_linear(data: &[f64]) -> Vec<f64> {
+    let valid: Vec<usize> = (0..data.len()).into_par_iter()
+        .with_min_len(8192)
+        .filter(|&i| !data[i].is_nan())
+        .collect();
+    if valid.is_empty() { return data.to_vec(); }
+
+    data.par_iter()
+        .enumerate()
+        .with_min_len(8192)
+        .map(|(i, &v)| {
+            if !v.is_nan() { return v; }
+            // `pos` is the first valid index after `i`
+            let pos = valid.partition_point(|&j| j < i);
+            if pos == 0 { return data[valid[0]]; }
+            if pos == valid.len() { return data[valid[pos - 1]]; }
+            let (lo, hi) = (valid[pos - 1], valid[pos]);
+            let t = (i - lo) as f64 / (hi - lo) as f64;
+            data[lo] + t * (data[hi] - data[lo])
+        })
+        .collect()
+}
+
+/// Removes all `NaN` values, preserving the order of the rest - Parallel
+#[wasm_bindgen(js_name = dropNan)]
+pub fn drop_nan(data: &[f64]) -> Vec<f64> {
+    data.par_iter()
+        .with_min_len(8192)
+        .cloned()
+        .filter(|v| !v.is_nan())
+        .collect()
+}
+
+/// Clips every value to `[lo, hi]`; vectorized `clamp` - Parallel
+#[wasm_bindgen]
+pub fn clip(data: &[f64], lo: f64, hi: f64) -> Result<Vec<f64>, JsValue> {
+    if lo > hi {
+        return Err(JsValue::from_str("Lower bound must not exceed upper bound"));
+    }
+    Ok(data.par_iter()
+        .with_min_len(8192)
+        .map(|&v| crate::basic::clamp(v, lo, hi))
+        .collect())
+}
+
+/// Clips values to the `lower_pct` and `upper_pct` percentiles (0-100) - Parallel
+#[wasm_bindgen]
+pub fn winsorize(data: &[f64], lower_pct: f64, upper_pct: f64) -> Result<Vec<f64>, JsValue> {
+    if !(0.0..=100.0).contains(&lower_pct) || !(0.0..=100.0).contains(&upper_pct) || lower_pct > upper_pct {
+        return Err(JsValue::from_str("Percentiles must satisfy 0 <= lower <= upper <= 100"));
+    }
+    if data.is_empty() { return Ok(vec![]); }
+    let lo = crate::stats::percentile(data, lower_pct);
+    let hi = crate::stats::percentile(data, upper_pct);
+    clip(data, lo, hi)
+}
+
+/// Replaces `NaN` and `±Inf` with `value` - Parallel
+#[wasm_bindgen(js_name = replaceNonFinite)]
+pub fn replace_nonfinite(data: &[f64], value: f64) -> Vec<f64> {
+    data.par_iter()
+        .with_min_len(8192)
+        .map(|&v| if v.is_finite() { v } else { value })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_nan_linear_interior_and_edges() {
+        let nan = f64::NAN;
+        let data = [nan, 1.0, nan, nan, nan, 5.0, 6.0, nan];
+        let filled = fill_nan_linear(&data);
+        assert_eq!(filled, vec![1.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 6.0]);
+        assert!(fill_nan_linear(&[nan, nan]).iter().all(|v| v.is_nan()));
+        assert_eq!(drop_nan(&data), vec![1.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn test_clip_winsorize_and_replace() {
+        let data: Vec<f64> = (0..=10).map(|i| i as f64).collect();
+        assert_eq!(clip(&data, 2.0, 5.0).unwrap()[..7], [2.0, 2.0, 2.0, 3.0, 4.0, 5.0, 5.0]);
+        let w = winsorize(&data, 10.0, 90.0).unwrap();
+        assert_eq!((w[0], w[5], w[10]), (1.0, 5.0, 9.0));
+        assert_eq!(replace_nonfinite(&[1.0, f64::NAN, f64::NEG_INFINITY], 0.0), vec![1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_integrate_peaks_valley_to_valley() {
+        let x: Vec<f64> = (0..9).map(|i| i as f64 * 0.5).collect();
+        let y = [1.0, 2.0, 3.0, 2.0, 1.0, 2.0, 4.0, 2.0, 2.0];
+        let areas = integrate_peaks(&x, &y, &[2, 6], false).unwrap();
+        // Peak at 2 spans [0, 4]; peak at 6 spans [4, 8]
+        assert!((areas[0] - 4.0).abs() < 1e-12);
+        assert!((areas[1] - 4.75).abs() < 1e-12);
+
+        let areas = integrate_peaks(&x, &y, &[2, 6], true).unwrap();
+        assert!((areas[0] - 2.0).abs() < 1e-12);
+        assert!((areas[1] - 1.75).abs() < 1e-12);
+
+        // Valleys at the same x leave a flat baseline instead of a 0/0 slope
+        let areas = integrate_peaks(&[2.0, 2.0, 2.0], &[1.0, 3.0, 1.0], &[1], true).unwrap();
+        assert_eq!(areas, vec![0.0]);
+    }
+}
@@ -28,24 +28,100 @@ pub fn find_peaks(data: &[f64], threshold: f64, prominence: f64) -> Vec<u32> {
     }
 
     // Stage 2: Prominence filtering (Simplified)
-    cand_indices.into_iter().filter(|&i| {
-        let val = data[i];
-        
-        let mut left_min = val;
-        for j in (0..i).rev() {
-            if data[j] > val { break; }
-            if data[j] < left_min { left_min = data[j]; }
+    cand_indices.into_iter()
+        .filter(|&i| peak_prominence(data, i) >= prominence)
+        .map(|i| i as u32)
+        .collect()
+}
+
+/// Prominence of the peak at `i`: height above the higher of the two lowest points
+/// reached before the signal rises above the peak on either side.
+pub fn peak_prominence(data: &[f64], i: usize) -> f64 {
+    let val = data[i];
+    
+    let mut left_min = val;
+    for &v in data[..i].iter().rev() {
+        if v > val { break; }
+        if v < left_min { left_min = v; }
+    }
+    
+    let mut right_min = val;
+    for &v in &data[i + 1..] {
+        if v > val { break; }
+        if v < right_min { right_min = v; }
+    }
+    
+    val - left_min.max(right_min)
+}
+
+/// Keeps the highest peaks so that no two retained peaks are closer than `min_distance` samples.
+/// `peaks` must be sorted ascending; the result is sorted ascending.
+pub fn filter_min_distance(data: &[f64], peaks: &[usize], min_distance: usize) -> Vec<usize> {
+    if min_distance <= 1 || peaks.len() < 2 { return peaks.to_vec(); }
+
+    let mut order: Vec<usize> = (0..peaks.len()).collect();
+    order.sort_by(|&a, &b| data[peaks[b]].partial_cmp(&data[peaks[a]]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut keep = vec![true; peaks.len()];
+    for &k in &order {
+        if !keep[k] { continue; }
+        let p = peaks[k];
+        let mut j = k;
+        while j > 0 && p - peaks[j - 1] < min_distance {
+            j -= 1;
+            keep[j] = false;
         }
-        
-        let mut right_min = val;
-        for j in (i+1)..n {
-            if data[j] > val { break; }
-            if data[j] < right_min { right_min = data[j]; }
+        let mut j = k + 1;
+        while j < peaks.len() && peaks[j] - p < min_distance {
+            keep[j] = false;
+            j += 1;
         }
-        
-        let higher_min = left_min.max(right_min);
-        (val - higher_min) >= prominence
-    }).map(|i| i as u32).collect()
+    }
+
+    peaks.iter().zip(keep).filter(|(_, k)| *k).map(|(&p, _)| p).collect()
+}
+
+/// 2D local-maxima detection with non-maximum suppression - Parallel
+///
+/// A pixel is a candidate when it exceeds `threshold` and is strictly greater than all of its
+/// (up to 8) neighbors. Candidates are then accepted in descending intensity order, suppressing
+/// any later candidate within a `(2 * min_distance - 1)`-wide square around an accepted peak.
+/// Returns interleaved `[row, col, ...]` in descending intensity order.
+pub fn find_peaks_2d(data: &[f64], rows: usize, cols: usize, threshold: f64, min_distance: usize) -> Vec<u32> {
+    if rows == 0 || cols == 0 || data.len() != rows * cols { return vec![]; }
+
+    let is_peak = |r: usize, c: usize| {
+        let val = data[r * cols + c];
+        if val.is_nan() || val <= threshold { return false; }
+        for nr in r.saturating_sub(1)..=(r + 1).min(rows - 1) {
+            for nc in c.saturating_sub(1)..=(c + 1).min(cols - 1) {
+                if (nr != r || nc != c) && data[nr * cols + nc] >= val { return false; }
+            }
+        }
+        true
+    };
+
+    let mut candidates: Vec<usize> = (0..rows).into_par_iter()
+        .with_min_len((4096 / cols).max(1))
+        .flat_map_iter(|r| (0..cols).filter(move |&c| is_peak(r, c)).map(move |c| r * cols + c))
+        .collect();
+    candidates.sort_by(|&a, &b| data[b].partial_cmp(&data[a]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let reach = min_distance.saturating_sub(1);
+    let mut suppressed = vec![false; if reach > 0 { rows * cols } else { 0 }];
+    let mut result = Vec::new();
+    for idx in candidates {
+        let (r, c) = (idx / cols, idx % cols);
+        if reach > 0 {
+            if suppressed[idx] { continue; }
+            for nr in r.saturating_sub(reach)..=(r + reach).min(rows - 1) {
+                suppressed[nr * cols + c.saturating_sub(reach)..=nr * cols + (c + reach).min(cols - 1)].fill(true);
+            }
+        }
+        result.push(r as u32);
+        result.push(c as u32);
+    }
+    result
 }
 
 #[cfg(test)]
@@ -68,4 +144,26 @@ mod tests {
         let peaks_high_prom = find_peaks(&data, 0.0, 2.0);
         assert_eq!(peaks_high_prom, vec![2]);
     }
+
+    #[test]
+    fn test_filter_min_distance_keeps_highest() {
+        let data = vec![0.0, 2.0, 0.0, 3.0, 0.0, 1.0, 0.0, 0.0, 1.5, 0.0];
+        let peaks = vec![1, 3, 5, 8];
+        assert_eq!(filter_min_distance(&data, &peaks, 3), vec![3, 8]);
+        assert_eq!(filter_min_distance(&data, &peaks, 1), peaks);
+    }
+
+    #[test]
+    fn test_find_peaks_2d_suppression() {
+        let (rows, cols) = (5, 6);
+        let mut img = vec![0.0; rows * cols];
+        img[cols + 1] = 5.0;
+        img[cols + 3] = 4.0;
+        img[3 * cols + 4] = 3.0;
+        img[4 * cols] = 0.5;
+
+        assert_eq!(find_peaks_2d(&img, rows, cols, 1.0, 0), vec![1, 1, 1, 3, 3, 4]);
+        assert_eq!(find_peaks_2d(&img, rows, cols, 1.0, 3), vec![1, 1, 3, 4]);
+        assert_eq!(find_peaks_2d(&img, rows, cols, 0.0, 1).len(), 8);
+    }
 }
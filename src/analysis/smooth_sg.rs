@@ -1,4 +1,5 @@
 use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
 
 /// Savitzky-Golay Smoothing Filter - Parallel (Chunked)
 pub fn smooth_savitzky_golay(data: &[f64], window: usize, degree: usize, out: &mut [f64]) {
@@ -41,6 +42,53 @@ pub fn smooth_savitzky_golay(data: &[f64], window: usize, degree: usize, out: &m
         });
 }
 
+/// Separable Savitzky-Golay smoothing of a row-major `rows x cols` image - Parallel
+///
+/// Applies the 1D filter from [`calculate_sg_coeffs`] along every row, then along every column.
+/// This is not the same as a true 2D least-squares polynomial fit (the implied surface has no
+/// mixed terms beyond the product of the two 1D fits), but it costs $O(2w)$ rather than $O(w^2)$
+/// per pixel and still preserves peak heights far better than a Gaussian blur.
+/// As in the 1D filter, the `window / 2` samples at each end of an axis are left unsmoothed,
+/// and an axis shorter than the window is skipped. An image with no rows or columns is returned as is.
+pub fn savitzky_golay_2d(data: &[f64], rows: usize, cols: usize, window: usize, degree: usize) -> Vec<f64> {
+    let mut out = data.to_vec();
+    if rows == 0 || cols == 0 || window < 3 || window % 2 == 0 || degree >= window {
+        return out;
+    }
+    let coeffs = match calculate_sg_coeffs(window, degree) {
+        Ok(c) => c,
+        Err(_) => return out,
+    };
+    let half = window / 2;
+
+    if cols >= window {
+        out.par_chunks_mut(cols)
+            .zip(data.par_chunks(cols))
+            .for_each(|(dst, src)| {
+                for c in half..cols - half {
+                    dst[c] = coeffs.iter().enumerate().map(|(j, w)| w * src[c + j - half]).sum();
+                }
+            });
+    }
+    if rows >= window {
+        let by_rows = out.clone();
+        out.par_chunks_mut(cols)
+            .enumerate()
+            .skip(half)
+            .take(rows - 2 * half)
+            .for_each(|(r, dst)| {
+                dst.fill(0.0);
+                for (j, w) in coeffs.iter().enumerate() {
+                    let src = &by_rows[(r + j - half) * cols..(r + j - half + 1) * cols];
+                    for (d, s) in dst.iter_mut().zip(src) {
+                        *d += w * s;
+                    }
+                }
+            });
+    }
+    out
+}
+
 fn match_static_sg(data: &[f64], window: usize, out: &mut [f64]) -> Option<()> {
     let n = data.len();
     let half = window / 2;
@@ -91,6 +139,15 @@ fn match_static_sg(data: &[f64], window: usize, out: &mut [f64]) -> Option<()> {
 }
 
 pub fn calculate_sg_coeffs(window: usize, degree: usize) -> Result<Vec<f64>, String> {
+    calculate_sg_coeffs_deriv(window, degree, 0)
+}
+
+/// Savitzky-Golay convolution weights for the `deriv`-th derivative (unit sample spacing).
+/// Divide by `h^deriv` for a sample spacing `h`.
+pub fn calculate_sg_coeffs_deriv(window: usize, degree: usize, deriv: usize) -> Result<Vec<f64>, String> {
+    if deriv > degree {
+        return Err("Derivative order must not exceed polynomial degree".into());
+    }
     let half = (window / 2) as i32;
     let m = degree + 1;
     let mut matrix = vec![0.0; m * m];
@@ -107,7 +164,8 @@ pub fn calculate_sg_coeffs(window: usize, degree: usize) -> Result<Vec<f64>, Str
         }
     }
     
-    b[0] = 1.0; 
+    b[deriv] = 1.0; 
+    let factorial: f64 = (1..=deriv).map(|v| v as f64).product();
     
     if let Some(coeffs_fit) = crate::fitting::solve_linear_system(&mut matrix, &mut b, m) {
         let mut weights = vec![0.0; window];
@@ -118,10 +176,104 @@ pub fn calculate_sg_coeffs(window: usize, degree: usize) -> Result<Vec<f64>, Str
                 val += coeffs_fit[p] * pk;
                 pk *= k as f64;
             }
-            weights[idx] = val;
+            weights[idx] = val * factorial;
         }
         Ok(weights)
     } else {
         Err("Failed to solve SG system".into())
     }
 }
+
+/// Returns the Savitzky-Golay convolution weights for a window, polynomial degree
+/// and derivative order (0 = smoothing).
+#[wasm_bindgen(js_name = sgCoefficients)]
+pub fn sg_coefficients(window: usize, degree: usize, deriv: usize) -> Result<Vec<f64>, JsValue> {
+    if window % 2 == 0 || window < 3 {
+        return Err(JsValue::from_str("Window must be odd and at least 3"));
+    }
+    if window <= degree {
+        return Err(JsValue::from_str("Window must be larger than the polynomial degree"));
+    }
+    calculate_sg_coeffs_deriv(window, degree, deriv).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sg_coeffs_match_static_kernel() {
+        let w = calculate_sg_coeffs(5, 2).unwrap();
+        let expected = [-3.0, 12.0, 17.0, 12.0, -3.0];
+        for (a, b) in w.iter().zip(expected.iter()) {
+            assert!((a - b / 35.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_sg_first_derivative_coeffs() {
+        // Quadratic fit, first derivative over 5 points: [-2, -1, 0, 1, 2] / 10
+        let w = calculate_sg_coeffs_deriv(5, 2, 1).unwrap();
+        let expected = [-2.0, -1.0, 0.0, 1.0, 2.0];
+        for (a, b) in w.iter().zip(expected.iter()) {
+            assert!((a - b / 10.0).abs() < 1e-12);
+        }
+    }
+
+    fn gaussian_blob(rows: usize, cols: usize) -> Vec<f64> {
+        let (cy, cx, sigma) = (rows as f64 / 2.0, cols as f64 / 2.0, 4.0);
+        (0..rows * cols)
+            .map(|i| {
+                let (y, x) = ((i / cols) as f64 - cy, (i % cols) as f64 - cx);
+                10.0 * (-(x * x + y * y) / (2.0 * sigma * sigma)).exp()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sg_2d_denoises_gaussian_blob() {
+        let (rows, cols) = (40, 48);
+        let clean = gaussian_blob(rows, cols);
+        // Deterministic pseudo-noise in [-0.5, 0.5)
+        let mut state = 12345u64;
+        let noisy: Vec<f64> = clean.iter().map(|v| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            v + ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5)
+        }).collect();
+
+        let smoothed = savitzky_golay_2d(&noisy, rows, cols, 7, 3);
+        let interior = |v: &[f64]| -> f64 {
+            let mut err = 0.0;
+            for r in 3..rows - 3 {
+                for c in 3..cols - 3 {
+                    err += (v[r * cols + c] - clean[r * cols + c]).powi(2);
+                }
+            }
+            err
+        };
+        assert!(interior(&smoothed) < 0.3 * interior(&noisy));
+        let centre = (rows / 2) * cols + cols / 2;
+        assert!((smoothed[centre] - clean[centre]).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_sg_2d_preserves_quadratic_surface() {
+        let (rows, cols) = (9, 11);
+        let surface: Vec<f64> = (0..rows * cols)
+            .map(|i| {
+                let (y, x) = ((i / cols) as f64, (i % cols) as f64);
+                1.0 + 0.5 * x - 0.2 * y + 0.1 * x * x + 0.3 * x * y - 0.05 * y * y
+            })
+            .collect();
+        let smoothed = savitzky_golay_2d(&surface, rows, cols, 5, 2);
+        for (a, b) in smoothed.iter().zip(&surface) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_sg_2d_empty_axis_is_untouched() {
+        assert!(savitzky_golay_2d(&[], 5, 0, 5, 2).is_empty());
+        assert!(savitzky_golay_2d(&[], 0, 7, 5, 2).is_empty());
+    }
+}
@@ -1,9 +1,49 @@
 use rayon::prelude::*;
 
-/// Robust SNR Estimate - Parallel
+/// Noise model used by the SNR estimators.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoiseMethod {
+    /// Median absolute first difference scaled by `1/0.6745`, squared and halved to undo the
+    /// doubling of white-noise power under differencing (robust to outliers).
+    MadDiff,
+    /// Standard deviation of the residual after removing a least-squares line.
+    DetrendStd,
+    /// Variance of the first difference divided by two (white-noise high-pass estimate).
+    Highpass,
+}
+
+impl NoiseMethod {
+    /// Parses `"mad_diff"`, `"detrend_std"` or `"highpass"`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "mad_diff" => Some(NoiseMethod::MadDiff),
+            "detrend_std" => Some(NoiseMethod::DetrendStd),
+            "highpass" => Some(NoiseMethod::Highpass),
+            _ => None,
+        }
+    }
+}
+
+/// Robust SNR Estimate in dB using the `MadDiff` noise model - Parallel
 pub fn estimate_snr(data: &[f64]) -> f64 {
+    estimate_snr_with(data, NoiseMethod::MadDiff)
+}
+
+/// SNR Estimate in dB with a selectable noise model - Parallel
+///
+/// Returns `0.0` for fewer than two samples and `INFINITY` when the estimated
+/// noise power is zero (e.g. a noiseless ramp) or more than 240 dB below the signal.
+pub fn estimate_snr_with(data: &[f64], method: NoiseMethod) -> f64 {
+    if data.len() < 2 { return 0.0; }
+    let (svar, nvar) = snr_powers(data, method);
+    if nvar == 0.0 { return f64::INFINITY; }
+    10.0 * (svar / nvar).log10()
+}
+
+/// Estimated `(signal_power, noise_power)`; the signal power is the population variance.
+pub fn snr_powers(data: &[f64], method: NoiseMethod) -> (f64, f64) {
     let n = data.len();
-    if n < 2 { return 0.0; }
+    if n < 2 { return (0.0, 0.0); }
     
     let sum: f64 = data.par_iter().sum();
     let mean = sum / n as f64;
@@ -13,13 +53,70 @@ pub fn estimate_snr(data: &[f64]) -> f64 {
         .sum();
     let svar = ss_tot / n as f64;
     
-    let mut diffs: Vec<f64> = data.par_windows(2).map(|w| (w[1] - w[0]).abs()).collect();
-    
-    diffs.par_sort_unstable_by(|a, b| a.partial_cmp(b).expect("Numeric SNR failure"));
-    
-    let ns = diffs[diffs.len() / 2] / 0.6745;
-    let nvar = ns.powi(2);
-    
-    if nvar < 1e-18 { return 100.0; }
-    10.0 * (svar / nvar).log10()
+    let nvar = match method {
+        NoiseMethod::MadDiff => {
+            let mut diffs: Vec<f64> = data.par_windows(2).map(|w| (w[1] - w[0]).abs()).collect();
+            diffs.par_sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            // Robust std of the differences, which carry twice the white-noise power
+            let ns = diffs[diffs.len() / 2] / 0.6745;
+            ns.powi(2) / 2.0
+        }
+        NoiseMethod::DetrendStd => {
+            // Least-squares line against the sample index
+            let nf = n as f64;
+            let t_mean = (nf - 1.0) / 2.0;
+            let s_ty: f64 = data.par_iter().enumerate()
+                .map(|(i, &y)| (i as f64 - t_mean) * (y - mean))
+                .sum();
+            let s_tt = nf * (nf * nf - 1.0) / 12.0;
+            let slope = s_ty / s_tt;
+            let ss_res: f64 = data.par_iter().enumerate()
+                .map(|(i, &y)| (y - mean - slope * (i as f64 - t_mean)).powi(2))
+                .sum();
+            ss_res / nf
+        }
+        NoiseMethod::Highpass => {
+            let ss_diff: f64 = data.par_windows(2).map(|w| (w[1] - w[0]).powi(2)).sum();
+            ss_diff / (2.0 * (n - 1) as f64)
+        }
+    };
+
+    // Noise below 1e-24 of the signal power (240 dB) is rounding error on a noiseless input
+    if nvar <= svar * 1e-24 { (svar, 0.0) } else { (svar, nvar) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noiseless_signals_are_infinite() {
+        let ramp: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        assert_eq!(estimate_snr_with(&ramp, NoiseMethod::DetrendStd), f64::INFINITY);
+        assert_eq!(estimate_snr(&[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_noise_methods_on_alternating_noise() {
+        // Slow ramp plus +/-0.1 alternating noise (power 0.01); being fully anti-correlated,
+        // it doubles under the first-difference model
+        let data: Vec<f64> = (0..1000)
+            .map(|i| 0.001 * i as f64 + if i % 2 == 0 { 0.1 } else { -0.1 })
+            .collect();
+        let (_, detrend) = snr_powers(&data, NoiseMethod::DetrendStd);
+        let (_, highpass) = snr_powers(&data, NoiseMethod::Highpass);
+        assert!((detrend - 0.01).abs() < 1e-3);
+        assert!((highpass - 0.02).abs() < 1e-3);
+        assert_eq!(NoiseMethod::parse("HighPass"), Some(NoiseMethod::Highpass));
+        assert_eq!(NoiseMethod::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_noise_methods_agree_on_white_noise() {
+        let noise = crate::utils::rng::random_normal(20_000, 0.0, 0.5, 9);
+        for method in [NoiseMethod::MadDiff, NoiseMethod::DetrendStd, NoiseMethod::Highpass] {
+            let (_, nvar) = snr_powers(&noise, method);
+            assert!((nvar / 0.25 - 1.0).abs() < 0.05, "{:?}: {}", method, nvar);
+        }
+    }
 }
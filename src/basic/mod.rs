@@ -3,6 +3,7 @@
 //! This module provides fundamental arithmetic and utility functions
 //! optimized for performance in WebAssembly environments.
 
+use rayon::prelude::*;
 use wasm_bindgen::prelude::*;
 
 /// Clamps a value between a minimum and maximum range.
@@ -70,6 +71,83 @@ pub fn round_to_precision(value: f64, decimals: u32) -> f64 {
     (value * factor).round() / factor
 }
 
+/// Modified Bessel function of the first kind, order zero.
+/// 
+/// Evaluated from its power series:
+/// $$ I_0(x) = \sum_{k=0}^{\infty} \frac{(x/2)^{2k}}{(k!)^2} $$
+/// 
+/// # Arguments
+/// * `x` - The argument.
+#[wasm_bindgen(js_name = besselI0)]
+pub fn bessel_i0(x: f64) -> f64 {
+    let half = x / 2.0;
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+    loop {
+        term *= (half / k) * (half / k);
+        sum += term;
+        if term < sum * 1e-17 || k > 500.0 { break; }
+        k += 1.0;
+    }
+    sum
+}
+
+/// Checks element-wise whether two arrays are equal within a tolerance - Parallel
+/// 
+/// Each pair must satisfy:
+/// $$ |a_i - b_i| \le \text{atol} + \text{rtol} \cdot |b_i| $$
+/// 
+/// Equal infinities compare as close. Arrays of different lengths are never close.
+/// 
+/// # Arguments
+/// * `a`, `b` - Arrays to compare (`b` is the reference).
+/// * `rtol` - Relative tolerance.
+/// * `atol` - Absolute tolerance.
+/// * `equal_nan` - Treat `NaN` in both arrays at the same position as equal (default `false`).
+#[wasm_bindgen]
+pub fn allclose(a: &[f64], b: &[f64], rtol: f64, atol: f64, equal_nan: Option<bool>) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let equal_nan = equal_nan.unwrap_or(false);
+    a.par_iter().zip(b.par_iter())
+        .with_min_len(8192)
+        .all(|(&x, &y)| {
+            if x.is_nan() || y.is_nan() {
+                return equal_nan && x.is_nan() && y.is_nan();
+            }
+            x == y || (x - y).abs() <= atol + rtol * y.abs()
+        })
+}
+
+/// Largest absolute element-wise difference between two arrays - Parallel
+/// 
+/// $$ \max_i |a_i - b_i| $$
+/// 
+/// Returns `NaN` if a position holds `NaN` in only one array (or in both, unless `equal_nan`).
+/// 
+/// # Arguments
+/// * `a`, `b` - Arrays of the same length.
+/// * `equal_nan` - Treat `NaN` in both arrays at the same position as a zero difference.
+#[wasm_bindgen(js_name = maxAbsDiff)]
+pub fn max_abs_diff(a: &[f64], b: &[f64], equal_nan: Option<bool>) -> Result<f64, JsValue> {
+    if a.len() != b.len() {
+        return Err(JsValue::from_str("Arrays must have the same length"));
+    }
+    let equal_nan = equal_nan.unwrap_or(false);
+    Ok(a.par_iter().zip(b.par_iter())
+        .with_min_len(8192)
+        .map(|(&x, &y)| {
+            if x == y || (equal_nan && x.is_nan() && y.is_nan()) {
+                0.0
+            } else {
+                (x - y).abs()
+            }
+        })
+        .reduce(|| 0.0, |m, d| if m.is_nan() || d.is_nan() { f64::NAN } else { m.max(d) }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,4 +177,25 @@ mod tests {
         assert_eq!(round_to_precision(3.14159, 2), 3.14);
         assert_eq!(round_to_precision(3.145, 2), 3.15);
     }
+
+    #[test]
+    fn test_bessel_i0() {
+        assert_eq!(bessel_i0(0.0), 1.0);
+        assert!((bessel_i0(1.0) - 1.2660658777520082).abs() < 1e-14);
+        assert!((bessel_i0(5.0) - 27.239871823604442).abs() < 1e-11);
+    }
+
+    #[test]
+    fn test_allclose_and_max_abs_diff() {
+        let a = [1.0, 2.0, f64::INFINITY, f64::NAN];
+        let b = [1.0 + 1e-9, 2.0, f64::INFINITY, f64::NAN];
+        assert!(!allclose(&a, &b, 1e-6, 0.0, None));
+        assert!(allclose(&a, &b, 1e-6, 0.0, Some(true)));
+        assert!(!allclose(&a[..2], &[1.1, 2.0], 1e-6, 1e-8, None));
+        assert!(!allclose(&a, &b[..3], 1e-6, 1e-8, Some(true)));
+
+        assert!(max_abs_diff(&a, &b, None).unwrap().is_nan());
+        assert!((max_abs_diff(&a, &b, Some(true)).unwrap() - 1e-9).abs() < 1e-15);
+        assert!(max_abs_diff(&[1.0], &[f64::NAN], Some(true)).unwrap().is_nan());
+    }
 }
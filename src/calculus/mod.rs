@@ -13,6 +13,11 @@ pub fn numerical_integrate(data: &[f64], h: f64) -> f64 {
     integrate_simpson(data, h)
 }
 
+#[wasm_bindgen(js_name = integrateTrapezoid)]
+pub fn numerical_integrate_trapezoid(data: &[f64], h: f64) -> f64 {
+    integrate_trapezoid(data, h)
+}
+
 /// Calculates the numerical gradient of a function sampled at points `x`.
 #[wasm_bindgen]
 pub fn gradient(data: &[f64], h: f64) -> Vec<f64> {
@@ -21,6 +26,123 @@ pub fn gradient(data: &[f64], h: f64) -> Vec<f64> {
     out
 }
 
+/// Calculates the numerical gradient of `y` sampled on a non-uniform grid `x` - Parallel
+/// Second-order three-point stencil in the interior, one-sided differences at the ends.
+/// `x` must be strictly increasing.
+#[wasm_bindgen(js_name = gradientNonuniform)]
+pub fn gradient_nonuniform(y: &[f64], x: &[f64]) -> Result<Vec<f64>, JsValue> {
+    three_point_gradient(y, x).map_err(JsValue::from_str)
+}
+
+fn three_point_gradient(y: &[f64], x: &[f64]) -> Result<Vec<f64>, &'static str> {
+    let n = y.len();
+    if n != x.len() {
+        return Err("X and Y must have the same length");
+    }
+    if n < 2 {
+        return Err("At least two samples are required");
+    }
+    if !x.windows(2).all(|w| w[1] > w[0]) {
+        return Err("X must be strictly increasing");
+    }
+
+    let mut out = vec![0.0; n];
+    out[0] = (y[1] - y[0]) / (x[1] - x[0]);
+    out[n-1] = (y[n-1] - y[n-2]) / (x[n-1] - x[n-2]);
+
+    out[1..n-1].par_iter_mut()
+        .with_min_len(16384)
+        .enumerate()
+        .for_each(|(k, val)| {
+            let i = k + 1;
+            let hs = x[i] - x[i-1];
+            let hd = x[i+1] - x[i];
+            *val = (hs * hs * y[i+1] + (hd * hd - hs * hs) * y[i] - hd * hd * y[i-1])
+                / (hs * hd * (hd + hs));
+        });
+
+    Ok(out)
+}
+
+/// Calculates the 1D Laplacian (second difference) of uniformly sampled data - Parallel
+/// Zero-Neumann boundaries via mirrored ghost points.
+#[wasm_bindgen(js_name = laplacian1D)]
+pub fn laplacian_1d(data: &[f64], h: f64) -> Vec<f64> {
+    let n = data.len();
+    if n < 2 { return vec![0.0; n]; }
+    let inv_h2 = 1.0 / (h * h);
+
+    (0..n).into_par_iter()
+        .with_min_len(16384)
+        .map(|i| {
+            let (left, right) = mirrored_neighbors(i, n);
+            (data[left] - 2.0 * data[i] + data[right]) * inv_h2
+        })
+        .collect()
+}
+
+/// Calculates the 2D Laplacian (5-point stencil) of a row-major `rows×cols` grid - Parallel
+/// Zero-Neumann boundaries via mirrored ghost points.
+#[wasm_bindgen(js_name = laplacian2D)]
+pub fn laplacian_2d(data: &[f64], rows: usize, cols: usize, h: f64) -> Result<Vec<f64>, JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Grid dimensions do not match data length"));
+    }
+    let inv_h2 = 1.0 / (h * h);
+
+    let mut out = vec![0.0; rows * cols];
+    out.par_chunks_mut(cols.max(1))
+        .enumerate()
+        .for_each(|(r, row_out)| {
+            let (up, down) = mirrored_neighbors(r, rows);
+            for c in 0..cols {
+                let (left, right) = mirrored_neighbors(c, cols);
+                let center = data[r * cols + c];
+                row_out[c] = (data[up * cols + c] + data[down * cols + c]
+                    + data[r * cols + left] + data[r * cols + right] - 4.0 * center) * inv_h2;
+            }
+        });
+    Ok(out)
+}
+
+/// Previous and next indices along an axis, reflecting across the edges (zero-Neumann ghosts).
+fn mirrored_neighbors(i: usize, len: usize) -> (usize, usize) {
+    if len < 2 { return (i, i); }
+    let prev = if i == 0 { 1 } else { i - 1 };
+    let next = if i == len - 1 { len - 2 } else { i + 1 };
+    (prev, next)
+}
+
+/// Calculates the divergence `dFx/dx + dFy/dy` of a 2D vector field on a row-major grid - Parallel
+/// Columns run along x and rows along y. Central differences inside, one-sided at the edges.
+#[wasm_bindgen(js_name = divergence2D)]
+pub fn divergence_2d(fx: &[f64], fy: &[f64], rows: usize, cols: usize, h: f64) -> Result<Vec<f64>, JsValue> {
+    if fx.len() != rows * cols || fy.len() != rows * cols {
+        return Err(JsValue::from_str("Grid dimensions do not match data length"));
+    }
+    // Returns (lo, hi, span) for a first difference along an axis of length `len`
+    let stencil = |i: usize, len: usize| -> (usize, usize, f64) {
+        if len < 2 { (i, i, 1.0) }
+        else if i == 0 { (0, 1, 1.0) }
+        else if i == len - 1 { (len - 2, len - 1, 1.0) }
+        else { (i - 1, i + 1, 2.0) }
+    };
+
+    let mut out = vec![0.0; rows * cols];
+    out.par_chunks_mut(cols.max(1))
+        .enumerate()
+        .for_each(|(r, row_out)| {
+            let (r_lo, r_hi, r_span) = stencil(r, rows);
+            for c in 0..cols {
+                let (c_lo, c_hi, c_span) = stencil(c, cols);
+                let dfx = (fx[r * cols + c_hi] - fx[r * cols + c_lo]) / (c_span * h);
+                let dfy = (fy[r_hi * cols + c] - fy[r_lo * cols + c]) / (r_span * h);
+                row_out[c] = dfx + dfy;
+            }
+        });
+    Ok(out)
+}
+
 /// Calculates the numerical derivative using the 5-point stencil method.
 pub unsafe fn diff_5pt_stencil(input: &[f64], h: f64, output: &mut [f64]) {
     let n = input.len();
@@ -71,6 +193,64 @@ pub fn integrate_simpson(data: &[f64], h: f64) -> f64 {
     result
 }
 
+/// Numerical Integration using the Trapezoidal Rule - Parallel
+pub fn integrate_trapezoid(data: &[f64], h: f64) -> f64 {
+    let n = data.len();
+    if n < 2 { return 0.0; }
+
+    let sum_mid: f64 = data[1..n-1].par_iter()
+        .with_min_len(32768)
+        .sum();
+
+    (0.5 * (data[0] + data[n-1]) + sum_mid) * h
+}
+
+/// Romberg Integration
+/// Richardson extrapolation of the trapezoidal rule for `f` over `[a, b]`.
+/// Stops when successive diagonal estimates differ by less than `tol` or after `max_levels`.
+#[wasm_bindgen(js_name = integrateRomberg)]
+pub fn integrate_romberg(f: &js_sys::Function, a: f64, b: f64, max_levels: usize, tol: f64) -> Result<f64, JsValue> {
+    let eval = |x: f64| -> Result<f64, JsValue> {
+        f.call1(&JsValue::NULL, &JsValue::from_f64(x))?
+            .as_f64().ok_or_else(|| JsValue::from_str("Romberg: Function must return a number"))
+    };
+    romberg(eval, a, b, max_levels, tol)
+}
+
+fn romberg(
+    mut eval: impl FnMut(f64) -> Result<f64, JsValue>,
+    a: f64,
+    b: f64,
+    max_levels: usize,
+    tol: f64,
+) -> Result<f64, JsValue> {
+    let levels = max_levels.clamp(1, 30);
+    let mut prev = vec![0.5 * (b - a) * (eval(a)? + eval(b)?)];
+    
+    for i in 1..levels {
+        let h = (b - a) / (1u64 << i) as f64;
+        let mut mid_sum = 0.0;
+        for k in 1..=(1u64 << (i - 1)) {
+            mid_sum += eval(a + (2 * k - 1) as f64 * h)?;
+        }
+
+        let mut row = Vec::with_capacity(i + 1);
+        row.push(0.5 * prev[0] + h * mid_sum);
+        let mut factor = 1.0;
+        for j in 1..=i {
+            factor *= 4.0;
+            row.push(row[j - 1] + (row[j - 1] - prev[j - 1]) / (factor - 1.0));
+        }
+
+        if (row[i] - prev[i - 1]).abs() < tol {
+            return Ok(row[i]);
+        }
+        prev = row;
+    }
+
+    Ok(prev[prev.len() - 1])
+}
+
 /// Adaptive Simpson's Integration
 pub fn integrate_adaptive(f: &dyn Fn(f64) -> f64, a: f64, b: f64, tol: f64) -> f64 {
     let fa = f(a);
@@ -172,15 +352,17 @@ pub fn roots_brent(f: &js_sys::Function, a: f64, b: f64, tol: f64) -> Result<f64
 
 /// Runge-Kutta 4th Order ODE Solver
 /// Solves dy/dt = f(t, y) from t_start to t_end
+/// `progress`, if given, is called with the completed fraction in [0, 1] as steps finish.
 #[wasm_bindgen]
-pub fn ode45_rk4(f: &js_sys::Function, y0: f64, t_start: f64, t_end: f64, steps: usize) -> Result<Vec<f64>, JsValue> {
+pub fn ode45_rk4(f: &js_sys::Function, y0: f64, t_start: f64, t_end: f64, steps: usize, progress: Option<js_sys::Function>) -> Result<Vec<f64>, JsValue> {
+    let mut report = crate::utils::progress_reporter(progress.as_ref(), steps);
     let h = (t_end - t_start) / steps as f64;
     let mut t = t_start;
     let mut y = y0;
     let mut res = Vec::with_capacity(steps + 1);
     res.push(y);
     
-    for _ in 0..steps {
+    for step in 0..steps {
         let k1 = f.call2(&JsValue::NULL, &JsValue::from_f64(t), &JsValue::from_f64(y))?
             .as_f64().ok_or_else(|| JsValue::from_str("RK4: Function must return a number"))?;
         let k2 = f.call2(&JsValue::NULL, &JsValue::from_f64(t + 0.5 * h), &JsValue::from_f64(y + 0.5 * h * k1))?
@@ -193,6 +375,7 @@ pub fn ode45_rk4(f: &js_sys::Function, y0: f64, t_start: f64, t_end: f64, steps:
         y += (h / 6.0) * (k1 + 2.0 * k2 + 2.0 * k3 + k4);
         t += h;
         res.push(y);
+        report(step + 1)?;
     }
     
     Ok(res)
@@ -220,6 +403,178 @@ pub fn interpolate_linear(x: &[f64], y: &[f64], xi: &[f64]) -> Result<Vec<f64>,
     Ok(res)
 }
 
+/// Polynomial interpolation through `(x, y)` in the barycentric form - Parallel
+/// The weights $w_j = 1 / \prod_{k \ne j} (x_j - x_k)$ are computed once; each `xi` is then
+/// evaluated in $O(n)$ as $\frac{\sum_j w_j y_j / (x_i - x_j)}{\sum_j w_j / (x_i - x_j)}$.
+/// A point that coincides with a node returns that node's `y` exactly.
+#[wasm_bindgen(js_name = interpolateBarycentric)]
+pub fn interpolate_barycentric(x: &[f64], y: &[f64], xi: &[f64]) -> Result<Vec<f64>, JsValue> {
+    if x.len() != y.len() || x.is_empty() {
+        return Err(JsValue::from_str("x and y must have the same, non-zero length"));
+    }
+    let weights = barycentric_weights(x).ok_or_else(|| JsValue::from_str("Interpolation nodes must be distinct"))?;
+
+    Ok(xi.par_iter()
+        .with_min_len(1024)
+        .map(|&v| {
+            let (mut num, mut den) = (0.0, 0.0);
+            for ((&xj, &yj), &wj) in x.iter().zip(y).zip(&weights) {
+                let d = v - xj;
+                if d == 0.0 {
+                    return yj;
+                }
+                let t = wj / d;
+                num += t * yj;
+                den += t;
+            }
+            num / den
+        })
+        .collect())
+}
+
+/// Barycentric weights, or `None` if two nodes coincide. Differences are scaled by
+/// $4 / (\max x - \min x)$ and the result normalized, so the products cannot overflow for
+/// large node counts; any common factor cancels in the barycentric formula.
+fn barycentric_weights(x: &[f64]) -> Option<Vec<f64>> {
+    let (lo, hi) = x.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let scale = if hi > lo { 4.0 / (hi - lo) } else { 1.0 };
+
+    let mut w = Vec::with_capacity(x.len());
+    for (j, &xj) in x.iter().enumerate() {
+        let mut prod = 1.0;
+        for (k, &xk) in x.iter().enumerate() {
+            if k != j {
+                let d = (xj - xk) * scale;
+                if d == 0.0 { return None; }
+                prod *= d;
+            }
+        }
+        w.push(1.0 / prod);
+    }
+    let max = w.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    Some(w.into_iter().map(|v| v / max).collect())
+}
+
+/// Inverse Distance Weighted (Shepard) interpolation of scattered 2D samples - Parallel
+/// `points` and `query` are interleaved `[x0, y0, x1, y1, ...]`. Each query value is
+/// $\sum_i w_i v_i / \sum_i w_i$ with $w_i = d_i^{-p}$; a query that coincides with a sample
+/// returns that sample's value.
+#[wasm_bindgen(js_name = idwInterpolate)]
+pub fn idw_interpolate(points: &[f64], values: &[f64], n_points: usize, query: &[f64], n_query: usize, power: f64) -> Result<Vec<f64>, JsValue> {
+    if points.len() != 2 * n_points || values.len() != n_points || query.len() != 2 * n_query {
+        return Err(JsValue::from_str("points and query must be interleaved [x, y] pairs matching their counts"));
+    }
+    if n_points == 0 {
+        return Err(JsValue::from_str("At least one sample point is required"));
+    }
+
+    // Weights are computed from squared distances: d^-p = (d^2)^(-p/2)
+    let half_p = -0.5 * power;
+    Ok(query.par_chunks(2)
+        .with_min_len(64)
+        .map(|q| {
+            let (mut num, mut den) = (0.0, 0.0);
+            for (p, &v) in points.chunks_exact(2).zip(values) {
+                let d2 = (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2);
+                if d2 == 0.0 {
+                    return v;
+                }
+                let w = d2.powf(half_p);
+                num += w * v;
+                den += w;
+            }
+            num / den
+        })
+        .collect())
+}
+
+/// Bilinear interpolation on a row-major `rows × cols` grid - Parallel
+///
+/// Query coordinates are in grid-index space: `qx` along columns, `qy` along rows, so
+/// `(qx, qy) = (j, i)` returns `grid[i * cols + j]`. Queries outside the grid follow
+/// `out_of_bounds`: `"clamp"` snaps them to the nearest edge, `"nan"` yields `NaN`.
+#[wasm_bindgen(js_name = interp2dBilinear)]
+pub fn interp2d_bilinear(grid: &[f64], rows: usize, cols: usize, qx: &[f64], qy: &[f64], out_of_bounds: &str) -> Result<Vec<f64>, JsValue> {
+    interp2d(grid, rows, cols, qx, qy, out_of_bounds, |x, y| {
+        let (j0, tx) = split_index(x, cols);
+        let (i0, ty) = split_index(y, rows);
+        let (j1, i1) = ((j0 + 1).min(cols - 1), (i0 + 1).min(rows - 1));
+        let top = grid[i0 * cols + j0] * (1.0 - tx) + grid[i0 * cols + j1] * tx;
+        let bottom = grid[i1 * cols + j0] * (1.0 - tx) + grid[i1 * cols + j1] * tx;
+        top * (1.0 - ty) + bottom * ty
+    })
+}
+
+/// Bicubic interpolation on a row-major `rows × cols` grid - Parallel
+///
+/// Separable Keys cubic convolution ($a = -0.5$, i.e. Catmull-Rom) over the surrounding 4×4
+/// samples, with edge samples repeated at the borders. Coordinates and `out_of_bounds` are as
+/// in `interp2dBilinear`.
+#[wasm_bindgen(js_name = interp2dBicubic)]
+pub fn interp2d_bicubic(grid: &[f64], rows: usize, cols: usize, qx: &[f64], qy: &[f64], out_of_bounds: &str) -> Result<Vec<f64>, JsValue> {
+    interp2d(grid, rows, cols, qx, qy, out_of_bounds, |x, y| {
+        let (j0, tx) = split_index(x, cols);
+        let (i0, ty) = split_index(y, rows);
+        let (wx, wy) = (keys_weights(tx), keys_weights(ty));
+        let tap = |base: usize, k: usize, n: usize| (base + k).saturating_sub(1).min(n - 1);
+
+        (0..4).map(|a| {
+            let row = tap(i0, a, rows) * cols;
+            let along_x: f64 = (0..4).map(|b| wx[b] * grid[row + tap(j0, b, cols)]).sum();
+            wy[a] * along_x
+        }).sum()
+    })
+}
+
+/// Shared bounds handling and parallel evaluation for the grid interpolators.
+fn interp2d(
+    grid: &[f64], rows: usize, cols: usize, qx: &[f64], qy: &[f64], out_of_bounds: &str,
+    sample: impl Fn(f64, f64) -> f64 + Sync,
+) -> Result<Vec<f64>, JsValue> {
+    if grid.len() != rows * cols || rows == 0 || cols == 0 {
+        return Err(JsValue::from_str("Grid dimensions do not match data length"));
+    }
+    if qx.len() != qy.len() {
+        return Err(JsValue::from_str("qx and qy must have the same length"));
+    }
+    let clamp = match out_of_bounds.to_ascii_lowercase().as_str() {
+        "clamp" => true,
+        "nan" => false,
+        _ => return Err(JsValue::from_str("out_of_bounds must be \"clamp\" or \"nan\"")),
+    };
+
+    let (max_x, max_y) = ((cols - 1) as f64, (rows - 1) as f64);
+    Ok(qx.par_iter().zip(qy.par_iter())
+        .with_min_len(1024)
+        .map(|(&x, &y)| {
+            let inside = (0.0..=max_x).contains(&x) && (0.0..=max_y).contains(&y);
+            if inside {
+                sample(x, y)
+            } else if clamp && !x.is_nan() && !y.is_nan() {
+                sample(x.clamp(0.0, max_x), y.clamp(0.0, max_y))
+            } else {
+                f64::NAN
+            }
+        })
+        .collect())
+}
+
+/// Splits an in-range grid coordinate into its cell index and fractional offset.
+fn split_index(v: f64, n: usize) -> (usize, f64) {
+    let i = (v.floor() as usize).min(n - 1);
+    (i, v - i as f64)
+}
+
+/// Keys cubic convolution weights for samples at offsets -1, 0, 1, 2 from the cell start.
+fn keys_weights(t: f64) -> [f64; 4] {
+    [
+        ((-0.5 * t + 1.0) * t - 0.5) * t,
+        (1.5 * t - 2.5) * t * t + 1.0,
+        ((-1.5 * t + 2.0) * t + 0.5) * t,
+        (0.5 * t - 0.5) * t * t,
+    ]
+}
+
 /// Calculates the numerical Hessian of a scalar field at a given point `x`.
 /// Returns the nxn matrix as a flattened vector.
 #[wasm_bindgen]
@@ -250,8 +605,264 @@ pub fn hessian(f: &js_sys::Function, x: &[f64], h: f64) -> Result<Vec<f64>, JsVa
     Ok(res)
 }
 
+/// Calculates the gradient of a scalar field at a given point `x` using central differences.
+/// `f` is called with the coordinates spread as arguments, as in `hessian`.
+#[wasm_bindgen(js_name = gradientScalar)]
+pub fn gradient_scalar(f: &js_sys::Function, x: &[f64], h: f64) -> Result<Vec<f64>, JsValue> {
+    central_gradient(|p| eval_field(f, p), x, h)
+}
+
+fn central_gradient(mut eval: impl FnMut(&[f64]) -> Result<f64, JsValue>, x: &[f64], h: f64) -> Result<Vec<f64>, JsValue> {
+    let n = x.len();
+    let mut res = vec![0.0; n];
+    let mut xp = x.to_vec();
+    
+    for i in 0..n {
+        xp[i] = x[i] + h;
+        let fp = eval(&xp)?;
+        xp[i] = x[i] - h;
+        let fm = eval(&xp)?;
+        xp[i] = x[i];
+        
+        res[i] = (fp - fm) / (2.0 * h);
+    }
+    
+    Ok(res)
+}
+
+/// Calculates the derivative of a scalar field at `x` along `dir` (normalized to unit length)
+/// using a central difference.
+#[wasm_bindgen(js_name = directionalDerivative)]
+pub fn directional_derivative(f: &js_sys::Function, x: &[f64], dir: &[f64], h: f64) -> Result<f64, JsValue> {
+    central_directional(|p| eval_field(f, p), x, dir, h)
+}
+
+fn central_directional(
+    mut eval: impl FnMut(&[f64]) -> Result<f64, JsValue>,
+    x: &[f64],
+    dir: &[f64],
+    h: f64,
+) -> Result<f64, JsValue> {
+    if x.len() != dir.len() {
+        return Err(JsValue::from_str("Point and direction must have the same length"));
+    }
+    let norm = dir.iter().map(|d| d * d).sum::<f64>().sqrt();
+    if norm < 1e-18 {
+        return Err(JsValue::from_str("Direction must be non-zero"));
+    }
+    
+    let xp: Vec<f64> = x.iter().zip(dir).map(|(&xi, &di)| xi + h * di / norm).collect();
+    let xm: Vec<f64> = x.iter().zip(dir).map(|(&xi, &di)| xi - h * di / norm).collect();
+    
+    Ok((eval(&xp)? - eval(&xm)?) / (2.0 * h))
+}
+
+/// Monte Carlo integration of a scalar field over the box `[lower, upper]` - Parallel
+///
+/// Averages `n_samples` uniform evaluations: $\hat I = V \bar f$ with standard error
+/// $V s_f / \sqrt{n}$, where $V$ is the box volume. Points are drawn in parallel from the
+/// seeded `randomUniform` stream, so results are reproducible per `seed`. `f` is called with
+/// each point as a single `Float64Array`. Returns `[estimate, standard_error]`.
+#[wasm_bindgen(js_name = monteCarloIntegrate)]
+pub fn monte_carlo_integrate(f: &js_sys::Function, lower: &[f64], upper: &[f64], n_samples: usize, seed: u32) -> Result<Vec<f64>, JsValue> {
+    monte_carlo_box(|x| eval_point(f, x), lower, upper, n_samples, seed)
+}
+
+pub(crate) fn monte_carlo_box(
+    mut eval: impl FnMut(&[f64]) -> Result<f64, JsValue>,
+    lower: &[f64],
+    upper: &[f64],
+    n_samples: usize,
+    seed: u32,
+) -> Result<Vec<f64>, JsValue> {
+    const BLOCK: usize = 8192;
+    let d = lower.len();
+    if d == 0 || upper.len() != d {
+        return Err(JsValue::from_str("Bounds must be non-empty and of equal length"));
+    }
+    if lower.iter().zip(upper).any(|(&lo, &hi)| !(lo.is_finite() && hi.is_finite() && lo < hi)) {
+        return Err(JsValue::from_str("Each bound must be finite with lower < upper"));
+    }
+    if n_samples < 2 {
+        return Err(JsValue::from_str("n_samples must be at least 2"));
+    }
+
+    let volume: f64 = lower.iter().zip(upper).map(|(lo, hi)| hi - lo).product();
+    let mut unit = vec![0.0; BLOCK.min(n_samples) * d];
+    let mut point = vec![0.0; d];
+    // Running count, mean and sum of squared deviations, merged block by block
+    let (mut count, mut mean, mut m2) = (0.0, 0.0, 0.0);
+
+    for start in (0..n_samples).step_by(BLOCK) {
+        let len = BLOCK.min(n_samples - start);
+        let coords = &mut unit[..len * d];
+        crate::utils::rng::fill_uniform(coords, 0.0, 1.0, seed, start * d);
+
+        let mut values = Vec::with_capacity(len);
+        for u in coords.chunks_exact(d) {
+            for k in 0..d {
+                point[k] = lower[k] + u[k] * (upper[k] - lower[k]);
+            }
+            values.push(eval(&point)?);
+        }
+
+        let b_count = len as f64;
+        let b_mean = values.iter().sum::<f64>() / b_count;
+        let b_m2: f64 = values.iter().map(|v| (v - b_mean).powi(2)).sum();
+        let delta = b_mean - mean;
+        let total = count + b_count;
+        mean += delta * b_count / total;
+        m2 += b_m2 + delta * delta * count * b_count / total;
+        count = total;
+    }
+
+    let variance = m2 / (count - 1.0);
+    Ok(vec![volume * mean, volume * (variance / count).sqrt()])
+}
+
+fn eval_field(f: &js_sys::Function, x: &[f64]) -> Result<f64, JsValue> {
+    f.apply(&JsValue::NULL, &to_array(x))?
+        .as_f64().ok_or_else(|| JsValue::from_str("Function must return a number"))
+}
+
+fn eval_point(f: &js_sys::Function, x: &[f64]) -> Result<f64, JsValue> {
+    f.call1(&JsValue::NULL, &js_sys::Float64Array::from(x))?
+        .as_f64().ok_or_else(|| JsValue::from_str("Function must return a number"))
+}
+
 fn to_array(v: &[f64]) -> js_sys::Array {
     let arr = js_sys::Array::new();
     for &val in v { arr.push(&JsValue::from_f64(val)); }
     arr
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trapezoid_and_romberg() {
+        // Trapezoid is exact for linear data: integral of 2x + 1 over [0, 4] is 20
+        let line: Vec<f64> = (0..=8).map(|i| 2.0 * (i as f64 * 0.5) + 1.0).collect();
+        assert!((numerical_integrate_trapezoid(&line, 0.5) - 20.0).abs() < 1e-12);
+        assert_eq!(integrate_trapezoid(&[1.0], 0.5), 0.0);
+
+        let pi = romberg(|x| Ok(4.0 / (1.0 + x * x)), 0.0, 1.0, 20, 1e-12).unwrap();
+        assert!((pi - std::f64::consts::PI).abs() < 1e-10);
+        // A cubic is integrated exactly after the first extrapolation
+        let cubic = romberg(|x| Ok(x * x * x), 0.0, 2.0, 3, 0.0).unwrap();
+        assert!((cubic - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gradient_nonuniform_quadratic() {
+        // The three-point stencil is exact for quadratics on any spacing
+        let x = [0.0, 0.1, 0.4, 0.5, 1.2, 2.0];
+        let y: Vec<f64> = x.iter().map(|v| v * v).collect();
+        let g = gradient_nonuniform(&y, &x).unwrap();
+        for i in 1..x.len() - 1 {
+            assert!((g[i] - 2.0 * x[i]).abs() < 1e-12);
+        }
+        // One-sided slopes at the ends
+        assert!((g[0] - 0.1).abs() < 1e-12 && (g[5] - 3.2).abs() < 1e-12);
+
+        // A repeated or decreasing abscissa would give a zero or negative spacing
+        assert!(three_point_gradient(&[1.0, 2.0, 3.0], &[0.0, 1.0, 1.0]).is_err());
+        assert!(three_point_gradient(&[1.0, 2.0, 3.0], &[0.0, 2.0, 1.0]).is_err());
+        assert!(three_point_gradient(&[1.0, 2.0], &[0.0, f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn test_gradient_and_directional_derivative() {
+        // f(x, y) = x^2 y + 3y has gradient (2xy, x^2 + 3); it is at most quadratic along each axis,
+        // so the axis-aligned central differences are exact
+        let f = |p: &[f64]| Ok(p[0] * p[0] * p[1] + 3.0 * p[1]);
+        let g = central_gradient(f, &[1.0, 2.0], 1e-3).unwrap();
+        assert!((g[0] - 4.0).abs() < 1e-8 && (g[1] - 4.0).abs() < 1e-8);
+
+        // Direction (3, 4) is normalized to (0.6, 0.8): 0.6 * 4 + 0.8 * 4, up to O(h^2)
+        let d = central_directional(f, &[1.0, 2.0], &[3.0, 4.0], 1e-4).unwrap();
+        assert!((d - 5.6).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_laplacians_and_divergence() {
+        // x^2 sampled at h = 0.5: second difference 2 inside, mirrored ghosts at the edges
+        let y: Vec<f64> = (0..5).map(|i| (i as f64 * 0.5).powi(2)).collect();
+        assert_eq!(laplacian_1d(&y, 0.5), vec![2.0, 2.0, 2.0, 2.0, -14.0]);
+
+        // r^2 + c^2 on a 4x5 grid: 4 inside; the mirrored last row loses the r^2 curvature
+        let (rows, cols) = (4usize, 5usize);
+        let grid: Vec<f64> = (0..rows * cols).map(|k| ((k / cols).pow(2) + (k % cols).pow(2)) as f64).collect();
+        let lap = laplacian_2d(&grid, rows, cols, 1.0).unwrap();
+        assert_eq!(lap[cols + 2], 4.0);
+        assert_eq!(lap[3 * cols + 2], -8.0);
+
+        // F = (x, 2y) has divergence 3 everywhere, including the one-sided edges
+        let h = 0.25;
+        let fx: Vec<f64> = (0..rows * cols).map(|k| (k % cols) as f64 * h).collect();
+        let fy: Vec<f64> = (0..rows * cols).map(|k| 2.0 * (k / cols) as f64 * h).collect();
+        assert!(divergence_2d(&fx, &fy, rows, cols, h).unwrap().iter().all(|d| (d - 3.0).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_monte_carlo_box() {
+        // Integral of x * y * z over [0, 1] x [0, 2] x [0, 1] is 0.5
+        let res = monte_carlo_box(|p| Ok(p[0] * p[1] * p[2]), &[0.0, 0.0, 0.0], &[1.0, 2.0, 1.0], 20_000, 3).unwrap();
+        assert!((res[0] - 0.5).abs() < 4.0 * res[1]);
+        assert!(res[1] > 0.0 && res[1] < 0.01);
+        assert_eq!(res, monte_carlo_box(|p| Ok(p[0] * p[1] * p[2]), &[0.0, 0.0, 0.0], &[1.0, 2.0, 1.0], 20_000, 3).unwrap());
+
+        // A constant integrand is exact with zero error
+        let c = monte_carlo_box(|_| Ok(3.0), &[-1.0, 0.0], &[1.0, 0.5], 100, 0).unwrap();
+        assert!((c[0] - 3.0).abs() < 1e-12 && c[1].abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_idw_interpolate() {
+        let points = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+        let values = [0.0, 1.0, 2.0, 3.0];
+        let out = idw_interpolate(&points, &values, 4, &[0.5, 0.5, 1.0, 0.0, 0.9, 0.0], 3, 2.0).unwrap();
+        // The centre is equidistant from all corners
+        assert!((out[0] - 1.5).abs() < 1e-12);
+        assert_eq!(out[1], 1.0);
+        assert!(out[2] > 0.5 && out[2] < 1.5);
+    }
+
+    #[test]
+    fn test_grid_interpolation() {
+        // f = 2x + 3y on a 4 × 5 grid; both schemes reproduce planes away from the border
+        let (rows, cols) = (4usize, 5usize);
+        let grid: Vec<f64> = (0..rows * cols).map(|k| 2.0 * (k % cols) as f64 + 3.0 * (k / cols) as f64).collect();
+        let qx = [1.5, 2.25, 3.0, -1.0];
+        let qy = [1.5, 1.75, 2.0, 0.0];
+
+        let bl = interp2d_bilinear(&grid, rows, cols, &qx, &qy, "clamp").unwrap();
+        let bc = interp2d_bicubic(&grid, rows, cols, &qx, &qy, "clamp").unwrap();
+        for k in 0..3 {
+            let exact = 2.0 * qx[k] + 3.0 * qy[k];
+            assert!((bl[k] - exact).abs() < 1e-12);
+            assert!((bc[k] - exact).abs() < 1e-12);
+        }
+        assert_eq!(bl[3], 0.0);
+        assert_eq!(bc[3], 0.0);
+
+        let nan = interp2d_bilinear(&grid, rows, cols, &qx, &qy, "nan").unwrap();
+        assert!(nan[3].is_nan() && !nan[0].is_nan());
+    }
+
+    #[test]
+    fn test_barycentric_reproduces_polynomial() {
+        // Cubic through 4 Chebyshev-like nodes is recovered exactly
+        let f = |t: f64| 2.0 * t.powi(3) - t + 0.5;
+        let x = [-0.9, -0.3, 0.4, 1.0];
+        let y: Vec<f64> = x.iter().map(|&t| f(t)).collect();
+        let xi = [-1.0, 0.0, 0.4, 0.77];
+        let out = interpolate_barycentric(&x, &y, &xi).unwrap();
+        for (o, &t) in out.iter().zip(&xi) {
+            assert!((o - f(t)).abs() < 1e-12);
+        }
+        assert_eq!(out[2], y[2]);
+        assert!(barycentric_weights(&[0.0, 1.0, 0.0]).is_none());
+    }
+}
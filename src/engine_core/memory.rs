@@ -1,10 +1,33 @@
 use std::collections::HashMap;
 
+use serde::Serialize;
+
+use crate::utils::{track_engine_alloc, track_engine_free};
+
+const F64_BYTES: usize = std::mem::size_of::<f64>();
+const F32_BYTES: usize = std::mem::size_of::<f32>();
+
 pub struct EngineState {
     pub vectors: HashMap<u32, Vec<f64>>,
     pub vectors_f32: HashMap<u32, Vec<f32>>,
     pub columns: HashMap<String, u32>,
     pub next_id: u32,
+    /// When set, freed vectors are kept as reusable slabs instead of being returned to the allocator.
+    pub pooled: bool,
+    free_f64: Vec<Vec<f64>>,
+    free_f32: Vec<Vec<f32>>,
+}
+
+/// Byte counts reported by `EngineState::memory_stats`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStats {
+    /// Capacity held by live vectors plus pooled free slabs.
+    pub allocated_bytes: usize,
+    /// Bytes actually addressed by live vectors.
+    pub used_bytes: usize,
+    /// Number of free slabs waiting to be reused.
+    pub free_slabs: usize,
 }
 
 impl EngineState {
@@ -14,23 +37,66 @@ impl EngineState {
             vectors_f32: HashMap::new(),
             columns: HashMap::new(),
             next_id: 0,
+            pooled: false,
+            free_f64: Vec::new(),
+            free_f32: Vec::new(),
+        }
+    }
+
+    /// Enables or disables slab reuse. Disabling releases every pooled slab.
+    ///
+    /// Slabs are individual buffers rather than offsets into one contiguous arena so that
+    /// pointers handed to JS via `get_ptr` stay valid while other vectors are created.
+    pub fn set_pooling(&mut self, enabled: bool) {
+        self.pooled = enabled;
+        if !enabled {
+            track_engine_free(self.pooled_bytes());
+            self.free_f64 = Vec::new();
+            self.free_f32 = Vec::new();
         }
     }
 
     pub fn create_vector(&mut self, size: usize) -> u32 {
         let id = self.next_id;
-        self.vectors.insert(id, vec![0.0; size]);
+        let v = match take_slab(&mut self.free_f64, size) {
+            Some(mut slab) => { slab.clear(); slab.resize(size, 0.0); slab }
+            None => {
+                track_engine_alloc(size * F64_BYTES);
+                vec![0.0; size]
+            }
+        };
+        self.vectors.insert(id, v);
         self.next_id += 1;
         id
     }
 
     pub fn create_vector_f32(&mut self, size: usize) -> u32 {
         let id = self.next_id;
-        self.vectors_f32.insert(id, vec![0.0; size]);
+        let v = match take_slab(&mut self.free_f32, size) {
+            Some(mut slab) => { slab.clear(); slab.resize(size, 0.0); slab }
+            None => {
+                track_engine_alloc(size * F32_BYTES);
+                vec![0.0; size]
+            }
+        };
+        self.vectors_f32.insert(id, v);
         self.next_id += 1;
         id
     }
 
+    /// Stores an externally produced vector under `id`, replacing any previous one. In pooled
+    /// mode the displaced buffer is kept for reuse, otherwise it is released.
+    pub fn insert_vector(&mut self, id: u32, v: Vec<f64>) {
+        track_engine_alloc(v.capacity() * F64_BYTES);
+        if let Some(old) = self.vectors.insert(id, v) {
+            if self.pooled && old.capacity() > 0 {
+                self.free_f64.push(old);
+            } else {
+                track_engine_free(old.capacity() * F64_BYTES);
+            }
+        }
+    }
+
     /// Allocates multiple vectors of the same size in one batch.
     pub fn create_batch(&mut self, count: usize, size: usize) -> Vec<u32> {
         let mut ids = Vec::with_capacity(count);
@@ -39,4 +105,54 @@ impl EngineState {
         }
         ids
     }
+
+    /// Releases a vector (f64 or f32). In pooled mode its buffer is kept for reuse.
+    /// Returns `false` if no vector has this ID.
+    pub fn free_vector(&mut self, id: u32) -> bool {
+        self.columns.retain(|_, &mut v| v != id);
+        if let Some(v) = self.vectors.remove(&id) {
+            if self.pooled { self.free_f64.push(v); } else { track_engine_free(v.capacity() * F64_BYTES); }
+            true
+        } else if let Some(v) = self.vectors_f32.remove(&id) {
+            if self.pooled { self.free_f32.push(v); } else { track_engine_free(v.capacity() * F32_BYTES); }
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn memory_stats(&self) -> MemoryStats {
+        let live_cap = self.vectors.values().map(|v| v.capacity() * F64_BYTES).sum::<usize>()
+            + self.vectors_f32.values().map(|v| v.capacity() * F32_BYTES).sum::<usize>();
+        let used = self.vectors.values().map(|v| v.len() * F64_BYTES).sum::<usize>()
+            + self.vectors_f32.values().map(|v| v.len() * F32_BYTES).sum::<usize>();
+        MemoryStats {
+            allocated_bytes: live_cap + self.pooled_bytes(),
+            used_bytes: used,
+            free_slabs: self.free_f64.len() + self.free_f32.len(),
+        }
+    }
+
+    fn pooled_bytes(&self) -> usize {
+        self.free_f64.iter().map(|v| v.capacity() * F64_BYTES).sum::<usize>()
+            + self.free_f32.iter().map(|v| v.capacity() * F32_BYTES).sum::<usize>()
+    }
+}
+
+impl Drop for EngineState {
+    fn drop(&mut self) {
+        track_engine_free(self.memory_stats().allocated_bytes);
+    }
+}
+
+/// Best fit: the smallest free slab whose capacity covers `size`. An empty vector needs no
+/// buffer, so it never takes one from the pool.
+fn take_slab<T>(free: &mut Vec<Vec<T>>, size: usize) -> Option<Vec<T>> {
+    if size == 0 { return None; }
+    let idx = free.iter()
+        .enumerate()
+        .filter(|(_, s)| s.capacity() >= size)
+        .min_by_key(|(_, s)| s.capacity())
+        .map(|(i, _)| i)?;
+    Some(free.swap_remove(idx))
 }
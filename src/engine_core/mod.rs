@@ -27,6 +27,22 @@ impl SciEngine {
         self.state.create_vector(size)
     }
 
+    /// Enables reuse of freed vector buffers for apps that churn through scratch vectors.
+    pub fn set_pooling(&mut self, enabled: bool) {
+        self.state.set_pooling(enabled);
+    }
+
+    /// Releases a vector; with pooling enabled its buffer is recycled by later `create_vector` calls.
+    pub fn free_vector(&mut self, id: u32) -> bool {
+        self.state.free_vector(id)
+    }
+
+    /// Returns `{ allocatedBytes, usedBytes, freeSlabs }` for the engine's vectors.
+    pub fn memory_stats(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.state.memory_stats())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
     pub fn get_ptr(&self, id: u32) -> Result<*const f64, JsValue> {
         self.state.vectors.get(&id)
             .map(|v| v.as_ptr())
@@ -57,7 +73,7 @@ impl SciEngine {
         let val = import::run_import_csv(data, delimiter, skip);
         if val.is_empty() { return vec![]; }
         let id = self.state.create_vector(0); // Reserve ID
-        self.state.vectors.insert(id, val);
+        self.state.insert_vector(id, val);
         vec![id]
     }
 
@@ -206,7 +222,15 @@ impl SciEngine {
         crate::linalg::det_lu(v, n)
     }
 
-    pub fn deconvolve_rl(&mut self, id_in: u32, id_kernel: u32, iterations: u32, id_out: u32) -> Result<(), JsValue> {
+    pub fn deconvolve_rl(
+        &mut self,
+        id_in: u32,
+        id_kernel: u32,
+        iterations: u32,
+        id_out: u32,
+        progress: Option<js_sys::Function>,
+        abort: Option<crate::utils::AbortFlag>
+    ) -> Result<(), JsValue> {
         let n = self.state.vectors.get(&id_in).ok_or("Input vector not found")?.len();
         let _k_len = self.state.vectors.get(&id_kernel).ok_or("Kernel vector not found")?.len();
         
@@ -216,21 +240,43 @@ impl SciEngine {
         let out_ptr = self.state.vectors.get_mut(&id_out).ok_or("Output vector not found")?.as_mut_ptr();
         let out_slice = unsafe { std::slice::from_raw_parts_mut(out_ptr, n) };
         
-        crate::analysis::deconvolve::deconvolve_rl(&in_vec, &kernel_vec, iterations, out_slice);
-        Ok(())
+        let control = crate::utils::iteration_control(progress.as_ref(), abort.as_ref(), iterations as usize);
+        crate::analysis::deconvolve::deconvolve_rl_with_progress(&in_vec, &kernel_vec, iterations, out_slice, control)
+    }
+
+    /// Regularized Richardson-Lucy into `id_out`; returns the number of iterations performed.
+    pub fn deconvolve_rl_reg(
+        &mut self,
+        id_in: u32,
+        id_kernel: u32,
+        max_iters: u32,
+        tol: f64,
+        reg_weight: f64,
+        id_out: u32
+    ) -> Result<u32, JsValue> {
+        if !(0.0..0.5).contains(&reg_weight) {
+            return Err(JsValue::from_str("reg_weight must be in [0, 0.5)"));
+        }
+        let in_vec = self.state.vectors.get(&id_in).ok_or("Input vector not found")?.clone();
+        let kernel_vec = self.state.vectors.get(&id_kernel).ok_or("Kernel vector not found")?.clone();
+        let out = self.state.vectors.get_mut(&id_out).ok_or("Output vector not found")?;
+        if out.len() != in_vec.len() {
+            return Err(JsValue::from_str("Output vector length must match input"));
+        }
+        Ok(crate::analysis::deconvolve::deconvolve_rl_reg(&in_vec, &kernel_vec, max_iters, tol, reg_weight, out))
     }
 
     pub fn decimate(&mut self, id_in: u32, factor: usize, id_out: u32) -> Result<(), JsValue> {
         let in_vec = self.state.vectors.get(&id_in).ok_or("Input vector not found")?.to_vec();
         let res = crate::analysis::decimate(&in_vec, factor);
-        self.state.vectors.insert(id_out, res);
+        self.state.insert_vector(id_out, res);
         Ok(())
     }
 
     pub fn resample_linear(&mut self, id_in: u32, new_len: usize, id_out: u32) -> Result<(), JsValue> {
         let in_vec = self.state.vectors.get(&id_in).ok_or("Input vector not found")?.to_vec();
         let res = crate::signal::resample(&in_vec, new_len);
-        self.state.vectors.insert(id_out, res);
+        self.state.insert_vector(id_out, res);
         Ok(())
     }
 
@@ -240,9 +286,12 @@ impl SciEngine {
         bounds: Vec<f64>,
         pop_size: usize,
         generations: usize,
-        mutation_rate: f64
+        mutation_rate: f64,
+        seed: u32,
+        progress: Option<js_sys::Function>,
+        abort: Option<crate::utils::AbortFlag>
     ) -> Result<Vec<f64>, JsValue> {
-        crate::optimization::genetic_algorithm(f, &bounds, pop_size, generations, mutation_rate)
+        crate::optimization::genetic_algorithm(f, &bounds, pop_size, generations, mutation_rate, seed, progress, abort)
     }
 
     pub fn butterworth_lp(&mut self, id_in: u32, id_out: u32, cutoff: f64, fs: f64) -> Result<(), JsValue> {
@@ -4,18 +4,60 @@ use rayon::prelude::*;
 #[wasm_bindgen]
 pub struct DataBuffer {
     data: Vec<f64>,
+    scratch: Vec<f64>,
 }
 
 #[wasm_bindgen]
 impl DataBuffer {
     #[wasm_bindgen(constructor)]
     pub fn new(size: usize) -> Self {
-        Self { data: vec![0.0; size] }
+        Self { data: vec![0.0; size], scratch: Vec::new() }
     }
 
     pub fn ptr(&self) -> *const f64 { self.data.as_ptr() }
     pub fn mut_ptr(&mut self) -> *mut f64 { self.data.as_mut_ptr() }
     pub fn len(&self) -> usize { self.data.len() }
+
+    /// Savitzky-Golay smoothing in place - Parallel
+    ///
+    /// Filters into the internal scratch buffer and copies back, so `ptr()` stays valid.
+    pub fn smooth_sg(&mut self, window: usize, degree: usize) -> Result<(), JsValue> {
+        if window % 2 == 0 || window < 3 {
+            return Err(JsValue::from_str("Window must be odd and at least 3"));
+        }
+        if window <= degree {
+            return Err(JsValue::from_str("Window must be larger than the polynomial degree"));
+        }
+        if window > self.data.len() {
+            return Err(JsValue::from_str("Window must not exceed the buffer length"));
+        }
+        self.scratch.resize(self.data.len(), 0.0);
+        crate::analysis::smooth_savitzky_golay(&self.data, window, degree, &mut self.scratch);
+        self.data.copy_from_slice(&self.scratch);
+        Ok(())
+    }
+
+    /// 2nd order Butterworth low-pass filter in place - Parallel (Chunked with Warmup)
+    pub fn butterworth_lp(&mut self, cutoff: f64, fs: f64) -> Result<(), JsValue> {
+        if !(fs > 0.0 && cutoff > 0.0 && cutoff < 0.5 * fs) {
+            return Err(JsValue::from_str("Cutoff must lie between 0 and fs / 2"));
+        }
+        self.scratch.resize(self.data.len(), 0.0);
+        crate::analysis::butterworth_lowpass(&self.data, &mut self.scratch, cutoff, fs);
+        self.data.copy_from_slice(&self.scratch);
+        Ok(())
+    }
+
+    /// Scales the buffer to unit Euclidean norm in place, as `normalize` - Parallel
+    ///
+    /// A zero buffer is left unchanged.
+    pub fn normalize(&mut self) {
+        let mag = crate::linalg::l2_norm(&self.data);
+        if mag < 1e-18 { return; }
+        self.data.par_iter_mut()
+            .with_min_len(8192)
+            .for_each(|x| *x /= mag);
+    }
 }
 
 /// MANDELBROT ZERO-COPY - Parallel
@@ -89,3 +131,27 @@ pub fn fast_matmul_ptr(
             }
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_buffer_in_place_filters() {
+        let signal: Vec<f64> = (0..4096).map(|i| (i as f64 * 0.01).sin() + if i % 2 == 0 { 0.1 } else { -0.1 }).collect();
+        let mut buf = DataBuffer::new(signal.len());
+        let ptr = buf.ptr();
+
+        buf.data.copy_from_slice(&signal);
+        buf.smooth_sg(7, 2).unwrap();
+        assert_eq!(buf.data, crate::analysis::smooth_sg_wasm(&signal, 7, 2));
+
+        buf.data.copy_from_slice(&signal);
+        buf.butterworth_lp(50.0, 1000.0).unwrap();
+        assert_eq!(buf.data, crate::analysis::butterworth_filter_wasm(&signal, 50.0, 1000.0));
+
+        buf.normalize();
+        assert_eq!(buf.data, crate::linalg::normalize(&crate::analysis::butterworth_filter_wasm(&signal, 50.0, 1000.0)));
+        assert_eq!(buf.ptr(), ptr);
+    }
+}
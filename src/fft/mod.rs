@@ -1,8 +1,66 @@
 use rayon::prelude::*;
 use std::f64::consts::PI;
 
+/// Sample type of the radix-2 kernel: `f64`, or `f32` for half the memory.
+pub(crate) trait FftFloat:
+    Copy + Send + Sync
+    + std::ops::Add<Output = Self> + std::ops::Sub<Output = Self> + std::ops::Mul<Output = Self>
+    + std::ops::AddAssign + std::ops::MulAssign
+{
+    fn from_f64(v: f64) -> Self;
+
+    /// First stage (`step == 1`): plain sum/difference of adjacent pairs.
+    fn first_stage(re: &mut [Self], im: &mut [Self]) {
+        for (r, i) in re.chunks_exact_mut(2).zip(im.chunks_exact_mut(2)) {
+            let (tr, ti) = (r[1], i[1]);
+            r[1] = r[0] - tr;
+            i[1] = i[0] - ti;
+            r[0] += tr;
+            i[0] += ti;
+        }
+    }
+}
+
+impl FftFloat for f32 {
+    fn from_f64(v: f64) -> Self { v as f32 }
+}
+
+impl FftFloat for f64 {
+    fn from_f64(v: f64) -> Self { v }
+
+    // SIMD for step == 1 (The most frequent butterflies)
+    #[cfg(target_feature = "simd128")]
+    fn first_stage(re: &mut [f64], im: &mut [f64]) {
+        use core::arch::wasm32::*;
+        for (r, i) in re.chunks_exact_mut(2).zip(im.chunks_exact_mut(2)) {
+            let vj = f64x2(r[0], i[0]);
+            let vk = f64x2(r[1], i[1]);
+            let v_add = f64x2_add(vj, vk);
+            let v_sub = f64x2_sub(vj, vk);
+            r[0] = f64x2_extract_lane::<0>(v_add);
+            i[0] = f64x2_extract_lane::<1>(v_add);
+            r[1] = f64x2_extract_lane::<0>(v_sub);
+            i[1] = f64x2_extract_lane::<1>(v_sub);
+        }
+    }
+}
+
 /// Cooley-Tukey FFT (Radix-2) In-Place - Parallel
 pub fn fft_radix2(re: &mut [f64], im: &mut [f64], inverse: bool) {
+    radix2(re, im, inverse);
+}
+
+/// Single-precision counterpart of `fft_radix2` (same sign convention and `1/n` inverse
+/// scaling) - Parallel
+///
+/// Twiddles are generated in `f64` and rounded per butterfly, so the error is dominated by
+/// `f32` storage: about `1e-7 * log2(n)` relative to the spectrum's peak magnitude.
+pub fn fft_radix2_f32(re: &mut [f32], im: &mut [f32], inverse: bool) {
+    radix2(re, im, inverse);
+}
+
+/// Shared radix-2 kernel behind `fft_radix2` and `fft_radix2_f32` - Parallel over groups
+fn radix2<T: FftFloat>(re: &mut [T], im: &mut [T], inverse: bool) {
     let n = re.len();
     assert_eq!(n, im.len());
     assert!(n.is_power_of_two());
@@ -11,115 +69,46 @@ pub fn fft_radix2(re: &mut [f64], im: &mut [f64], inverse: bool) {
     bit_reverse_copy(re, im, n);
 
     let sign = if inverse { -1.0 } else { 1.0 };
-    
-    // Convert to raw pointers for raw Rayon access in butterflies
-    let p_re = re.as_mut_ptr() as usize;
-    let p_im = im.as_mut_ptr() as usize;
-    
     let mut step = 1;
     while step < n {
         let jump = step << 1;
+        if step == 1 {
+            T::first_stage(re, im);
+            step = jump;
+            continue;
+        }
+
         let delta_angle = sign * PI / step as f64;
-        let wpr = (delta_angle).cos();
-        let wpi = (delta_angle).sin();
-        
-        // SIMD for step == 1 (The most frequent butterflies)
-        #[cfg(target_feature = "simd128")]
-        {
-            if step == 1 {
-                use core::arch::wasm32::*;
-                let n_simd = n / 2;
-                unsafe {
-                    let pr = p_re as *mut f64;
-                    let pi = p_im as *mut f64;
-                    for i in 0..n_simd {
-                        let j = 2 * i;
-                        let k = j + 1;
-                        
-                        let rej = *pr.add(j);
-                        let rek = *pr.add(k);
-                        let imj = *pi.add(j);
-                        let imk = *pi.add(k);
-                        
-                        let vj = f64x2(rej, imj);
-                        let vk = f64x2(rek, imk);
-                        
-                        let v_add = f64x2_add(vj, vk);
-                        let v_sub = f64x2_sub(vj, vk);
-                        
-                        *pr.add(j) = f64x2_extract_lane::<0>(v_add);
-                        *pi.add(j) = f64x2_extract_lane::<1>(v_add);
-                        *pr.add(k) = f64x2_extract_lane::<0>(v_sub);
-                        *pi.add(k) = f64x2_extract_lane::<1>(v_sub);
-                    }
-                }
-                step = jump;
-                continue;
+        let (wpr, wpi) = (delta_angle.cos(), delta_angle.sin());
+        let butterflies = |(gr, gi): (&mut [T], &mut [T])| {
+            let (lo_re, hi_re) = gr.split_at_mut(step);
+            let (lo_im, hi_im) = gi.split_at_mut(step);
+            let (mut wr, mut wi) = (1.0f64, 0.0f64);
+            for i in 0..step {
+                let (w_re, w_im) = (T::from_f64(wr), T::from_f64(wi));
+                let tr = w_re * hi_re[i] - w_im * hi_im[i];
+                let ti = w_re * hi_im[i] + w_im * hi_re[i];
+                hi_re[i] = lo_re[i] - tr;
+                hi_im[i] = lo_im[i] - ti;
+                lo_re[i] += tr;
+                lo_im[i] += ti;
+                let wtemp = wr;
+                wr = wr * wpr - wi * wpi;
+                wi = wi * wpr + wtemp * wpi;
             }
-        }
+        };
 
         // Parallelize over groups if there are enough butterflies to justify overhead
         if n >= 2048 {
-            (0..n).into_par_iter().step_by(jump).for_each(|group_start| unsafe {
-                let pr = p_re as *mut f64;
-                let pi = p_im as *mut f64;
-                let mut wr = 1.0;
-                let mut wi = 0.0;
-                
-                for i in 0..step {
-                    let j = group_start + i;
-                    let k = j + step;
-                    
-                    let rek = *pr.add(k);
-                    let imk = *pi.add(k);
-                    
-                    let tr = wr * rek - wi * imk;
-                    let ti = wr * imk + wi * rek;
-                    
-                    let rej = *pr.add(j);
-                    let imj = *pi.add(j);
-                    
-                    *pr.add(k) = rej - tr;
-                    *pi.add(k) = imj - ti;
-                    *pr.add(j) = rej + tr;
-                    *pi.add(j) = imj + ti;
-                    
-                    let wtemp = wr;
-                    wr = wr * wpr - wi * wpi;
-                    wi = wi * wpr + wtemp * wpi;
-                }
-            });
+            re.par_chunks_mut(jump).zip(im.par_chunks_mut(jump)).for_each(butterflies);
         } else {
-            // Sequential fallback for small stages
-            for group_start in (0..n).step_by(jump) {
-                let mut wr = 1.0;
-                let mut wi = 0.0;
-                unsafe {
-                    let pr = p_re as *mut f64;
-                    let pi = p_im as *mut f64;
-                    for i in 0..step {
-                        let j = group_start + i;
-                        let k = j + step;
-                        let tr = wr * *pr.add(k) - wi * *pi.add(k);
-                        let ti = wr * *pi.add(k) + wi * *pr.add(k);
-                        let rej = *pr.add(j);
-                        let imj = *pi.add(j);
-                        *pr.add(k) = rej - tr;
-                        *pi.add(k) = imj - ti;
-                        *pr.add(j) = rej + tr;
-                        *pi.add(j) = imj + ti;
-                        let wtemp = wr;
-                        wr = wr * wpr - wi * wpi;
-                        wi = wi * wpr + wtemp * wpi;
-                    }
-                }
-            }
+            re.chunks_mut(jump).zip(im.chunks_mut(jump)).for_each(butterflies);
         }
         step = jump;
     }
 
     if inverse {
-        let inv_n = 1.0 / n as f64;
+        let inv_n = T::from_f64(1.0 / n as f64);
         re.par_iter_mut().for_each(|x| *x *= inv_n);
         im.par_iter_mut().for_each(|x| *x *= inv_n);
     }
@@ -129,9 +118,9 @@ pub fn ifft_radix2(re: &mut [f64], im: &mut [f64]) {
     fft_radix2(re, im, true);
 }
 
-fn bit_reverse_copy(re: &mut [f64], im: &mut [f64], n: usize) {
+fn bit_reverse_copy<T>(re: &mut [T], im: &mut [T], n: usize) {
     let mut j = 0;
-    for i in 0..n - 1 {
+    for i in 0..n.saturating_sub(1) {
         if i < j {
             re.swap(i, j);
             im.swap(i, j);
@@ -266,3 +255,375 @@ pub fn fft_complex_wasm(re: Vec<f64>, im: Vec<f64>) -> Result<Vec<f64>, JsValue>
 
     Ok(output)
 }
+
+/// Complex-to-complex FFT in either direction without padding.
+/// `re` and `im` must have the same power-of-two length; returns interleaved `[re, im, ...]`.
+/// The inverse transform is scaled by `1/n`.
+#[wasm_bindgen(js_name = fftC2C)]
+pub fn fft_complex(re: &[f64], im: &[f64], inverse: bool) -> Result<Vec<f64>, JsValue> {
+    let n = re.len();
+    if n != im.len() {
+        return Err(JsValue::from_str("Real and imaginary parts must have the same length"));
+    }
+    if n == 0 || !n.is_power_of_two() {
+        return Err(JsValue::from_str("Input length must be a non-zero power of two"));
+    }
+
+    let mut re_mut = re.to_vec();
+    let mut im_mut = im.to_vec();
+    fft_radix2(&mut re_mut, &mut im_mut, inverse);
+
+    Ok(interleave(&re_mut, &im_mut))
+}
+
+/// Single-precision `fftC2C`: half the memory, about 7 significant digits.
+#[wasm_bindgen(js_name = fftC2CF32)]
+pub fn fft_complex_f32(re: &[f32], im: &[f32], inverse: bool) -> Result<Vec<f32>, JsValue> {
+    let n = re.len();
+    if n != im.len() {
+        return Err(JsValue::from_str("Real and imaginary parts must have the same length"));
+    }
+    if n == 0 || !n.is_power_of_two() {
+        return Err(JsValue::from_str("Input length must be a non-zero power of two"));
+    }
+
+    let mut re_mut = re.to_vec();
+    let mut im_mut = im.to_vec();
+    fft_radix2_f32(&mut re_mut, &mut im_mut, inverse);
+
+    Ok(interleave(&re_mut, &im_mut))
+}
+
+/// Discrete Cosine Transform, type II (orthonormal) - via an N-point FFT (Makhoul)
+///
+/// $$ X_k = w_k \sum_{n=0}^{N-1} x_n \cos\left(\frac{\pi k (2n+1)}{2N}\right), \quad w_0 = \sqrt{1/N}, \; w_k = \sqrt{2/N} $$
+///
+/// Matches SciPy's `dct(x, type=2, norm="ortho")`. Power-of-two lengths use the FFT; other
+/// lengths fall back to direct $O(N^2)$ summation.
+#[wasm_bindgen]
+pub fn dct2(data: &[f64]) -> Result<Vec<f64>, JsValue> {
+    let n = data.len();
+    if n == 0 {
+        return Err(JsValue::from_str("Input must not be empty"));
+    }
+    if !n.is_power_of_two() {
+        return Ok(dct_direct(data, false));
+    }
+
+    // Even samples ascending, odd samples descending
+    let mut re = vec![0.0; n];
+    for k in 0..n / 2 {
+        re[k] = data[2 * k];
+        re[n - 1 - k] = data[2 * k + 1];
+    }
+    if n == 1 { re[0] = data[0]; }
+    let mut im = vec![0.0; n];
+    fft_radix2(&mut re, &mut im, false);
+
+    // `fft_radix2` uses the e^{+i} kernel, so the standard spectrum is (re, -im)
+    let scale0 = (1.0 / n as f64).sqrt();
+    let scale = (2.0 / n as f64).sqrt();
+    Ok((0..n).map(|k| {
+        let theta = PI * k as f64 / (2.0 * n as f64);
+        let x = re[k] * theta.cos() - im[k] * theta.sin();
+        x * if k == 0 { scale0 } else { scale }
+    }).collect())
+}
+
+/// Discrete Cosine Transform, type III (orthonormal) - the inverse of `dct2`.
+///
+/// $$ x_n = \sum_{k=0}^{N-1} w_k X_k \cos\left(\frac{\pi k (2n+1)}{2N}\right) $$
+///
+/// Matches SciPy's `idct(X, type=2, norm="ortho")`. Power-of-two lengths use the FFT; other
+/// lengths fall back to direct $O(N^2)$ summation.
+#[wasm_bindgen]
+pub fn idct3(data: &[f64]) -> Result<Vec<f64>, JsValue> {
+    let n = data.len();
+    if n == 0 {
+        return Err(JsValue::from_str("Input must not be empty"));
+    }
+    if !n.is_power_of_two() {
+        return Ok(dct_direct(data, true));
+    }
+
+    // Undo orthonormal scaling, then rebuild V_k = e^{i theta_k} (X_k - i X_{N-k})
+    let scale0 = (n as f64).sqrt();
+    let scale = (n as f64 / 2.0).sqrt();
+    let coef = |k: usize| if k == 0 { data[0] * scale0 } else { data[k] * scale };
+
+    let mut re = vec![0.0; n];
+    let mut im = vec![0.0; n];
+    for k in 0..n {
+        let a = coef(k);
+        let b = if k == 0 { 0.0 } else { -coef(n - k) };
+        let theta = PI * k as f64 / (2.0 * n as f64);
+        let (c, s) = (theta.cos(), theta.sin());
+        re[k] = a * c - b * s;
+        im[k] = a * s + b * c;
+    }
+
+    // With the e^{+i} kernel the forward pass is N times the standard inverse
+    fft_radix2(&mut re, &mut im, false);
+    let inv_n = 1.0 / n as f64;
+
+    let mut out = vec![0.0; n];
+    for k in 0..n / 2 {
+        out[2 * k] = re[k] * inv_n;
+        out[2 * k + 1] = re[n - 1 - k] * inv_n;
+    }
+    if n == 1 { out[0] = re[0]; }
+    Ok(out)
+}
+
+/// Orthonormal DCT-II (or its inverse, DCT-III) by direct summation - Parallel
+fn dct_direct(data: &[f64], inverse: bool) -> Vec<f64> {
+    let n = data.len();
+    let weight = |k: usize| if k == 0 { (1.0 / n as f64).sqrt() } else { (2.0 / n as f64).sqrt() };
+    let basis = |k: usize, i: usize| (PI * k as f64 * (2 * i + 1) as f64 / (2 * n) as f64).cos();
+    (0..n).into_par_iter()
+        .map(|j| {
+            if inverse {
+                data.iter().enumerate().map(|(k, &v)| weight(k) * v * basis(k, j)).sum()
+            } else {
+                weight(j) * data.iter().enumerate().map(|(i, &v)| v * basis(j, i)).sum::<f64>()
+            }
+        })
+        .collect()
+}
+
+fn interleave<T: FftFloat>(re: &[T], im: &[T]) -> Vec<T> {
+    let mut output = Vec::with_capacity(re.len() * 2);
+    for (&r, &i) in re.iter().zip(im) {
+        output.push(r);
+        output.push(i);
+    }
+    output
+}
+
+/// Precomputed radix-2 FFT plan for repeated transforms of a fixed power-of-two size.
+///
+/// Stores the bit-reversal swaps and per-stage twiddle tables once, so each transform is only
+/// the butterfly passes. Uses the same conventions as `fft_radix2` (inverse scaled by `1/n`).
+#[wasm_bindgen]
+pub struct FftPlan {
+    n: usize,
+    swaps: Vec<(u32, u32)>,
+    // Stage with half-size `step` stores its twiddles at `step - 1 .. 2 * step - 1`
+    tw_re: Vec<f64>,
+    tw_im: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl FftPlan {
+    #[wasm_bindgen(constructor)]
+    pub fn new(n: usize) -> Result<FftPlan, JsValue> {
+        if n == 0 || !n.is_power_of_two() {
+            return Err(JsValue::from_str("Plan size must be a non-zero power of two"));
+        }
+
+        let mut swaps = Vec::new();
+        let mut j = 0;
+        for i in 0..n - 1 {
+            if i < j { swaps.push((i as u32, j as u32)); }
+            let mut k = n / 2;
+            while k <= j {
+                j -= k;
+                k /= 2;
+            }
+            j += k;
+        }
+
+        let mut tw_re = Vec::with_capacity(n);
+        let mut tw_im = Vec::with_capacity(n);
+        let mut step = 1;
+        while step < n {
+            for i in 0..step {
+                let angle = PI * i as f64 / step as f64;
+                tw_re.push(angle.cos());
+                tw_im.push(angle.sin());
+            }
+            step <<= 1;
+        }
+
+        Ok(FftPlan { n, swaps, tw_re, tw_im })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// Forward transform in place.
+    pub fn forward(&self, re: &mut [f64], im: &mut [f64]) -> Result<(), JsValue> {
+        self.check(re, im)?;
+        self.execute(re, im, false);
+        Ok(())
+    }
+
+    /// Inverse transform in place (scaled by `1/n`).
+    pub fn inverse(&self, re: &mut [f64], im: &mut [f64]) -> Result<(), JsValue> {
+        self.check(re, im)?;
+        self.execute(re, im, true);
+        Ok(())
+    }
+}
+
+impl FftPlan {
+    fn check(&self, re: &[f64], im: &[f64]) -> Result<(), JsValue> {
+        if re.len() != self.n || im.len() != self.n {
+            return Err(JsValue::from_str("Input length does not match plan size"));
+        }
+        Ok(())
+    }
+
+    /// Runs the planned transform; `re` and `im` must have the plan's length.
+    pub fn execute(&self, re: &mut [f64], im: &mut [f64], inverse: bool) {
+        for &(i, j) in &self.swaps {
+            re.swap(i as usize, j as usize);
+            im.swap(i as usize, j as usize);
+        }
+
+        let sign = if inverse { -1.0 } else { 1.0 };
+        let mut step = 1;
+        while step < self.n {
+            let jump = step << 1;
+            let tw_re = &self.tw_re[step - 1..jump - 1];
+            let tw_im = &self.tw_im[step - 1..jump - 1];
+            let butterflies = |(gr, gi): (&mut [f64], &mut [f64])| {
+                let (lo_re, hi_re) = gr.split_at_mut(step);
+                let (lo_im, hi_im) = gi.split_at_mut(step);
+                for i in 0..step {
+                    let wr = tw_re[i];
+                    let wi = sign * tw_im[i];
+                    let tr = wr * hi_re[i] - wi * hi_im[i];
+                    let ti = wr * hi_im[i] + wi * hi_re[i];
+                    hi_re[i] = lo_re[i] - tr;
+                    hi_im[i] = lo_im[i] - ti;
+                    lo_re[i] += tr;
+                    lo_im[i] += ti;
+                }
+            };
+
+            if self.n >= 2048 {
+                re.par_chunks_mut(jump).zip(im.par_chunks_mut(jump)).for_each(butterflies);
+            } else {
+                re.chunks_mut(jump).zip(im.chunks_mut(jump)).for_each(butterflies);
+            }
+            step = jump;
+        }
+
+        if inverse {
+            let inv_n = 1.0 / self.n as f64;
+            re.iter_mut().for_each(|x| *x *= inv_n);
+            im.iter_mut().for_each(|x| *x *= inv_n);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dct2_naive(x: &[f64]) -> Vec<f64> {
+        let n = x.len();
+        (0..n).map(|k| {
+            let sum: f64 = x.iter().enumerate()
+                .map(|(i, &v)| v * (PI * k as f64 * (2 * i + 1) as f64 / (2 * n) as f64).cos())
+                .sum();
+            sum * if k == 0 { (1.0 / n as f64).sqrt() } else { (2.0 / n as f64).sqrt() }
+        }).collect()
+    }
+
+    #[test]
+    fn test_dct2_matches_direct_sum() {
+        let x = vec![1.0, -2.0, 3.5, 0.25, 4.0, -1.0, 0.0, 2.0];
+        let fast = dct2(&x).unwrap();
+        for (a, b) in fast.iter().zip(dct2_naive(&x)) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_fft_plan_matches_fft_radix2() {
+        for &n in &[1usize, 2, 8, 4096] {
+            let plan = FftPlan::new(n).unwrap();
+            let mut re: Vec<f64> = (0..n).map(|i| ((i * 13) % 7) as f64 - 3.0).collect();
+            let mut im: Vec<f64> = (0..n).map(|i| ((i * 5) % 3) as f64).collect();
+            let (mut re_ref, mut im_ref) = (re.clone(), im.clone());
+
+            plan.execute(&mut re, &mut im, false);
+            fft_radix2(&mut re_ref, &mut im_ref, false);
+            for i in 0..n {
+                assert!((re[i] - re_ref[i]).abs() < 1e-9 && (im[i] - im_ref[i]).abs() < 1e-9);
+            }
+
+            plan.execute(&mut re, &mut im, true);
+            fft_radix2(&mut re_ref, &mut im_ref, true);
+            for i in 0..n {
+                assert!((re[i] - re_ref[i]).abs() < 1e-9 && (im[i] - im_ref[i]).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dct_non_power_of_two_lengths() {
+        let x = vec![0.5, -1.0, 2.0, 3.0, -0.75, 1.5];
+        for (a, b) in dct2(&x).unwrap().iter().zip(dct2_naive(&x)) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        let back = idct3(&dct2(&x).unwrap()).unwrap();
+        for (a, b) in back.iter().zip(&x) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_idct3_inverts_dct2() {
+        let x: Vec<f64> = (0..32).map(|i| ((i * 7) % 11) as f64 - 5.0).collect();
+        let back = idct3(&dct2(&x).unwrap()).unwrap();
+        for (a, b) in back.iter().zip(&x) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert!((idct3(&[2.0]).unwrap()[0] - 2.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_fft_radix2_f32_matches_f64() {
+        let n = 4096;
+        let src: Vec<f64> = (0..n).map(|i| (i as f64 * 0.37).sin() + 0.5 * (i as f64 * 1.3).cos()).collect();
+        let (mut re64, mut im64) = (src.clone(), vec![0.0; n]);
+        let (mut re32, mut im32): (Vec<f32>, Vec<f32>) = (src.iter().map(|&v| v as f32).collect(), vec![0.0; n]);
+        fft_radix2(&mut re64, &mut im64, false);
+        fft_radix2_f32(&mut re32, &mut im32, false);
+
+        let peak = re64.iter().zip(&im64).map(|(r, i)| r.hypot(*i)).fold(0.0, f64::max);
+        for k in 0..n {
+            assert!((re32[k] as f64 - re64[k]).abs() < 1e-5 * peak);
+            assert!((im32[k] as f64 - im64[k]).abs() < 1e-5 * peak);
+        }
+
+        fft_radix2_f32(&mut re32, &mut im32, true);
+        for k in 0..n {
+            assert!((re32[k] as f64 - src[k]).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_fft_complex_known_answer_and_round_trip() {
+        // A shifted impulse gives a pure phase ramp (e^{+i} kernel): [1, i, -1, -i]
+        let out = fft_complex(&[0.0, 1.0, 0.0, 0.0], &[0.0; 4], false).unwrap();
+        let expected = [1.0, 0.0, 0.0, 1.0, -1.0, 0.0, 0.0, -1.0];
+        assert!(out.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-12));
+        let out32 = fft_complex_f32(&[0.0, 1.0, 0.0, 0.0], &[0.0; 4], false).unwrap();
+        assert!(out32.iter().zip(&expected).all(|(a, b)| (*a as f64 - b).abs() < 1e-6));
+
+        let re = [1.0, -2.0, 3.5, 0.25, 4.0, -1.0, 0.0, 2.0];
+        let im = [0.5, 0.0, -1.0, 2.0, 0.0, 1.5, -0.5, 0.0];
+        let spec = fft_complex(&re, &im, false).unwrap();
+        let (s_re, s_im): (Vec<f64>, Vec<f64>) = spec.chunks(2).map(|c| (c[0], c[1])).unzip();
+        let back = fft_complex(&s_re, &s_im, true).unwrap();
+        for (k, c) in back.chunks(2).enumerate() {
+            assert!((c[0] - re[k]).abs() < 1e-12 && (c[1] - im[k]).abs() < 1e-12);
+        }
+    }
+}
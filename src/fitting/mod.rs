@@ -1,4 +1,5 @@
 use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
 
 /// Simple Linear Regression Fit: y = mx + c - Parallel
 pub fn fit_linear(x: &[f64], y: &[f64]) -> (f64, f64, f64) {
@@ -179,79 +180,31 @@ fn multi_gaussian(x: f64, p: &[f64]) -> f64 {
     sum
 }
 
-/// Levenberg-Marquardt for Multi-Gaussian Fitting
+/// Levenberg-Marquardt for Multi-Gaussian Fitting - Parallel residuals
+///
+/// Runs the bounded solver of `levenbergMarquardt` with every sigma kept at or above `1e-12`
+/// (initial sigmas are taken by magnitude), so widths can no longer go negative or collapse.
+/// Returns `initial` unchanged if its length is not a multiple of three.
 pub fn fit_gaussians(x: &[f64], y: &[f64], initial: &[f64]) -> Vec<f64> {
-    let mut p = initial.to_vec();
-    let n_params = p.len();
-    if n_params % 3 != 0 { return p; }
-    
-    let mut lambda = 0.001;
-    
-    for _iter in 0..30 {
-        let (j_t_j_sum, j_t_r_sum, total_error_sum) = x.par_iter().zip(y.par_iter()).with_min_len(4096).fold(
-            || (vec![0.0; n_params * n_params], vec![0.0; n_params], 0.0),
-            |(mut jtj, mut jtr, mut err), (&xi, &yi)| {
-                let mut fi = 0.0;
-                let mut jac = vec![0.0; n_params];
-                
-                for k in (0..n_params).step_by(3) {
-                    let amp = p[k];
-                    let mu = p[k+1];
-                    let sigma = p[k+2];
-                    
-                    if sigma.abs() < 1e-12 { continue; }
-                    
-                    let exp_term = (-(xi - mu).powi(2) / (2.0 * sigma.powi(2))).exp();
-                    fi += amp * exp_term;
-                    
-                    jac[k] = exp_term;
-                    jac[k+1] = amp * exp_term * (xi - mu) / sigma.powi(2);
-                    jac[k+2] = amp * exp_term * (xi - mu).powi(2) / sigma.powi(3);
-                }
-                
-                let ri = yi - fi;
-                err += ri.powi(2);
-
-                for r in 0..n_params {
-                    for c in 0..n_params {
-                        jtj[r * n_params + c] += jac[r] * jac[c];
-                    }
-                    jtr[r] += jac[r] * ri;
-                }
-                (jtj, jtr, err)
-            }
-        ).reduce(
-            || (vec![0.0; n_params * n_params], vec![0.0; n_params], 0.0),
-            |(mut jtj1, mut jtr1, err1), (jtj2, jtr2, err2)| {
-                for i in 0..jtj1.len() { jtj1[i] += jtj2[i]; }
-                for i in 0..jtr1.len() { jtr1[i] += jtr2[i]; }
-                (jtj1, jtr1, err1 + err2)
-            }
-        );
-
-        let mut j_t_j = j_t_j_sum;
-        let mut j_t_r_vec = j_t_r_sum;
-
-        for i in 0..n_params { j_t_j[i * n_params + i] += lambda * j_t_j[i * n_params + i]; }
-
-        if let Some(delta) = solve_linear_system(&mut j_t_j, &mut j_t_r_vec, n_params) {
-            let mut p_new = p.clone();
-            for i in 0..n_params { p_new[i] += delta[i]; }
+    let n_params = initial.len();
+    if n_params % 3 != 0 || x.len() != y.len() { return initial.to_vec(); }
 
-            let new_error = x.par_iter().zip(y.par_iter()).with_min_len(4096).map(|(&xi, &yi)| {
-                (yi - multi_gaussian(xi, &p_new)).powi(2)
-            }).sum::<f64>();
-
-            if new_error < total_error_sum {
-                lambda /= 10.0;
-                p = p_new;
-                if (total_error_sum - new_error).abs() < 1e-7 { break; }
-            } else {
-                lambda *= 10.0;
-            }
-        } else { break; }
+    let mut x0 = initial.to_vec();
+    let mut lower = vec![f64::NEG_INFINITY; n_params];
+    let upper = vec![f64::INFINITY; n_params];
+    for k in (2..n_params).step_by(3) {
+        x0[k] = x0[k].abs();
+        lower[k] = 1e-12;
     }
-    p
+
+    let residual = |p: &[f64]| -> Result<Vec<f64>, JsValue> {
+        Ok(x.par_iter().zip(y.par_iter())
+            .with_min_len(4096)
+            .map(|(&xi, &yi)| yi - multi_gaussian(xi, p))
+            .collect())
+    };
+    crate::optimization::run_levenberg_marquardt(residual, &x0, &lower, &upper, 50)
+        .unwrap_or_else(|_| initial.to_vec())
 }
 
 /// Exponential Fit: y = A * exp(B * x) - Parallel
@@ -289,3 +242,318 @@ pub fn fit_logarithmic(x: &[f64], y: &[f64]) -> Option<[f64; 2]> {
     let (slope_b, intercept_a, _) = fit_linear(&log_x, &valid_y);
     Some([intercept_a, slope_b])
 }
+
+/// Linear quantile regression $y \approx m x + c$ for quantile `tau` in (0, 1) - Parallel
+///
+/// Minimizes the check loss $\sum_i \rho_\tau(y_i - m x_i - c)$ with
+/// $\rho_\tau(r) = r(\tau - [r < 0])$ by iteratively reweighted least squares, starting from
+/// the ordinary fit. Each step solves the weighted normal equations with weights
+/// $w_i = \tau / |r_i|$ above the line and $(1 - \tau) / |r_i|$ below (residuals floored to
+/// avoid division by zero). `tau = 0.5` is median (least absolute deviation) regression.
+/// Returns `[slope, intercept]`.
+#[wasm_bindgen(js_name = fitQuantile)]
+pub fn fit_quantile(x: &[f64], y: &[f64], tau: f64, iters: usize) -> Result<Vec<f64>, JsValue> {
+    if x.len() != y.len() {
+        return Err(JsValue::from_str("X and Y must have the same length"));
+    }
+    if x.len() < 2 {
+        return Err(JsValue::from_str("At least 2 points are required"));
+    }
+    if !(tau > 0.0 && tau < 1.0) {
+        return Err(JsValue::from_str("tau must be in (0, 1)"));
+    }
+
+    let (mut slope, mut intercept, _) = fit_linear(x, y);
+    let scale = y.iter().fold(0.0f64, |m, v| m.max(v.abs())).max(1.0);
+    let floor = 1e-9 * scale;
+    for _ in 0..iters {
+        // Weighted sums for the 2x2 normal equations
+        let (sw, swx, swxx, swy, swxy) = x.par_iter().zip(y.par_iter())
+            .with_min_len(4096)
+            .fold(|| (0.0, 0.0, 0.0, 0.0, 0.0), |acc, (&xi, &yi)| {
+                let r = yi - slope * xi - intercept;
+                let w = if r >= 0.0 { tau } else { 1.0 - tau } / r.abs().max(floor);
+                (acc.0 + w, acc.1 + w * xi, acc.2 + w * xi * xi, acc.3 + w * yi, acc.4 + w * xi * yi)
+            })
+            .reduce(|| (0.0, 0.0, 0.0, 0.0, 0.0), |a, b| {
+                (a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3, a.4 + b.4)
+            });
+
+        let mut a = [swxx, swx, swx, sw];
+        let mut b = [swxy, swy];
+        let Some(sol) = solve_linear_system(&mut a, &mut b, 2) else { break };
+        let change = (sol[0] - slope).abs() + (sol[1] - intercept).abs();
+        slope = sol[0];
+        intercept = sol[1];
+        if change <= 1e-12 * (1.0 + slope.abs() + intercept.abs()) { break; }
+    }
+    Ok(vec![slope, intercept])
+}
+
+/// Piecewise-linear (segmented) regression with `n_segments` lines and optimized breakpoints.
+///
+/// Points are sorted by `x` and split into consecutive segments, each fitted independently with
+/// [`fit_linear`] (segments are not forced to meet). The split positions minimize the total
+/// squared error: Nelder-Mead searches the breakpoints as fractions of the point count, then a
+/// one-point-at-a-time polish settles them on the exact discrete optimum nearby. Every segment
+/// keeps at least 2 points, so `x.len() >= 2 * n_segments` is required.
+///
+/// Returns `[b_1, ..., b_{k-1}, slope_1, intercept_1, ..., slope_k, intercept_k]`, where each
+/// breakpoint is the midpoint between the last `x` of one segment and the first of the next.
+#[wasm_bindgen(js_name = fitPiecewiseLinear)]
+pub fn fit_piecewise_linear(x: &[f64], y: &[f64], n_segments: usize) -> Result<Vec<f64>, JsValue> {
+    if x.len() != y.len() {
+        return Err(JsValue::from_str("X and Y must have the same length"));
+    }
+    if n_segments == 0 || x.len() < 2 * n_segments {
+        return Err(JsValue::from_str("Need at least one segment and 2 points per segment"));
+    }
+
+    let mut pts: Vec<(f64, f64)> = x.iter().copied().zip(y.iter().copied()).collect();
+    pts.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (xs, ys): (Vec<f64>, Vec<f64>) = pts.into_iter().unzip();
+    let n = xs.len();
+    let k = n_segments;
+
+    // Segment boundaries are [0, splits..., n]
+    let segments = |splits: &[usize]| -> Vec<(usize, usize)> {
+        let mut edges = Vec::with_capacity(k + 1);
+        edges.push(0);
+        edges.extend_from_slice(splits);
+        edges.push(n);
+        edges.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let sse = |splits: &[usize]| -> f64 {
+        segments(splits).into_iter().map(|(a, b)| {
+            if b < a + 2 { return f64::INFINITY; }
+            let (m, c, _) = fit_linear(&xs[a..b], &ys[a..b]);
+            xs[a..b].iter().zip(&ys[a..b]).map(|(xi, yi)| (yi - m * xi - c).powi(2)).sum::<f64>()
+        }).sum()
+    };
+    let to_splits = |u: &[f64]| -> Vec<usize> {
+        let mut splits: Vec<usize> = u.iter().map(|&v| (v.clamp(0.0, 1.0) * n as f64).round() as usize).collect();
+        splits.sort_unstable();
+        splits
+    };
+
+    let mut splits = Vec::new();
+    if k > 1 {
+        let u0: Vec<f64> = (1..k).map(|j| j as f64 / k as f64).collect();
+        let u = crate::optimization::run_nelder_mead(|u| Ok(sse(&to_splits(u))), &u0, 0.0, 200 * k, None)?;
+        splits = to_splits(&u);
+        let mut best = sse(&splits);
+        loop {
+            let mut improved = false;
+            for j in 0..splits.len() {
+                for step in [-1isize, 1] {
+                    let mut trial = splits.clone();
+                    trial[j] = trial[j].saturating_add_signed(step).min(n);
+                    let err = sse(&trial);
+                    if err < best {
+                        best = err;
+                        splits = trial;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved { break; }
+        }
+    }
+
+    let mut out: Vec<f64> = splits.iter().map(|&s| 0.5 * (xs[s - 1] + xs[s])).collect();
+    for (a, b) in segments(&splits) {
+        let (m, c, _) = fit_linear(&xs[a..b], &ys[a..b]);
+        out.push(m);
+        out.push(c);
+    }
+    Ok(out)
+}
+
+/// Least-squares slope of `y` on `x` over a trailing window of length `window` - Parallel
+///
+/// Output `i` is the [`fit_linear`] slope of samples `i + 1 - window ..= i`, maintained with
+/// sliding sums so the total work is $O(n)$; the first `window - 1` outputs are NaN.
+/// Windows where `x` is constant yield 0.0.
+#[wasm_bindgen(js_name = rollingSlope)]
+pub fn rolling_slope(x: &[f64], y: &[f64], window: usize) -> Result<Vec<f64>, JsValue> {
+    let n = x.len();
+    if n != y.len() {
+        return Err(JsValue::from_str("X and Y must have the same length"));
+    }
+    if window < 2 {
+        return Err(JsValue::from_str("Window must be at least 2"));
+    }
+
+    let mut result = vec![f64::NAN; n];
+    if n < window { return Ok(result); }
+
+    let chunk_size = 32768;
+    let res_ptr = result.as_mut_ptr() as usize;
+    let w = window as f64;
+
+    (window - 1..n).into_par_iter().step_by(chunk_size).for_each(|start| unsafe {
+        let end = (start + chunk_size).min(n);
+        let out = res_ptr as *mut f64;
+
+        // Warm up on the first window, shifted by its first sample to limit cancellation
+        let first = start + 1 - window;
+        let (sx0, sy0) = (x[first], y[first]);
+        let (mut sx, mut sy, mut sxy, mut sxx) = (0.0, 0.0, 0.0, 0.0);
+        for j in first..start {
+            let (a, b) = (x[j] - sx0, y[j] - sy0);
+            sx += a; sy += b; sxy += a * b; sxx += a * a;
+        }
+
+        for i in start..end {
+            let (a, b) = (x[i] - sx0, y[i] - sy0);
+            sx += a; sy += b; sxy += a * b; sxx += a * a;
+
+            let vx = sxx - sx * sx / w;
+            *out.add(i) = if vx > 0.0 { (sxy - sx * sy / w) / vx } else { 0.0 };
+
+            let old = i + 1 - window;
+            let (a, b) = (x[old] - sx0, y[old] - sy0);
+            sx -= a; sy -= b; sxy -= a * b; sxx -= a * a;
+        }
+    });
+
+    Ok(result)
+}
+
+/// Goodness-of-fit metrics from observed and predicted values - Parallel
+/// Returns `[rmse, r2, reduced_chi2, aic, bic]`.
+pub fn compute_fit_metrics(y: &[f64], y_pred: &[f64], n_params: usize) -> [f64; 5] {
+    let n_pts = y.len().min(y_pred.len());
+    if n_pts == 0 { return [f64::NAN; 5]; }
+    let n = n_pts as f64;
+
+    let (sum_y, rss) = y[..n_pts].par_iter().zip(y_pred[..n_pts].par_iter())
+        .with_min_len(4096)
+        .fold(|| (0.0, 0.0), |acc, (&yi, &pi)| (acc.0 + yi, acc.1 + (yi - pi).powi(2)))
+        .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+
+    let y_mean = sum_y / n;
+    let ss_tot: f64 = y[..n_pts].par_iter()
+        .with_min_len(4096)
+        .map(|&yi| (yi - y_mean).powi(2))
+        .sum();
+
+    let k = n_params as f64;
+    let rmse = (rss / n).sqrt();
+    let r2 = if ss_tot > 0.0 { 1.0 - rss / ss_tot } else { 0.0 };
+    let reduced_chi2 = if n_pts > n_params { rss / (n - k) } else { f64::NAN };
+
+    // Gaussian log-likelihood form; guard against a perfect fit
+    let log_term = n * (rss / n).max(f64::MIN_POSITIVE).ln();
+    let aic = log_term + 2.0 * k;
+    let bic = log_term + k * n.ln();
+
+    [rmse, r2, reduced_chi2, aic, bic]
+}
+
+/// Goodness-of-fit metrics for any fitted model.
+/// `model` is called as `model(x, params)` for every sample, `params` being a `Float64Array`.
+/// Returns `[rmse, r2, reduced_chi2, aic, bic]`.
+#[wasm_bindgen(js_name = fitMetrics)]
+pub fn fit_metrics(model: &js_sys::Function, x: &[f64], y: &[f64], params: &[f64], n_params: usize) -> Result<Vec<f64>, JsValue> {
+    if x.len() != y.len() {
+        return Err(JsValue::from_str("X and Y must have the same length"));
+    }
+    if x.is_empty() {
+        return Err(JsValue::from_str("Input must not be empty"));
+    }
+
+    let params_js = js_sys::Float64Array::from(params);
+    let mut y_pred = Vec::with_capacity(x.len());
+    for &xi in x {
+        let v = model.call2(&JsValue::NULL, &JsValue::from_f64(xi), &params_js)?
+            .as_f64().ok_or_else(|| JsValue::from_str("Model must return a number"))?;
+        y_pred.push(v);
+    }
+
+    Ok(compute_fit_metrics(y, &y_pred, n_params).to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_metrics_perfect_fit() {
+        let y = vec![1.0, 2.0, 3.0, 4.0];
+        let m = compute_fit_metrics(&y, &y, 2);
+        assert!(m[0].abs() < 1e-12);
+        assert!((m[1] - 1.0).abs() < 1e-12);
+        assert!(m[2].abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fit_metrics_values() {
+        let y = vec![1.0, 2.0, 3.0, 4.0];
+        let pred = vec![1.5, 2.0, 3.0, 3.5];
+        let m = compute_fit_metrics(&y, &pred, 2);
+        // rss = 0.5, ss_tot = 5
+        assert!((m[0] - (0.5f64 / 4.0).sqrt()).abs() < 1e-12);
+        assert!((m[1] - 0.9).abs() < 1e-12);
+        assert!((m[2] - 0.25).abs() < 1e-12);
+        let aic = 4.0 * (0.5f64 / 4.0).ln() + 4.0;
+        assert!((m[3] - aic).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fit_quantile_median_ignores_outliers() {
+        let x: Vec<f64> = (0..30).map(|i| i as f64).collect();
+        let mut y: Vec<f64> = x.iter().map(|v| 2.0 * v + 1.0).collect();
+        y[5] += 500.0;
+        y[17] -= 300.0;
+        y[25] += 800.0;
+        let fit = fit_quantile(&x, &y, 0.5, 200).unwrap();
+        assert!((fit[0] - 2.0).abs() < 1e-3 && (fit[1] - 1.0).abs() < 1e-2, "{:?}", fit);
+
+        // Spread of 0..9 around a unit-slope line: upper quantiles sit higher
+        let y: Vec<f64> = x.iter().map(|v| v + ((*v as usize * 7) % 10) as f64).collect();
+        let low = fit_quantile(&x, &y, 0.1, 200).unwrap();
+        let high = fit_quantile(&x, &y, 0.9, 200).unwrap();
+        assert!(high[1] - low[1] > 5.0);
+        // Roughly 90% of the points lie on or below the tau = 0.9 line
+        let below = x.iter().zip(&y).filter(|(&xi, &yi)| yi <= high[0] * xi + high[1] + 1e-6).count();
+        assert!((26..=29).contains(&below), "{}", below);
+    }
+
+    #[test]
+    fn test_fit_piecewise_linear_finds_knee() {
+        // Slope 1 up to x = 15, slope 3 afterwards; shuffled input order
+        let x: Vec<f64> = (0..40).map(|i| ((i * 17) % 40) as f64).collect();
+        let y: Vec<f64> = x.iter().map(|&v| if v <= 15.0 { v } else { 15.0 + 3.0 * (v - 15.0) }).collect();
+        let fit = fit_piecewise_linear(&x, &y, 2).unwrap();
+        assert_eq!(fit.len(), 5);
+        assert!((fit[0] - 15.0).abs() <= 0.5, "{:?}", fit);
+        assert!((fit[1] - 1.0).abs() < 1e-9 && fit[2].abs() < 1e-9);
+        assert!((fit[3] - 3.0).abs() < 1e-9 && (fit[4] + 30.0).abs() < 1e-9);
+
+        let single = fit_piecewise_linear(&x, &y, 1).unwrap();
+        assert_eq!(single.len(), 2);
+    }
+
+    #[test]
+    fn test_rolling_slope_matches_refits() {
+        let x: Vec<f64> = (0..50).map(|i| i as f64 * 0.5).collect();
+        let y: Vec<f64> = x.iter().map(|v| (v * 0.7).sin() * 3.0 + v * v * 0.1).collect();
+        let slopes = rolling_slope(&x, &y, 7).unwrap();
+        assert!(slopes[..6].iter().all(|v| v.is_nan()));
+        for i in 6..50 {
+            let (m, _, _) = fit_linear(&x[i - 6..=i], &y[i - 6..=i]);
+            assert!((slopes[i] - m).abs() < 1e-9, "{} vs {}", slopes[i], m);
+        }
+    }
+
+    #[test]
+    fn test_fit_gaussians_keeps_sigma_positive() {
+        let x: Vec<f64> = (0..200).map(|i| i as f64 * 0.05).collect();
+        let y: Vec<f64> = x.iter().map(|&xi| multi_gaussian(xi, &[2.0, 4.0, 0.7]) + multi_gaussian(xi, &[1.0, 7.0, 0.4])).collect();
+        // Negative initial width is read by magnitude
+        let p = fit_gaussians(&x, &y, &[1.5, 3.8, -1.0, 0.8, 7.2, 0.5]);
+        let expected = [2.0, 4.0, 0.7, 1.0, 7.0, 0.4];
+        assert!(p.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-5), "{:?}", p);
+    }
+}
@@ -0,0 +1,448 @@
+//! Minimal Apache Arrow IPC reader (stream and file formats).
+//!
+//! Only the pieces needed to pull numeric columns out of uncompressed, little-endian
+//! record batches are implemented: the flatbuffer metadata is walked by hand instead of
+//! pulling in the full `arrow` crate. Integer and floating-point columns are widened to
+//! `f64` (nulls become NaN); every other column type is skipped.
+
+use wasm_bindgen::prelude::*;
+use js_sys::{Float64Array, Object, Reflect};
+
+const MAGIC: &[u8] = b"ARROW1";
+
+// Message header union
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+
+// Type union
+const TYPE_NULL: u8 = 1;
+const TYPE_INT: u8 = 2;
+const TYPE_FLOAT: u8 = 3;
+const TYPE_BINARY: u8 = 4;
+const TYPE_UTF8: u8 = 5;
+const TYPE_BOOL: u8 = 6;
+const TYPE_DECIMAL: u8 = 7;
+const TYPE_DATE: u8 = 8;
+const TYPE_TIME: u8 = 9;
+const TYPE_TIMESTAMP: u8 = 10;
+const TYPE_INTERVAL: u8 = 11;
+const TYPE_LIST: u8 = 12;
+const TYPE_STRUCT: u8 = 13;
+const TYPE_UNION: u8 = 14;
+const TYPE_FIXED_SIZE_BINARY: u8 = 15;
+const TYPE_FIXED_SIZE_LIST: u8 = 16;
+const TYPE_MAP: u8 = 17;
+const TYPE_DURATION: u8 = 18;
+const TYPE_LARGE_BINARY: u8 = 19;
+const TYPE_LARGE_UTF8: u8 = 20;
+const TYPE_LARGE_LIST: u8 = 21;
+
+/// Reads an Arrow IPC stream or file and returns `{ [column]: Float64Array }` for every
+/// integer / floating-point column, concatenated across record batches.
+///
+/// Nulls become NaN. Non-numeric columns are skipped; compressed bodies, big-endian data,
+/// dictionary-encoded and union columns are rejected.
+#[wasm_bindgen(js_name = readArrowIPC)]
+pub fn read_arrow_ipc(bytes: &[u8]) -> Result<JsValue, JsValue> {
+    let columns = parse_arrow_ipc(bytes).map_err(|e| JsValue::from_str(&e))?;
+    let out = Object::new();
+    for (name, values) in columns {
+        let arr = Float64Array::new_with_length(values.len() as u32);
+        arr.copy_from(&values);
+        Reflect::set(&out, &JsValue::from_str(&name), &arr)?;
+    }
+    Ok(out.into())
+}
+
+enum Column {
+    Int { bits: u32, signed: bool },
+    Float { precision: u16 },
+    Skip { buffers: usize },
+}
+
+struct FieldLayout {
+    name: String,
+    column: Column,
+    /// Nodes and buffers of nested children, which are consumed but never decoded.
+    child_nodes: usize,
+    child_buffers: usize,
+}
+
+pub(crate) fn parse_arrow_ipc(bytes: &[u8]) -> Result<Vec<(String, Vec<f64>)>, String> {
+    let mut columns = Columns { layouts: None, data: Vec::new() };
+
+    if bytes.len() >= 18 && bytes.starts_with(MAGIC) && bytes.ends_with(MAGIC) {
+        // File format: the footer indexes the schema and every record batch
+        let footer_len = read_i32(bytes, bytes.len() - 10)? as usize;
+        let footer_start = (bytes.len() - 10).checked_sub(footer_len).ok_or("Invalid Arrow footer length")?;
+        let fb = Flatbuffer { buf: &bytes[footer_start..bytes.len() - 10] };
+        let footer = fb.root()?;
+        let schema = fb.field_table(footer, 1)?.ok_or("Arrow footer has no schema")?;
+        columns.set_schema(&fb, schema)?;
+        let (blocks_at, n_blocks) = fb.field_vector(footer, 3)?.unwrap_or((0, 0));
+        for i in 0..n_blocks {
+            let offset = to_len(read_i64(fb.buf, blocks_at + 24 * i)?)?;
+            if let Some(msg) = read_message(bytes, offset)? {
+                msg.apply(&mut columns)?;
+            }
+        }
+    } else {
+        let mut pos = 0;
+        while let Some(msg) = read_message(bytes, pos)? {
+            pos = msg.end;
+            msg.apply(&mut columns)?;
+        }
+    }
+
+    let layouts = columns.layouts.ok_or("No Arrow schema found")?;
+    Ok(layouts.into_iter()
+        .zip(columns.data)
+        .filter(|(l, _)| !matches!(l.column, Column::Skip { .. }))
+        .map(|(l, d)| (l.name, d))
+        .collect())
+}
+
+struct Message<'a> {
+    meta: Flatbuffer<'a>,
+    header_type: u8,
+    header: Option<usize>,
+    body: &'a [u8],
+    end: usize,
+}
+
+/// Reads the encapsulated message at `pos`; `None` at end-of-stream.
+fn read_message(bytes: &[u8], pos: usize) -> Result<Option<Message<'_>>, String> {
+    if bytes.len().saturating_sub(pos) < 4 { return Ok(None); }
+    let mut pos = pos;
+    let mut meta_len = read_i32(bytes, pos)?;
+    pos += 4;
+    if meta_len == -1 {
+        // Continuation marker (format >= 0.15); the real length follows
+        meta_len = read_i32(bytes, pos)?;
+        pos += 4;
+    }
+    if meta_len <= 0 { return Ok(None); }
+    let meta_len = meta_len as usize;
+    let meta_end = pos.checked_add(meta_len).ok_or("Truncated message metadata")?;
+    let meta = Flatbuffer { buf: bytes.get(pos..meta_end).ok_or("Truncated message metadata")? };
+    pos = meta_end;
+
+    let message = meta.root()?;
+    let header_type = meta.field_u8(message, 1)?.unwrap_or(0);
+    let header = meta.field_table(message, 2)?;
+    let body_len = to_len(meta.field_i64(message, 3)?.unwrap_or(0))?;
+    let end = pos.checked_add(body_len).ok_or("Truncated message body")?;
+    let body = bytes.get(pos..end).ok_or("Truncated message body")?;
+    Ok(Some(Message { meta, header_type, header, body, end }))
+}
+
+impl Message<'_> {
+    fn apply(&self, columns: &mut Columns) -> Result<(), String> {
+        match (self.header_type, self.header) {
+            (HEADER_SCHEMA, Some(schema)) => columns.set_schema(&self.meta, schema),
+            (HEADER_RECORD_BATCH, Some(batch)) => columns.append_batch(&self.meta, batch, self.body),
+            // Dictionary batches, tensors and unknown headers carry nothing we decode
+            _ => Ok(()),
+        }
+    }
+}
+
+struct Columns {
+    layouts: Option<Vec<FieldLayout>>,
+    data: Vec<Vec<f64>>,
+}
+
+impl Columns {
+    fn set_schema(&mut self, fb: &Flatbuffer, schema: usize) -> Result<(), String> {
+        if fb.field_i16(schema, 0)?.unwrap_or(0) != 0 {
+            return Err("Big-endian Arrow data is not supported".into());
+        }
+        let layouts = match fb.field_vector(schema, 1)? {
+            Some((start, n)) => (0..n).map(|i| fb.field_layout(fb.indirect(start + 4 * i)?)).collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        self.data = layouts.iter().map(|_| Vec::new()).collect();
+        self.layouts = Some(layouts);
+        Ok(())
+    }
+
+    fn append_batch(&mut self, fb: &Flatbuffer, batch: usize, body: &[u8]) -> Result<(), String> {
+        let layouts = self.layouts.as_ref().ok_or("Record batch before schema")?;
+        if fb.field_table(batch, 3)?.is_some() {
+            return Err("Compressed Arrow record batches are not supported".into());
+        }
+        let (nodes_at, n_nodes) = fb.field_vector(batch, 1)?.unwrap_or((0, 0));
+        let (bufs_at, n_bufs) = fb.field_vector(batch, 2)?.unwrap_or((0, 0));
+        let node = |i: usize| -> Result<(usize, usize), String> {
+            if i >= n_nodes { return Err("Record batch has too few field nodes".into()); }
+            let at = nodes_at + 16 * i;
+            Ok((to_len(read_i64(fb.buf, at)?)?, to_len(read_i64(fb.buf, at + 8)?)?))
+        };
+        let buffer = |i: usize| -> Result<&[u8], String> {
+            if i >= n_bufs { return Err("Record batch has too few buffers".into()); }
+            let at = bufs_at + 16 * i;
+            let (off, len) = (to_len(read_i64(fb.buf, at)?)?, to_len(read_i64(fb.buf, at + 8)?)?);
+            off.checked_add(len)
+                .and_then(|end| body.get(off..end))
+                .ok_or_else(|| "Buffer outside message body".to_string())
+        };
+
+        let (mut ni, mut bi) = (0, 0);
+        for (layout, out) in layouts.iter().zip(self.data.iter_mut()) {
+            let (len, null_count) = node(ni)?;
+            ni += 1;
+            match layout.column {
+                Column::Skip { buffers } => bi += buffers,
+                ref col => {
+                    decode_column(col, len, null_count, buffer(bi)?, buffer(bi + 1)?, out)?;
+                    bi += 2;
+                }
+            }
+            ni += layout.child_nodes;
+            bi += layout.child_buffers;
+        }
+        Ok(())
+    }
+}
+
+fn decode_column(col: &Column, len: usize, null_count: usize, validity: &[u8], values: &[u8], out: &mut Vec<f64>) -> Result<(), String> {
+    let width = match *col {
+        Column::Int { bits, .. } => bits as usize / 8,
+        Column::Float { precision: 0 } => 2,
+        Column::Float { precision: 1 } => 4,
+        Column::Float { .. } => 8,
+        Column::Skip { .. } => return Ok(()),
+    };
+    // `len` comes from the header, so the product can overflow on a crafted file
+    match len.checked_mul(width) {
+        Some(need) if need <= values.len() => {}
+        _ => return Err("Value buffer shorter than column length".into()),
+    }
+
+    out.reserve(len);
+    for i in 0..len {
+        let valid = null_count == 0 || validity.is_empty() || validity.get(i / 8).is_some_and(|b| b & (1 << (i % 8)) != 0);
+        if !valid {
+            out.push(f64::NAN);
+            continue;
+        }
+        let b = &values[i * width..(i + 1) * width];
+        out.push(match *col {
+            Column::Int { signed: true, .. } => match width {
+                1 => b[0] as i8 as f64,
+                2 => i16::from_le_bytes([b[0], b[1]]) as f64,
+                4 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                _ => i64::from_le_bytes(b.try_into().unwrap()) as f64,
+            },
+            Column::Int { signed: false, .. } => match width {
+                1 => b[0] as f64,
+                2 => u16::from_le_bytes([b[0], b[1]]) as f64,
+                4 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                _ => u64::from_le_bytes(b.try_into().unwrap()) as f64,
+            },
+            Column::Float { precision: 0 } => half_to_f64(u16::from_le_bytes([b[0], b[1]])),
+            Column::Float { precision: 1 } => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            _ => f64::from_le_bytes(b.try_into().unwrap()),
+        });
+    }
+    Ok(())
+}
+
+fn half_to_f64(h: u16) -> f64 {
+    let sign = if h & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((h >> 10) & 0x1f) as i32;
+    let frac = (h & 0x3ff) as f64;
+    sign * match exp {
+        0 => frac * 2f64.powi(-24),
+        31 => if frac == 0.0 { f64::INFINITY } else { f64::NAN },
+        _ => (1.0 + frac / 1024.0) * 2f64.powi(exp - 15),
+    }
+}
+
+fn read_i32(buf: &[u8], at: usize) -> Result<i32, String> {
+    buf.get(at..at + 4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]])).ok_or_else(|| "Unexpected end of Arrow data".to_string())
+}
+
+fn read_i64(buf: &[u8], at: usize) -> Result<i64, String> {
+    buf.get(at..at + 8).map(|b| i64::from_le_bytes(b.try_into().unwrap())).ok_or_else(|| "Unexpected end of Arrow data".to_string())
+}
+
+/// Converts a length or offset read from the (untrusted) header, rejecting negative values
+/// and values that do not fit in `usize` (32 bits on wasm32).
+fn to_len(v: i64) -> Result<usize, String> {
+    usize::try_from(v).map_err(|_| format!("Invalid length {} in Arrow header", v))
+}
+
+/// Read-only view of a flatbuffer; tables are identified by their absolute position.
+struct Flatbuffer<'a> {
+    buf: &'a [u8],
+}
+
+impl Flatbuffer<'_> {
+    fn root(&self) -> Result<usize, String> {
+        self.indirect(0)
+    }
+
+    /// Follows a `uoffset_t` stored at `at`.
+    fn indirect(&self, at: usize) -> Result<usize, String> {
+        Ok(at + read_i32(self.buf, at)? as u32 as usize)
+    }
+
+    /// Absolute position of field `id` of the table at `table`, if present.
+    fn field_pos(&self, table: usize, id: usize) -> Result<Option<usize>, String> {
+        let vtable = (table as i64 - read_i32(self.buf, table)? as i64) as usize;
+        let vt_len = self.u16_at(vtable)? as usize;
+        let slot = 4 + 2 * id;
+        if slot + 2 > vt_len { return Ok(None); }
+        let off = self.u16_at(vtable + slot)? as usize;
+        Ok(if off == 0 { None } else { Some(table + off) })
+    }
+
+    fn u16_at(&self, at: usize) -> Result<u16, String> {
+        self.buf.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]])).ok_or_else(|| "Unexpected end of Arrow metadata".to_string())
+    }
+
+    fn field_u8(&self, table: usize, id: usize) -> Result<Option<u8>, String> {
+        self.field_pos(table, id)?
+            .map(|p| self.buf.get(p).copied().ok_or_else(|| "Unexpected end of Arrow metadata".to_string()))
+            .transpose()
+    }
+
+    fn field_i16(&self, table: usize, id: usize) -> Result<Option<i16>, String> {
+        self.field_pos(table, id)?.map(|p| self.u16_at(p).map(|v| v as i16)).transpose()
+    }
+
+    fn field_i32(&self, table: usize, id: usize) -> Result<Option<i32>, String> {
+        self.field_pos(table, id)?.map(|p| read_i32(self.buf, p)).transpose()
+    }
+
+    fn field_i64(&self, table: usize, id: usize) -> Result<Option<i64>, String> {
+        self.field_pos(table, id)?.map(|p| read_i64(self.buf, p)).transpose()
+    }
+
+    fn field_table(&self, table: usize, id: usize) -> Result<Option<usize>, String> {
+        self.field_pos(table, id)?.map(|p| self.indirect(p)).transpose()
+    }
+
+    /// Start of the elements and element count of a vector field.
+    fn field_vector(&self, table: usize, id: usize) -> Result<Option<(usize, usize)>, String> {
+        match self.field_table(table, id)? {
+            Some(v) => Ok(Some((v + 4, read_i32(self.buf, v)? as u32 as usize))),
+            None => Ok(None),
+        }
+    }
+
+    fn field_string(&self, table: usize, id: usize) -> Result<String, String> {
+        match self.field_vector(table, id)? {
+            Some((start, len)) => {
+                let bytes = self.buf.get(start..start + len).ok_or("Unexpected end of Arrow metadata")?;
+                Ok(String::from_utf8_lossy(bytes).into_owned())
+            }
+            None => Ok(String::new()),
+        }
+    }
+
+    fn field_layout(&self, field: usize) -> Result<FieldLayout, String> {
+        let name = self.field_string(field, 0)?;
+        let type_id = self.field_u8(field, 2)?.unwrap_or(0);
+        let type_table = self.field_table(field, 3)?;
+        if self.field_table(field, 4)?.is_some() {
+            return Err(format!("Dictionary-encoded column '{}' is not supported", name));
+        }
+
+        let column = match (type_id, type_table) {
+            (TYPE_INT, Some(t)) => {
+                let bits = self.field_i32(t, 0)?.unwrap_or(0) as u32;
+                if ![8, 16, 32, 64].contains(&bits) {
+                    return Err(format!("Unsupported integer width {} in column '{}'", bits, name));
+                }
+                Column::Int { bits, signed: self.field_u8(t, 1)?.unwrap_or(0) != 0 }
+            }
+            (TYPE_FLOAT, t) => {
+                let precision = match t { Some(t) => self.field_i16(t, 0)?.unwrap_or(0) as u16, None => 0 };
+                Column::Float { precision }
+            }
+            (TYPE_NULL, _) => Column::Skip { buffers: 0 },
+            // validity + offsets + data
+            (TYPE_BINARY | TYPE_UTF8 | TYPE_LARGE_BINARY | TYPE_LARGE_UTF8, _) => Column::Skip { buffers: 3 },
+            // validity + offsets; values live in the children
+            (TYPE_LIST | TYPE_LARGE_LIST | TYPE_MAP, _) => Column::Skip { buffers: 2 },
+            (TYPE_STRUCT | TYPE_FIXED_SIZE_LIST, _) => Column::Skip { buffers: 1 },
+            (TYPE_UNION, _) => return Err(format!("Union column '{}' is not supported", name)),
+            // Bool, Decimal, temporal and fixed-size binary types: validity + one data buffer
+            (TYPE_BOOL | TYPE_DECIMAL | TYPE_DATE | TYPE_TIME | TYPE_TIMESTAMP | TYPE_INTERVAL
+                | TYPE_FIXED_SIZE_BINARY | TYPE_DURATION, _) => Column::Skip { buffers: 2 },
+            // Run-end encoded, view and list-view types have other buffer layouts
+            (id, _) => return Err(format!("Unsupported Arrow type id {} in column '{}'", id, name)),
+        };
+
+        let (mut child_nodes, mut child_buffers) = (0, 0);
+        if let Some((start, n)) = self.field_vector(field, 5)? {
+            for i in 0..n {
+                let child = self.field_layout(self.indirect(start + 4 * i)?)?;
+                let own = match child.column { Column::Skip { buffers } => buffers, _ => 2 };
+                child_nodes += 1 + child.child_nodes;
+                child_buffers += own + child.child_buffers;
+            }
+        }
+
+        Ok(FieldLayout { name, column, child_nodes, child_buffers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Written by arrow-rs 54: columns t: f64, label: utf8 (nullable), count: i32 (nullable),
+    // v: f32, in two record batches of 2 and 1 rows.
+    const STREAM: &[u8] = include_bytes!("../../tests/fixtures/mixed.arrows");
+    const FILE: &[u8] = include_bytes!("../../tests/fixtures/mixed.arrow");
+
+    #[test]
+    fn test_read_arrow_stream_and_file() {
+        for bytes in [STREAM, FILE] {
+            let cols = parse_arrow_ipc(bytes).unwrap();
+            let names: Vec<&str> = cols.iter().map(|(n, _)| n.as_str()).collect();
+            assert_eq!(names, ["t", "count", "v"]);
+            assert_eq!(cols[0].1, vec![0.0, 0.5, 1.0]);
+            assert_eq!(cols[1].1[0], 7.0);
+            assert!(cols[1].1[1].is_nan());
+            assert_eq!(cols[1].1[2], -3.0);
+            assert_eq!(cols[2].1, vec![1.5, -2.0, 0.25]);
+        }
+    }
+
+    #[test]
+    fn test_half_to_f64() {
+        assert_eq!(half_to_f64(0x3c00), 1.0);
+        assert_eq!(half_to_f64(0xc000), -2.0);
+        assert_eq!(half_to_f64(0x3555), 0.333251953125);
+    }
+
+    #[test]
+    fn test_untrusted_lengths_are_rejected() {
+        assert_eq!(to_len(5), Ok(5));
+        assert!(to_len(-1).is_err());
+        // A stream whose first message claims more metadata than the input holds
+        let mut bytes = vec![0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x7f];
+        bytes.extend([0u8; 8]);
+        assert!(parse_arrow_ipc(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_column_length_must_fit_value_buffer() {
+        let col = Column::Float { precision: 2 };
+        let values = [0u8; 16];
+        let mut out = Vec::new();
+        decode_column(&col, 2, 0, &[], &values, &mut out).unwrap();
+        assert_eq!(out, vec![0.0, 0.0]);
+
+        // Truncated buffer, and a length whose byte size overflows usize
+        for len in [3, usize::MAX / 4] {
+            let mut out = Vec::new();
+            assert!(decode_column(&col, len, 0, &[], &values, &mut out).is_err());
+            assert!(out.is_empty());
+        }
+    }
+}
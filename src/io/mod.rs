@@ -7,6 +7,8 @@
 //! - `text.rs`: Universal text streamer with configurable delimiters
 //! - `binary.rs`: Binary file handlers (Excel, future HDF5)
 //! - `sniffers.rs`: Auto-detection of file formats
+//! - `arrow.rs`: Apache Arrow IPC (stream/file) numeric columns
+//! - `wav.rs`: RIFF/WAVE audio
 //!
 //! ## Usage
 //! ```typescript
@@ -28,11 +30,15 @@ pub mod sniffers;
 pub mod fast_numeric;  // Ultra-fast zero-copy numeric parser
 pub mod npy;           // NumPy .npy file parser
 pub mod matlab;        // MATLAB .mat file parser
+pub mod arrow;         // Apache Arrow IPC numeric reader
+pub mod wav;           // RIFF/WAVE audio reader/writer
 
 // Re-export main types for convenience
-pub use text::{TextStreamer, CSVReaderOptions, read_csv_with_options, write_csv};
+pub use text::{TextStreamer, CSVReaderOptions, read_csv_with_options, write_csv, write_numeric_csv};
 pub use binary::read_excel_file;
 pub use sniffers::{sniff_format, FormatHint};
 pub use npy::read_npy;
 pub use matlab::read_mat_file;
+pub use arrow::read_arrow_ipc;
+pub use wav::{read_wav, write_wav};
 pub use fast_numeric::{parse_numeric_csv_fast, parse_fixed_width_fast, alloc_parse_buffer, parse_buffer_in_place, get_result_ptr, get_result_len};
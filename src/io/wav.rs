@@ -0,0 +1,195 @@
+//! RIFF/WAVE audio reader and writer.
+//!
+//! Reads 8/16/24/32-bit integer PCM and 32/64-bit IEEE float (including
+//! `WAVE_FORMAT_EXTENSIBLE` headers) into per-channel `f64` samples in $[-1, 1]$.
+//! Writes 16-bit PCM or 32-bit float.
+
+use wasm_bindgen::prelude::*;
+use js_sys::{Array, Float64Array, Object, Reflect};
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+pub(crate) struct WavAudio {
+    pub sample_rate: u32,
+    /// One vector per channel.
+    pub channels: Vec<Vec<f64>>,
+}
+
+/// Decodes a `.wav` file into `{ sample_rate, channels, data }`, where `data` holds one
+/// `Float64Array` per channel with samples normalized to $[-1, 1]$.
+#[wasm_bindgen]
+pub fn read_wav(bytes: &[u8]) -> Result<JsValue, JsValue> {
+    let audio = parse_wav(bytes).map_err(|e| JsValue::from_str(&e))?;
+    let data = Array::new();
+    for ch in &audio.channels {
+        let arr = Float64Array::new_with_length(ch.len() as u32);
+        arr.copy_from(ch);
+        data.push(&arr);
+    }
+    let out = Object::new();
+    Reflect::set(&out, &"sample_rate".into(), &audio.sample_rate.into())?;
+    Reflect::set(&out, &"channels".into(), &(audio.channels.len() as u32).into())?;
+    Reflect::set(&out, &"data".into(), &data)?;
+    Ok(out.into())
+}
+
+/// Encodes interleaved samples in $[-1, 1]$ as a `.wav` file.
+/// `bits` selects 16-bit PCM (`16`) or 32-bit float (`32`); PCM samples are clipped.
+#[wasm_bindgen]
+pub fn write_wav(data: &[f64], sample_rate: u32, channels: usize, bits: u16) -> Result<Vec<u8>, JsValue> {
+    encode_wav(data, sample_rate, channels, bits).map_err(|e| JsValue::from_str(&e))
+}
+
+pub(crate) fn parse_wav(bytes: &[u8]) -> Result<WavAudio, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("Not a RIFF/WAVE file".into());
+    }
+
+    let mut fmt: Option<(u16, usize, u32, u16)> = None;
+    let mut samples: Option<&[u8]> = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        let start = pos + 8;
+        // Writers sometimes put a bogus size on the last chunk (streaming writers use
+        // 0xFFFFFFFF for `data`); clamp to the file
+        let chunk = &bytes[start..start.saturating_add(size).min(bytes.len())];
+        match id {
+            b"fmt " => {
+                if chunk.len() < 16 { return Err("fmt chunk too short".into()); }
+                let mut format = u16::from_le_bytes([chunk[0], chunk[1]]);
+                let channels = u16::from_le_bytes([chunk[2], chunk[3]]) as usize;
+                let sample_rate = u32::from_le_bytes(chunk[4..8].try_into().unwrap());
+                let bits = u16::from_le_bytes([chunk[14], chunk[15]]);
+                if format == FORMAT_EXTENSIBLE {
+                    if chunk.len() < 26 { return Err("Extensible fmt chunk too short".into()); }
+                    // The sub-format GUID starts with the actual format tag
+                    format = u16::from_le_bytes([chunk[24], chunk[25]]);
+                }
+                fmt = Some((format, channels, sample_rate, bits));
+            }
+            b"data" => samples = Some(chunk),
+            _ => {}
+        }
+        // Chunks are word-aligned; a size running past the address space ends the scan
+        match start.checked_add(size).and_then(|end| end.checked_add(size & 1)) {
+            Some(next) => pos = next,
+            None => break,
+        }
+    }
+
+    let (format, n_channels, sample_rate, bits) = fmt.ok_or("Missing fmt chunk")?;
+    let samples = samples.ok_or("Missing data chunk")?;
+    if n_channels == 0 {
+        return Err("WAV file declares zero channels".into());
+    }
+
+    let width = bits as usize / 8;
+    let decode: fn(&[u8]) -> f64 = match (format, bits) {
+        (FORMAT_PCM, 8) => |b| (b[0] as f64 - 128.0) / 128.0,
+        (FORMAT_PCM, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f64 / 32768.0,
+        (FORMAT_PCM, 24) => |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f64 / 8_388_608.0,
+        (FORMAT_PCM, 32) => |b| i32::from_le_bytes(b.try_into().unwrap()) as f64 / 2_147_483_648.0,
+        (FORMAT_FLOAT, 32) => |b| f32::from_le_bytes(b.try_into().unwrap()) as f64,
+        (FORMAT_FLOAT, 64) => |b| f64::from_le_bytes(b.try_into().unwrap()),
+        _ => return Err(format!("Unsupported WAV encoding (format {}, {} bits)", format, bits)),
+    };
+
+    let frame = width * n_channels;
+    let n_frames = samples.len() / frame;
+    let mut channels = vec![Vec::with_capacity(n_frames); n_channels];
+    for f in samples.chunks_exact(frame) {
+        for (ch, s) in channels.iter_mut().zip(f.chunks_exact(width)) {
+            ch.push(decode(s));
+        }
+    }
+
+    Ok(WavAudio { sample_rate, channels })
+}
+
+pub(crate) fn encode_wav(data: &[f64], sample_rate: u32, channels: usize, bits: u16) -> Result<Vec<u8>, String> {
+    if channels == 0 || data.len() % channels != 0 {
+        return Err("Sample count must be a non-zero multiple of the channel count".into());
+    }
+    let format = match bits {
+        16 => FORMAT_PCM,
+        32 => FORMAT_FLOAT,
+        _ => return Err("Only 16-bit PCM and 32-bit float WAV output is supported".into()),
+    };
+
+    if channels > u16::MAX as usize {
+        return Err("At most 65535 channels are supported".into());
+    }
+
+    let width = bits as usize / 8;
+    let data_len = data.len() * width;
+    if data_len > (u32::MAX - 36) as usize {
+        return Err("Sample data exceeds the 4 GiB RIFF limit".into());
+    }
+    let block_align = u16::try_from(channels * width).map_err(|_| "Too many channels for the WAV block size".to_string())?;
+    let byte_rate = sample_rate.checked_mul(block_align as u32).ok_or("Sample rate too high for the channel count")?;
+    let mut out = Vec::with_capacity(44 + data_len);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&((36 + data_len) as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&format.to_le_bytes());
+    out.extend_from_slice(&(channels as u16).to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&byte_rate.to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&bits.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(data_len as u32).to_le_bytes());
+
+    for &s in data {
+        if bits == 16 {
+            let v = (s.clamp(-1.0, 1.0) * 32767.0).round() as i16;
+            out.extend_from_slice(&v.to_le_bytes());
+        } else {
+            out.extend_from_slice(&(s as f32).to_le_bytes());
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav_roundtrip_deinterleaves() {
+        // Stereo: left ramps up, right is the negated left
+        let frames: Vec<f64> = (0..64).flat_map(|i| {
+            let v = i as f64 / 64.0 - 0.5;
+            [v, -v]
+        }).collect();
+
+        let pcm = parse_wav(&encode_wav(&frames, 44_100, 2, 16).unwrap()).unwrap();
+        assert_eq!(pcm.sample_rate, 44_100);
+        assert_eq!(pcm.channels.len(), 2);
+        assert_eq!(pcm.channels[0].len(), 64);
+        for i in 0..64 {
+            assert!((pcm.channels[0][i] - frames[2 * i]).abs() < 1e-4);
+            assert!((pcm.channels[1][i] - frames[2 * i + 1]).abs() < 1e-4);
+        }
+
+        let float = parse_wav(&encode_wav(&frames, 8_000, 2, 32).unwrap()).unwrap();
+        assert_eq!(float.channels[1][10], frames[21]);
+    }
+
+    #[test]
+    fn test_streaming_data_size_is_clamped() {
+        // Streaming writers leave the data size at 0xFFFFFFFF
+        let mut bytes = encode_wav(&[0.5, -0.5, 0.25], 8_000, 1, 16).unwrap();
+        bytes[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+        let audio = parse_wav(&bytes).unwrap();
+        assert_eq!(audio.channels[0].len(), 3);
+        assert!((audio.channels[0][2] - 0.25).abs() < 1e-4);
+
+        assert!(encode_wav(&[0.0; 4], u32::MAX, 2, 16).is_err());
+    }
+}
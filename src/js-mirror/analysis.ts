@@ -165,8 +165,9 @@ export function estimateSNR(data: Float64Array | number[]): number {
     const diffs = new Float64Array(n - 1);
     for(let i=0; i<n-1; i++) diffs[i] = Math.abs(data[i+1] - data[i]);
     diffs.sort();
+    // Robust std of the differences, which carry twice the white-noise power
     const ns = diffs[Math.floor(diffs.length / 2)] / 0.6745;
-    const nvar = ns * ns;
-    if (nvar < 1e-18) return 100.0;
+    const nvar = ns * ns / 2;
+    if (nvar <= svar * 1e-24) return Infinity;
     return 10.0 * Math.log10(svar / nvar);
 }
@@ -55,6 +55,22 @@ fn dot_product_simd(a: &[f64], b: &[f64]) -> f64 {
     total
 }
 
+/// Single-precision dot product - Parallel
+///
+/// Inputs take half the memory of `dotProduct`; products and partial sums are `f32`, so
+/// expect roughly 7 significant digits, degrading with vector length and cancellation.
+#[wasm_bindgen(js_name = dotProductF32)]
+pub fn dot_product_f32(a: &[f32], b: &[f32]) -> Result<f32, JsValue> {
+    if a.len() != b.len() {
+        return Err(JsValue::from_str("Vectors must have the same length"));
+    }
+
+    Ok(a.par_iter().zip(b.par_iter())
+        .with_min_len(8192)
+        .map(|(&x, &y)| x * y)
+        .sum())
+}
+
 /// Computes the QR decomposition of a matrix - Gram-Schmidt (Parallel)
 /// Returns [Q, R] as flattened vectors.
 #[wasm_bindgen]
@@ -112,12 +128,7 @@ pub fn normalize(v: &[f64]) -> Vec<f64> {
     let n = v.len();
     if n == 0 { return vec![]; }
     
-    let mag_sq: f64 = v.par_iter()
-        .with_min_len(8192)
-        .map(|&x| x * x)
-        .sum();
-    let mag = mag_sq.sqrt();
-    
+    let mag = l2_norm(v);
     if mag < 1e-18 { return v.to_vec(); }
     
     v.par_iter()
@@ -126,6 +137,75 @@ pub fn normalize(v: &[f64]) -> Vec<f64> {
      .collect()
 }
 
+/// Euclidean norm of a vector - Parallel
+pub(crate) fn l2_norm(v: &[f64]) -> f64 {
+    v.par_iter()
+        .with_min_len(8192)
+        .map(|&x| x * x)
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Normalizes a row-major `rows × cols` matrix along an axis - Parallel
+///
+/// `axis = 0` transforms each column, `axis = 1` each row. `mode` is "zscore" (population
+/// standard deviation), "minmax" (to [0, 1]), "l2" or "l1" (unit norm). Every slice is mapped as
+/// $(x - \text{offset}) / \text{scale}$ and the result is `[matrix, offsets, scales]`, with one
+/// offset and scale per slice, so the fitted transform can be reapplied to new data.
+/// Constant slices get a scale of 1 and zero-norm slices are left unchanged.
+#[wasm_bindgen(js_name = normalizeMatrix)]
+pub fn normalize_matrix(data: &[f64], rows: usize, cols: usize, axis: usize, mode: &str) -> Result<Vec<f64>, JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Matrix dimensions do not match data length"));
+    }
+    let (slices, len) = match axis {
+        0 => (cols, rows),
+        1 => (rows, cols),
+        _ => return Err(JsValue::from_str("axis must be 0 or 1")),
+    };
+    let at = |s: usize, i: usize| if axis == 0 { data[i * cols + s] } else { data[s * cols + i] };
+
+    let fit: fn(&mut dyn Iterator<Item = f64>, usize) -> (f64, f64) = match mode {
+        "zscore" => |it, len| {
+            let (mut n, mut mean, mut m2) = (0.0, 0.0, 0.0);
+            for x in it {
+                n += 1.0;
+                let d = x - mean;
+                mean += d / n;
+                m2 += d * (x - mean);
+            }
+            (mean, (m2 / len.max(1) as f64).sqrt())
+        },
+        "minmax" => |it, _| {
+            let (lo, hi) = it.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| (lo.min(x), hi.max(x)));
+            (lo, hi - lo)
+        },
+        "l2" => |it, _| (0.0, it.map(|x| x * x).sum::<f64>().sqrt()),
+        "l1" => |it, _| (0.0, it.map(f64::abs).sum::<f64>()),
+        _ => return Err(JsValue::from_str("Mode must be \"zscore\", \"minmax\", \"l2\" or \"l1\"")),
+    };
+
+    let params: Vec<(f64, f64)> = (0..slices).into_par_iter()
+        .map(|s| {
+            let (offset, scale) = fit(&mut (0..len).map(|i| at(s, i)), len);
+            (offset, if scale > 0.0 && scale.is_finite() { scale } else { 1.0 })
+        })
+        .collect();
+
+    let mut out: Vec<f64> = data.par_iter()
+        .enumerate()
+        .with_min_len(8192)
+        .map(|(idx, &v)| {
+            let (offset, scale) = params[if axis == 0 { idx % cols } else { idx / cols }];
+            (v - offset) / scale
+        })
+        .collect();
+    out.reserve(2 * slices);
+    out.extend(params.iter().map(|p| p.0));
+    out.extend(params.iter().map(|p| p.1));
+    Ok(out)
+}
+
 /// Multiplies two matrices represented as flat arrays - Parallel
 #[wasm_bindgen(js_name = matrixMultiply)]
 pub fn matrix_multiply(
@@ -156,6 +236,37 @@ pub fn matrix_multiply(
     Ok(result)
 }
 
+/// Single-precision matrix multiply on flat row-major arrays - Parallel
+///
+/// Same layout and algorithm as `matrixMultiply` with `f32` storage and accumulation.
+#[wasm_bindgen(js_name = matrixMultiplyF32)]
+pub fn matrix_multiply_f32(
+    a: &[f32], rows_a: usize, cols_a: usize,
+    b: &[f32], rows_b: usize, cols_b: usize
+) -> Result<Vec<f32>, JsValue> {
+    if cols_a != rows_b {
+        return Err(JsValue::from_str("Incompatible dimensions for matrix multiplication"));
+    }
+    if a.len() != rows_a * cols_a || b.len() != rows_b * cols_b {
+        return Err(JsValue::from_str("Data length does not match dimensions"));
+    }
+
+    let mut result = vec![0.0f32; rows_a * cols_b];
+    result.par_chunks_mut(cols_b.max(1))
+        .enumerate()
+        .for_each(|(i, out_row)| {
+            let a_row = &a[i * cols_a..(i + 1) * cols_a];
+            for (k, &aik) in a_row.iter().enumerate() {
+                let b_row = &b[k * cols_b..(k + 1) * cols_b];
+                for (o, &bkj) in out_row.iter_mut().zip(b_row) {
+                    *o += aik * bkj;
+                }
+            }
+        });
+
+    Ok(result)
+}
+
 /// Transposes a matrix - Parallel
 #[wasm_bindgen]
 pub fn transpose(data: &[f64], rows: usize, cols: usize) -> Vec<f64> {
@@ -177,6 +288,66 @@ pub fn transpose(data: &[f64], rows: usize, cols: usize) -> Vec<f64> {
     result
 }
 
+fn check_shape(data: &[f64], rows: usize, cols: usize) -> Result<(), JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Data length must equal rows * cols"));
+    }
+    Ok(())
+}
+
+/// Copies the `nr x nc` block starting at row `r0`, column `c0` of a row-major matrix.
+#[wasm_bindgen]
+pub fn submatrix(data: &[f64], rows: usize, cols: usize, r0: usize, c0: usize, nr: usize, nc: usize) -> Result<Vec<f64>, JsValue> {
+    check_shape(data, rows, cols)?;
+    if r0 + nr > rows || c0 + nc > cols {
+        return Err(JsValue::from_str("Block exceeds matrix bounds"));
+    }
+    Ok(data.chunks_exact(cols.max(1))
+        .skip(r0)
+        .take(nr)
+        .flat_map(|row| &row[c0..c0 + nc])
+        .copied()
+        .collect())
+}
+
+/// Places `b` to the right of `a`; both must have the same number of rows.
+#[wasm_bindgen]
+pub fn hstack(a: &[f64], ra: usize, ca: usize, b: &[f64], rb: usize, cb: usize) -> Result<Vec<f64>, JsValue> {
+    check_shape(a, ra, ca)?;
+    check_shape(b, rb, cb)?;
+    if ra != rb {
+        return Err(JsValue::from_str("hstack requires equal row counts"));
+    }
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    for r in 0..ra {
+        out.extend_from_slice(&a[r * ca..(r + 1) * ca]);
+        out.extend_from_slice(&b[r * cb..(r + 1) * cb]);
+    }
+    Ok(out)
+}
+
+/// Places `b` below `a`; both must have the same number of columns.
+#[wasm_bindgen]
+pub fn vstack(a: &[f64], ra: usize, ca: usize, b: &[f64], rb: usize, cb: usize) -> Result<Vec<f64>, JsValue> {
+    check_shape(a, ra, ca)?;
+    check_shape(b, rb, cb)?;
+    if ca != cb {
+        return Err(JsValue::from_str("vstack requires equal column counts"));
+    }
+    Ok([a, b].concat())
+}
+
+/// Reinterprets a row-major `old_rows x old_cols` matrix as `new_rows x new_cols`.
+/// Elements keep their row-major order, so the element count must not change.
+#[wasm_bindgen]
+pub fn reshape(data: &[f64], old_rows: usize, old_cols: usize, new_rows: usize, new_cols: usize) -> Result<Vec<f64>, JsValue> {
+    check_shape(data, old_rows, old_cols)?;
+    if new_rows * new_cols != data.len() {
+        return Err(JsValue::from_str("Reshape must preserve the number of elements"));
+    }
+    Ok(data.to_vec())
+}
+
 /// Inverts a 2x2 matrix.
 #[wasm_bindgen(js_name = invert2x2)]
 pub fn invert_2x2(m: &[f64]) -> Result<Vec<f64>, JsValue> {
@@ -263,6 +434,280 @@ pub fn solve_linear_system(a: &[f64], b: &[f64], n: usize) -> Result<Vec<f64>, J
     Ok(b_copy)
 }
 
+/// Solves Ax = B by LU factorization followed by iterative refinement.
+///
+/// A is factored once with partial pivoting. Each of up to `iters` rounds computes the residual
+/// $r = b - Ax$ with compensated (error-free transformation) dot products, solves
+/// $A\,\delta = r$ against the same factors and updates $x \leftarrow x + \delta$, stopping
+/// early once the correction is negligible.
+#[wasm_bindgen(js_name = solveRefined)]
+pub fn solve_refined(a: &[f64], b: &[f64], n: usize, iters: usize) -> Result<Vec<f64>, JsValue> {
+    if a.len() != n * n || b.len() != n {
+        return Err(JsValue::from_str("Invalid dimensions for linear system"));
+    }
+    let (factors, perm) = lu_factor_packed(a, n).map_err(JsValue::from_str)?;
+    let mut x = lu_substitute(&factors, &perm, b, n).map_err(JsValue::from_str)?;
+    for _ in 0..iters {
+        let r: Vec<f64> = a.par_chunks(n.max(1))
+            .zip(b.par_iter())
+            .map(|(row, &bi)| residual_compensated(row, &x, bi))
+            .collect();
+        let dx = lu_substitute(&factors, &perm, &r, n).map_err(JsValue::from_str)?;
+
+        let dx_norm = dx.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        let x_norm = x.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+        x.iter_mut().zip(&dx).for_each(|(xi, d)| *xi += d);
+        if dx_norm <= f64::EPSILON * x_norm {
+            break;
+        }
+    }
+    Ok(x)
+}
+
+/// Doolittle LU with partial pivoting of a row-major `n × n` matrix, in the packed column-major
+/// layout `lu_substitute` accepts, together with its row permutation vector.
+fn lu_factor_packed(a: &[f64], n: usize) -> Result<(Vec<f64>, Vec<f64>), &'static str> {
+    let mut m = a.to_vec();
+    let mut perm: Vec<usize> = (0..n).collect();
+    for k in 0..n {
+        let pivot_row = (k..n)
+            .max_by(|&i, &j| m[i * n + k].abs().total_cmp(&m[j * n + k].abs()))
+            .unwrap_or(k);
+        if m[pivot_row * n + k].abs() < 1e-18 {
+            return Err("Matrix is singular or nearly singular");
+        }
+        if pivot_row != k {
+            for j in 0..n {
+                m.swap(k * n + j, pivot_row * n + j);
+            }
+            perm.swap(k, pivot_row);
+        }
+        let pivot = m[k * n + k];
+        for i in k + 1..n {
+            let factor = m[i * n + k] / pivot;
+            m[i * n + k] = factor;
+            for j in k + 1..n {
+                m[i * n + j] -= factor * m[k * n + j];
+            }
+        }
+    }
+    let packed = (0..n * n).map(|idx| m[(idx % n) * n + idx / n]).collect();
+    Ok((packed, perm.into_iter().map(|r| r as f64).collect()))
+}
+
+/// $b - a \cdot x$ with the Dot2 algorithm (Ogita, Rump & Oishi): products are split exactly
+/// with FMA and sums with TwoSum, giving roughly twice the working precision.
+fn residual_compensated(a: &[f64], x: &[f64], b: f64) -> f64 {
+    let (mut sum, mut err) = (b, 0.0);
+    for (&ai, &xi) in a.iter().zip(x) {
+        let p = -ai * xi;
+        let p_err = (-ai).mul_add(xi, -p);
+        // TwoSum(sum, p)
+        let t = sum + p;
+        let z = t - sum;
+        err += (sum - (t - z)) + (p - z) + p_err;
+        sum = t;
+    }
+    sum + err
+}
+
+/// Solves a tridiagonal system with the Thomas algorithm in O(n).
+///
+/// `diag` holds the n main-diagonal entries; `lower` and `upper` hold the n-1 sub- and
+/// super-diagonal entries (`lower[i]` sits at row i+1, `upper[i]` at row i). No pivoting is
+/// done, so the system should be diagonally dominant or symmetric positive-definite.
+#[wasm_bindgen(js_name = solveTridiagonal)]
+pub fn solve_tridiagonal(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Result<Vec<f64>, JsValue> {
+    thomas(lower, diag, upper, rhs).map_err(JsValue::from_str)
+}
+
+pub(crate) fn thomas(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Result<Vec<f64>, &'static str> {
+    let n = diag.len();
+    if rhs.len() != n || lower.len() + 1 != n.max(1) || upper.len() + 1 != n.max(1) {
+        return Err("Expected diag and rhs of length n, lower and upper of length n-1");
+    }
+    if n == 0 { return Ok(vec![]); }
+
+    // Forward sweep: c' holds the modified super-diagonal, x the modified rhs
+    let mut c = vec![0.0; n];
+    let mut x = vec![0.0; n];
+    let mut denom = diag[0];
+    for i in 0..n {
+        if i > 0 {
+            denom = diag[i] - lower[i - 1] * c[i - 1];
+        }
+        if denom.abs() < 1e-18 {
+            return Err("Zero pivot in tridiagonal solve");
+        }
+        if i + 1 < n { c[i] = upper[i] / denom; }
+        let carry = if i > 0 { lower[i - 1] * x[i - 1] } else { 0.0 };
+        x[i] = (rhs[i] - carry) / denom;
+    }
+
+    // Back substitution
+    for i in (0..n - 1).rev() {
+        x[i] -= c[i] * x[i + 1];
+    }
+    Ok(x)
+}
+
+/// Solves a banded system Ax = b with partial-pivoting band LU (LAPACK `gbsv` layout).
+///
+/// `ab` is row-major with `2*lower_bw + upper_bw + 1` rows and `n` columns, where
+/// `A[i][j]` is stored at `ab[(lower_bw + upper_bw + i - j) * n + j]`. The first
+/// `lower_bw` rows are workspace for pivoting fill-in and their contents are ignored.
+#[wasm_bindgen(js_name = solveBanded)]
+pub fn solve_banded(lower_bw: usize, upper_bw: usize, ab: &[f64], b: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
+    banded_lu_solve(lower_bw, upper_bw, ab, b, n).map_err(JsValue::from_str)
+}
+
+pub(crate) fn banded_lu_solve(kl: usize, ku: usize, ab: &[f64], b: &[f64], n: usize) -> Result<Vec<f64>, &'static str> {
+    let ldab = 2 * kl + ku + 1;
+    if ab.len() != ldab * n || b.len() != n {
+        return Err("Expected ab of length (2*lower_bw + upper_bw + 1) * n and b of length n");
+    }
+
+    // U may fill in up to kl + ku super-diagonals once rows are swapped
+    let kv = kl + ku;
+    let idx = |i: usize, j: usize| (kv + i - j) * n + j;
+    let mut a = ab.to_vec();
+    // Clear the fill-in rows so stale input cannot leak into U
+    a[..kl * n].fill(0.0);
+    let mut x = b.to_vec();
+
+    for k in 0..n {
+        let last_row = (k + kl).min(n - 1);
+        let last_col = (k + kv).min(n - 1);
+
+        let mut p = k;
+        for i in k + 1..=last_row {
+            if a[idx(i, k)].abs() > a[idx(p, k)].abs() { p = i; }
+        }
+        if a[idx(p, k)].abs() < 1e-18 {
+            return Err("Matrix is singular or nearly singular");
+        }
+        if p != k {
+            for j in k..=last_col {
+                a.swap(idx(k, j), idx(p, j));
+            }
+            x.swap(k, p);
+        }
+
+        let pivot = a[idx(k, k)];
+        for i in k + 1..=last_row {
+            let l = a[idx(i, k)] / pivot;
+            if l == 0.0 { continue; }
+            for j in k + 1..=last_col {
+                a[idx(i, j)] -= l * a[idx(k, j)];
+            }
+            x[i] -= l * x[k];
+        }
+    }
+
+    for k in (0..n).rev() {
+        let last_col = (k + kv).min(n - 1);
+        let mut sum = x[k];
+        for j in k + 1..=last_col {
+            sum -= a[idx(k, j)] * x[j];
+        }
+        x[k] = sum / a[idx(k, k)];
+    }
+    Ok(x)
+}
+
+/// Distance metric accepted by `pairwise_distances`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DistanceMetric {
+    Euclidean,
+    SqEuclidean,
+    Manhattan,
+    Cosine,
+}
+
+impl DistanceMetric {
+    pub(crate) fn parse(name: &str) -> Option<Self> {
+        match name {
+            "euclidean" => Some(Self::Euclidean),
+            "sqeuclidean" => Some(Self::SqEuclidean),
+            "manhattan" => Some(Self::Manhattan),
+            "cosine" => Some(Self::Cosine),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn distance(self, a: &[f64], b: &[f64]) -> f64 {
+        match self {
+            Self::Euclidean => Self::SqEuclidean.distance(a, b).sqrt(),
+            Self::SqEuclidean => a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum(),
+            Self::Manhattan => a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum(),
+            Self::Cosine => {
+                let (mut dot, mut na, mut nb) = (0.0, 0.0, 0.0);
+                for (x, y) in a.iter().zip(b) {
+                    dot += x * y;
+                    na += x * x;
+                    nb += y * y;
+                }
+                // A zero vector has no direction; treat it as orthogonal to everything
+                if na == 0.0 || nb == 0.0 { return 1.0; }
+                1.0 - dot / (na.sqrt() * nb.sqrt())
+            }
+        }
+    }
+}
+
+/// Computes every pairwise distance between the rows of a row-major `rows × cols` matrix - Parallel
+/// Returns the flattened symmetric `rows × rows` matrix.
+/// `metric` is "euclidean", "sqeuclidean", "manhattan" or "cosine" ($1 - \cos\theta$).
+#[wasm_bindgen(js_name = pairwiseDistances)]
+pub fn pairwise_distances(data: &[f64], rows: usize, cols: usize, metric: &str) -> Result<Vec<f64>, JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Matrix dimensions do not match data length"));
+    }
+    let metric = DistanceMetric::parse(metric)
+        .ok_or_else(|| JsValue::from_str("Metric must be \"euclidean\", \"sqeuclidean\", \"manhattan\" or \"cosine\""))?;
+    Ok(symmetric_pairwise(data, rows, cols, |a, b| metric.distance(a, b), 0.0))
+}
+
+/// Computes the Gram matrix $G = XX^T$ of a row-major `rows × cols` matrix - Parallel
+#[wasm_bindgen(js_name = gramMatrix)]
+pub fn gram_matrix(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Matrix dimensions do not match data length"));
+    }
+    Ok(symmetric_pairwise(data, rows, cols, |a, b| a.iter().zip(b).map(|(x, y)| x * y).sum(), f64::NAN))
+}
+
+/// Fills a symmetric `rows × rows` matrix from `f(row_i, row_j)`, evaluating only the upper
+/// triangle in parallel. The diagonal is `diag` unless that is NaN, in which case it is `f(row_i, row_i)`.
+pub(crate) fn symmetric_pairwise(
+    data: &[f64],
+    rows: usize,
+    cols: usize,
+    f: impl Fn(&[f64], &[f64]) -> f64 + Sync,
+    diag: f64,
+) -> Vec<f64> {
+    let row = |i: usize| &data[i * cols..(i + 1) * cols];
+    let upper: Vec<Vec<f64>> = (0..rows).into_par_iter()
+        .with_min_len(16)
+        .map(|i| {
+            let ri = row(i);
+            (i..rows).map(|j| {
+                if i == j && !diag.is_nan() { diag } else { f(ri, row(j)) }
+            }).collect()
+        })
+        .collect();
+
+    let mut out = vec![0.0; rows * rows];
+    for (i, tri) in upper.iter().enumerate() {
+        for (k, &v) in tri.iter().enumerate() {
+            let j = i + k;
+            out[i * rows + j] = v;
+            out[j * rows + i] = v;
+        }
+    }
+    out
+}
+
 /// Computes the Singular Value Decomposition (SVD) of a matrix.
 /// Returns [U, S, Vt] as flattened vectors.
 #[wasm_bindgen]
@@ -283,14 +728,15 @@ pub fn svd(matrix: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue
     Ok(res)
 }
 
-/// Computes the LU decomposition (with partial pivoting) of a square matrix.
-/// Returns [L, U, P] as flattened vectors.
+/// Computes the LU decomposition (with partial pivoting) of a square matrix, $PA = LU$.
+/// Returns [L, U, P] as flattened column-major vectors, ready for `luSolve`.
 #[wasm_bindgen]
 pub fn lu(matrix: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
     use nalgebra::DMatrix;
     if matrix.len() != n * n { return Err(JsValue::from_str("Matrix must be square")); }
     let m = DMatrix::from_row_slice(n, n, matrix);
-    let lu = m.full_piv_lu();
+    // Row pivoting only, so that L, U and P alone are enough to solve against the factors
+    let lu = m.lu();
     
     let l = lu.l();
     let u = lu.u();
@@ -304,6 +750,65 @@ pub fn lu(matrix: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
     Ok(res)
 }
 
+/// Solves $Ax = b$ from the factors returned by `lu`, in $O(n^2)$ per right-hand side.
+///
+/// `lu_packed` is either the L and U blocks of `lu`'s output (length $2n^2$) or a single packed
+/// $n^2$ matrix with U on and above the diagonal and the unit-lower L below it; both are
+/// column-major. `p` is the permutation matrix from `lu` (length $n^2$) or a row permutation
+/// vector (length $n$) where row i of $PA$ is row `p[i]` of A.
+#[wasm_bindgen(js_name = luSolve)]
+pub fn lu_solve(lu_packed: &[f64], p: &[f64], b: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
+    lu_substitute(lu_packed, p, b, n).map_err(JsValue::from_str)
+}
+
+pub(crate) fn lu_substitute(lu_packed: &[f64], p: &[f64], b: &[f64], n: usize) -> Result<Vec<f64>, &'static str> {
+    let nn = n * n;
+    if b.len() != n {
+        return Err("Right-hand side must have length n");
+    }
+    let packed = match lu_packed.len() {
+        l if l == nn => true,
+        l if l == 2 * nn => false,
+        _ => return Err("LU factors must have length n*n (packed) or 2*n*n (L then U)"),
+    };
+
+    // Column-major accessors; L sits in the first block unless packed
+    let u_off = if packed { 0 } else { nn };
+    let l_at = |i: usize, j: usize| lu_packed[j * n + i];
+    let u_at = |i: usize, j: usize| lu_packed[u_off + j * n + i];
+
+    let mut y: Vec<f64> = if n == 1 && p.len() == 1 {
+        // The only 1×1 permutation is the identity, whichever form was passed
+        b.to_vec()
+    } else if p.len() == n {
+        p.iter().map(|&r| {
+            let r = r as usize;
+            if r < n { Ok(b[r]) } else { Err("Permutation index out of range") }
+        }).collect::<Result<_, _>>()?
+    } else if p.len() == nn {
+        (0..n).map(|i| (0..n).map(|j| p[j * n + i] * b[j]).sum()).collect()
+    } else {
+        return Err("Permutation must have length n or n*n");
+    };
+
+    // Forward substitution with L (unit diagonal when packed)
+    for i in 0..n {
+        let sum = y[i] - (0..i).map(|j| l_at(i, j) * y[j]).sum::<f64>();
+        y[i] = if packed { sum } else { sum / l_at(i, i) };
+    }
+
+    // Back substitution with U
+    for i in (0..n).rev() {
+        let sum = y[i] - (i + 1..n).map(|j| u_at(i, j) * y[j]).sum::<f64>();
+        let d = u_at(i, i);
+        if d.abs() < 1e-18 {
+            return Err("Matrix is singular");
+        }
+        y[i] = sum / d;
+    }
+    Ok(y)
+}
+
 /// Computes the Cholesky decomposition of a symmetric positive-definite matrix.
 #[wasm_bindgen]
 pub fn cholesky(matrix: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
@@ -317,6 +822,48 @@ pub fn cholesky(matrix: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
     }
 }
 
+/// Whether a square matrix is symmetric (to $10^{-10}$ relative to its largest entry) and
+/// positive-definite, decided by attempting a Cholesky factorization.
+/// A wrong-sized input returns `false`.
+#[wasm_bindgen(js_name = isPositiveDefinite)]
+pub fn is_positive_definite(matrix: &[f64], n: usize) -> bool {
+    use nalgebra::DMatrix;
+    if matrix.len() != n * n || n == 0 { return false; }
+    let scale = matrix.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    let tol = 1e-10 * scale;
+    for i in 0..n {
+        for j in i + 1..n {
+            let (upper, lower) = (matrix[i * n + j], matrix[j * n + i]);
+            if upper.is_nan() || lower.is_nan() || (upper - lower).abs() > tol { return false; }
+        }
+    }
+    DMatrix::from_row_slice(n, n, matrix).cholesky().is_some()
+}
+
+/// Nearest symmetric positive-definite matrix in the Frobenius norm (Higham, 1988).
+///
+/// Symmetrizes $B = (A + A^T) / 2$, takes its eigen-decomposition $B = V \Lambda V^T$ and
+/// returns $V \max(\Lambda, \delta) V^T$. Higham's nearest positive *semi*-definite matrix
+/// clips at 0; the tiny floor $\delta = 10^{-10} \max|\lambda|$ keeps the result Cholesky-factorable.
+/// A matrix that is already positive-definite is returned (symmetrized) essentially unchanged.
+#[wasm_bindgen(js_name = nearestPositiveDefinite)]
+pub fn nearest_positive_definite(matrix: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
+    use nalgebra::{DMatrix, DVector};
+    if matrix.len() != n * n { return Err(JsValue::from_str("Matrix must be square")); }
+    let a = DMatrix::from_row_slice(n, n, matrix);
+    let b = (&a + a.transpose()) * 0.5;
+    let eig = b.symmetric_eigen();
+    let largest = eig.eigenvalues.iter().fold(0.0f64, |m, v| m.max(v.abs()));
+    let floor = (1e-10 * largest).max(f64::MIN_POSITIVE);
+    let clipped = DVector::from_iterator(n, eig.eigenvalues.iter().map(|&l| l.max(floor)));
+    let v = &eig.eigenvectors;
+    let x = v * DMatrix::from_diagonal(&clipped) * v.transpose();
+    // Remove the asymmetry introduced by rounding
+    let x = (&x + x.transpose()) * 0.5;
+    // Symmetric, so column-major storage equals row-major
+    Ok(x.as_slice().to_vec())
+}
+
 /// Calculates the determinant of a square matrix.
 #[wasm_bindgen]
 pub fn determinant(matrix: &[f64], n: usize) -> Result<f64, JsValue> {
@@ -326,22 +873,36 @@ pub fn determinant(matrix: &[f64], n: usize) -> Result<f64, JsValue> {
     Ok(m.determinant())
 }
 
-/// Calculates the rank of a matrix.
+/// Calculates the rank of a matrix: the number of singular values above `tol`.
+/// A negative `tol` selects the NumPy default $\sigma_{max} \cdot \max(rows, cols) \cdot \varepsilon$.
 #[wasm_bindgen]
-pub fn rank(matrix: &[f64], rows: usize, cols: usize) -> Result<usize, JsValue> {
+pub fn rank(matrix: &[f64], rows: usize, cols: usize, tol: f64) -> Result<usize, JsValue> {
     use nalgebra::DMatrix;
     if matrix.len() != rows * cols { return Err(JsValue::from_str("Invalid dimensions")); }
     let m = DMatrix::from_row_slice(rows, cols, matrix);
-    Ok(m.rank(1e-10))
+    let sv = m.singular_values();
+    let cutoff = singular_cutoff(sv.as_slice(), rows, cols, tol);
+    Ok(sv.iter().filter(|&&s| s > cutoff).count())
 }
 
-/// Computes the Moore-Penrose pseudo-inverse of a matrix.
+/// Singular-value threshold: `tol` itself, or the NumPy-style relative default when negative.
+fn singular_cutoff(singular_values: &[f64], rows: usize, cols: usize, tol: f64) -> f64 {
+    if tol >= 0.0 {
+        return tol;
+    }
+    let s_max = singular_values.iter().fold(0.0f64, |m, &s| m.max(s));
+    s_max * rows.max(cols) as f64 * f64::EPSILON
+}
+
+/// Computes the Moore-Penrose pseudo-inverse of a matrix, treating singular values at or
+/// below `tol` as zero. A negative `tol` selects the same relative default as `rank`.
 #[wasm_bindgen(js_name = pseudoInverse)]
-pub fn pseudo_inverse(matrix: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+pub fn pseudo_inverse(matrix: &[f64], rows: usize, cols: usize, tol: f64) -> Result<Vec<f64>, JsValue> {
     use nalgebra::DMatrix;
     if matrix.len() != rows * cols { return Err(JsValue::from_str("Invalid dimensions")); }
     let m = DMatrix::from_row_slice(rows, cols, matrix);
-    if let Ok(pinv) = m.pseudo_inverse(1e-10) {
+    let cutoff = if tol >= 0.0 { tol } else { singular_cutoff(m.singular_values().as_slice(), rows, cols, tol) };
+    if let Ok(pinv) = m.pseudo_inverse(cutoff) {
         Ok(pinv.as_slice().to_vec())
     } else {
         Err(JsValue::from_str("Failed to compute pseudo-inverse"))
@@ -365,6 +926,151 @@ pub fn eigenvalues(matrix: &[f64], n: usize) -> Result<Vec<f64>, JsValue> {
     Ok(res)
 }
 
+/// Full eigen-decomposition of a symmetric matrix with sorted eigenpairs.
+///
+/// Returns the `n` eigenvalues (ascending or descending) followed by the `n` unit eigenvectors,
+/// each stored contiguously (`n` values, i.e. one eigenvector per row of the trailing
+/// `n x n` block) in the same order as the eigenvalues. Only the symmetric part
+/// $(A + A^T)/2$ is used. Each eigenvector's sign is fixed so its largest-magnitude entry is positive.
+#[wasm_bindgen(js_name = symmetricEig)]
+pub fn symmetric_eig(matrix: &[f64], n: usize, ascending: bool) -> Result<Vec<f64>, JsValue> {
+    use nalgebra::DMatrix;
+    if matrix.len() != n * n { return Err(JsValue::from_str("Matrix must be square")); }
+    let a = DMatrix::from_row_slice(n, n, matrix);
+    let eig = ((&a + a.transpose()) * 0.5).symmetric_eigen();
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| eig.eigenvalues[i].total_cmp(&eig.eigenvalues[j]));
+    if !ascending { order.reverse(); }
+
+    let mut res = Vec::with_capacity(n + n * n);
+    res.extend(order.iter().map(|&i| eig.eigenvalues[i]));
+    for &i in &order {
+        let v = eig.eigenvectors.column(i);
+        let pivot = v.iter().fold(0.0f64, |p, &x| if x.abs() > p.abs() { x } else { p });
+        let sign = if pivot < 0.0 { -1.0 } else { 1.0 };
+        res.extend(v.iter().map(|x| sign * x));
+    }
+    Ok(res)
+}
+
+/// Finds the `k` largest (or smallest) eigenvalues of a symmetric matrix with the Lanczos iteration.
+///
+/// Builds a Krylov basis with full reorthogonalization and stops as soon as the wanted Ritz
+/// values have converged, which for extreme eigenvalues is usually far fewer than `n` steps.
+/// Eigenvalues come first, largest-first or smallest-first; with `vectors` set they are followed
+/// by the `k` unit eigenvectors, one length-`n` vector after another.
+#[wasm_bindgen(js_name = eigshExtreme)]
+pub fn eigsh_extreme(matrix: &[f64], n: usize, k: usize, largest: bool, vectors: Option<bool>) -> Result<Vec<f64>, JsValue> {
+    if matrix.len() != n * n { return Err(JsValue::from_str("Matrix must be square")); }
+    if k == 0 || k > n { return Err(JsValue::from_str("k must be between 1 and n")); }
+    let (values, vecs) = lanczos_extreme(matrix, n, k, largest, vectors.unwrap_or(false));
+    let mut res = values;
+    res.extend(vecs);
+    Ok(res)
+}
+
+/// Lanczos core for `eigsh_extreme`; returns the wanted eigenvalues and (optionally) the
+/// concatenated Ritz vectors.
+pub(crate) fn lanczos_extreme(a: &[f64], n: usize, k: usize, largest: bool, want_vectors: bool) -> (Vec<f64>, Vec<f64>) {
+    use nalgebra::DMatrix;
+
+    let dot = |x: &[f64], y: &[f64]| x.iter().zip(y).map(|(p, q)| p * q).sum::<f64>();
+    let matvec = |v: &[f64]| -> Vec<f64> {
+        a.par_chunks(n).with_min_len(128).map(|row| dot(row, v)).collect()
+    };
+    // Deterministic pseudo-random start vectors, so results are reproducible
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut random_orthogonal = |basis: &[Vec<f64>]| -> Option<Vec<f64>> {
+        let mut v: Vec<f64> = (0..n).map(|_| {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+        }).collect();
+        for _ in 0..2 {
+            for u in basis {
+                let c = dot(&v, u);
+                v.iter_mut().zip(u).for_each(|(vi, ui)| *vi -= c * ui);
+            }
+        }
+        let norm = dot(&v, &v).sqrt();
+        (norm > 1e-10).then(|| v.into_iter().map(|x| x / norm).collect())
+    };
+
+    let tridiagonal = |alpha: &[f64], beta: &[f64]| {
+        let m = alpha.len();
+        DMatrix::from_fn(m, m, |i, j| {
+            if i == j { alpha[i] } else if i + 1 == j { beta[i] } else if j + 1 == i { beta[j] } else { 0.0 }
+        })
+    };
+
+    let mut basis: Vec<Vec<f64>> = Vec::new();
+    let mut alpha: Vec<f64> = Vec::new();
+    let mut beta: Vec<f64> = Vec::new();
+    let mut v = random_orthogonal(&basis).unwrap_or_else(|| vec![1.0; n]);
+
+    let eig = loop {
+        let mut w = matvec(&v);
+        let a_j = dot(&w, &v);
+        // Full reorthogonalization (twice) keeps the basis orthogonal in floating point
+        for _ in 0..2 {
+            for u in basis.iter().chain(std::iter::once(&v)) {
+                let c = dot(&w, u);
+                w.iter_mut().zip(u).for_each(|(wi, ui)| *wi -= c * ui);
+            }
+        }
+        basis.push(v);
+        alpha.push(a_j);
+        let b = dot(&w, &w).sqrt();
+        let m = basis.len();
+
+        if m == n || (m >= k && (m - k) % 5 == 0) {
+            let eig = tridiagonal(&alpha, &beta).symmetric_eigen();
+            let order = extreme_order(eig.eigenvalues.as_slice(), largest);
+            let scale = eig.eigenvalues.amax().max(1.0);
+            // Residual of a Ritz pair is |b * last component of its eigenvector of T|
+            let converged = order.iter().take(k)
+                .all(|&i| (b * eig.eigenvectors[(m - 1, i)]).abs() <= 1e-10 * scale);
+            if m == n || converged { break eig; }
+        }
+
+        if b > 1e-12 {
+            beta.push(b);
+            v = w.into_iter().map(|x| x / b).collect();
+        } else {
+            // Invariant subspace found: continue from a fresh direction (T becomes block diagonal)
+            beta.push(0.0);
+            match random_orthogonal(&basis) {
+                Some(fresh) => v = fresh,
+                None => break tridiagonal(&alpha, &beta).symmetric_eigen(),
+            }
+        }
+    };
+
+    let order = extreme_order(eig.eigenvalues.as_slice(), largest);
+    let values: Vec<f64> = order.iter().take(k).map(|&i| eig.eigenvalues[i]).collect();
+    let mut vecs = Vec::new();
+    if want_vectors {
+        vecs.reserve(k * n);
+        for &i in order.iter().take(k) {
+            let mut x = vec![0.0; n];
+            for (j, u) in basis.iter().enumerate() {
+                let c = eig.eigenvectors[(j, i)];
+                x.iter_mut().zip(u).for_each(|(xi, ui)| *xi += c * ui);
+            }
+            vecs.extend(x);
+        }
+    }
+    (values, vecs)
+}
+
+/// Indices of `values` sorted largest-first or smallest-first.
+fn extreme_order(values: &[f64], largest: bool) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+    if largest { order.reverse(); }
+    order
+}
+
 /// Calculates the trace of a square matrix.
 #[wasm_bindgen]
 pub fn trace(matrix: &[f64], n: usize) -> Result<f64, JsValue> {
@@ -376,6 +1082,30 @@ pub fn trace(matrix: &[f64], n: usize) -> Result<f64, JsValue> {
     Ok(tr)
 }
 
+/// `n x n` identity matrix.
+#[wasm_bindgen]
+pub fn eye(n: usize) -> Vec<f64> {
+    diag_from(&vec![1.0; n])
+}
+
+/// Square matrix with `v` on the main diagonal and zeros elsewhere.
+#[wasm_bindgen(js_name = diagFrom)]
+pub fn diag_from(v: &[f64]) -> Vec<f64> {
+    let n = v.len();
+    let mut out = vec![0.0; n * n];
+    for (i, &x) in v.iter().enumerate() {
+        out[i * n + i] = x;
+    }
+    out
+}
+
+/// Main diagonal of a row-major `rows x cols` matrix (`min(rows, cols)` values).
+#[wasm_bindgen(js_name = diagOf)]
+pub fn diag_of(matrix: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    check_shape(matrix, rows, cols)?;
+    Ok((0..rows.min(cols)).map(|i| matrix[i * cols + i]).collect())
+}
+
 /// Calculates the determinant using LU decomposition.
 #[wasm_bindgen(js_name = detLU)]
 pub fn det_lu(matrix: &[f64], n: usize) -> Result<f64, JsValue> {
@@ -385,3 +1115,272 @@ pub fn det_lu(matrix: &[f64], n: usize) -> Result<f64, JsValue> {
     Ok(m.determinant()) 
     // nalgebra uses LU for determinant calculation efficiency already for square matrices generally
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thomas_matches_dense_solve() {
+        let lower = [1.0, -2.0, 0.5];
+        let diag = [4.0, 5.0, 6.0, 3.0];
+        let upper = [2.0, 1.0, -1.0];
+        let rhs = [1.0, 2.0, 3.0, 4.0];
+        let x = thomas(&lower, &diag, &upper, &rhs).unwrap();
+
+        let dense = [
+            4.0, 2.0, 0.0, 0.0,
+            1.0, 5.0, 1.0, 0.0,
+            0.0, -2.0, 6.0, -1.0,
+            0.0, 0.0, 0.5, 3.0,
+        ];
+        let expected = solve_linear_system(&dense, &rhs, 4).unwrap();
+        for (a, b) in x.iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert!(thomas(&[], &[0.0], &[], &[1.0]).is_err());
+    }
+
+    #[test]
+    fn test_refinement_improves_ill_conditioned_solve() {
+        // Hilbert matrix, condition number ~1e10 at n = 8
+        let n = 8;
+        let a: Vec<f64> = (0..n * n).map(|k| 1.0 / ((k / n + k % n + 1) as f64)).collect();
+        let x_true: Vec<f64> = (0..n).map(|i| (i + 1) as f64).collect();
+        // b = A x_true, correctly rounded (0 - A x, negated)
+        let b: Vec<f64> = a.chunks(n).map(|row| -residual_compensated(row, &x_true, 0.0)).collect();
+
+        let err = |x: &[f64]| x.iter().zip(&x_true).map(|(u, v)| (u - v).abs()).fold(0.0, f64::max);
+        let plain = solve_linear_system(&a, &b, n).unwrap();
+        let refined = solve_refined(&a, &b, n, 5).unwrap();
+        assert!(err(&refined) < err(&plain));
+        assert!(err(&refined) < 1e-6);
+    }
+
+    #[test]
+    fn test_banded_matches_dense_solve() {
+        // Pentadiagonal with a small leading diagonal so pivoting kicks in
+        let n = 7;
+        let (kl, ku) = (2, 2);
+        let mut dense = vec![0.0; n * n];
+        for i in 0..n {
+            for j in i.saturating_sub(kl)..=(i + ku).min(n - 1) {
+                dense[i * n + j] = if i == j { 0.1 + i as f64 } else { 1.0 + ((i * 3 + j * 5) % 7) as f64 * 0.5 };
+            }
+        }
+        let b: Vec<f64> = (0..n).map(|i| (i as f64).sin() + 1.0).collect();
+
+        let ldab = 2 * kl + ku + 1;
+        let mut ab = vec![0.0; ldab * n];
+        // Workspace rows are ignored on input
+        ab[..kl * n].fill(99.0);
+        for i in 0..n {
+            for j in i.saturating_sub(kl)..=(i + ku).min(n - 1) {
+                ab[(kl + ku + i - j) * n + j] = dense[i * n + j];
+            }
+        }
+
+        let x = banded_lu_solve(kl, ku, &ab, &b, n).unwrap();
+        let expected = solve_linear_system(&dense, &b, n).unwrap();
+        for (a, e) in x.iter().zip(&expected) {
+            assert!((a - e).abs() < 1e-10, "{} vs {}", a, e);
+        }
+    }
+
+    #[test]
+    fn test_lu_solve_reuses_factors() {
+        let a = [
+            0.0, 2.0, 1.0,
+            1.0, 1.0, 0.0,
+            3.0, 0.0, 1.0,
+        ];
+        let factors = lu(&a, 3).unwrap();
+        let (lu_blocks, p) = factors.split_at(18);
+        for b in [[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]] {
+            let x = lu_substitute(lu_blocks, p, &b, 3).unwrap();
+            let expected = solve_linear_system(&a, &b, 3).unwrap();
+            for (v, e) in x.iter().zip(&expected) {
+                assert!((v - e).abs() < 1e-12);
+            }
+        }
+
+        // Packed form with a permutation vector
+        let mut packed = lu_blocks[9..].to_vec();
+        for j in 0..3 {
+            for i in j + 1..3 {
+                packed[j * 3 + i] = lu_blocks[j * 3 + i];
+            }
+        }
+        let perm: Vec<f64> = (0..3)
+            .map(|i| (0..3).position(|j| p[j * 3 + i] == 1.0).unwrap() as f64)
+            .collect();
+        let x = lu_substitute(&packed, &perm, &[1.0, 2.0, 3.0], 3).unwrap();
+        let expected = solve_linear_system(&a, &[1.0, 2.0, 3.0], 3).unwrap();
+        for (v, e) in x.iter().zip(&expected) {
+            assert!((v - e).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_lanczos_matches_full_decomposition() {
+        use nalgebra::DMatrix;
+        let n = 60;
+        let m = DMatrix::from_fn(n, n, |i, j| {
+            let off = ((i * 7 + j * 7 + i * j) % 11) as f64 * 0.05;
+            if i == j { (i + 1) as f64 } else { off }
+        });
+        let sym = (&m + m.transpose()) * 0.5;
+        let data: Vec<f64> = sym.transpose().as_slice().to_vec();
+        let mut full = sym.symmetric_eigen().eigenvalues.as_slice().to_vec();
+        full.sort_by(f64::total_cmp);
+
+        let (top, vecs) = lanczos_extreme(&data, n, 3, true, true);
+        for (i, v) in top.iter().enumerate() {
+            assert!((v - full[n - 1 - i]).abs() < 1e-8, "{} vs {}", v, full[n - 1 - i]);
+        }
+        // A x = lambda x for the leading Ritz vector
+        let x = &vecs[..n];
+        for (row, xi) in data.chunks(n).zip(x) {
+            let ax: f64 = row.iter().zip(x).map(|(a, b)| a * b).sum();
+            assert!((ax - top[0] * xi).abs() < 1e-6);
+        }
+
+        let (bottom, _) = lanczos_extreme(&data, n, 2, false, false);
+        assert!((bottom[0] - full[0]).abs() < 1e-8);
+        assert!((bottom[1] - full[1]).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_normalize_matrix_axes_and_params() {
+        let data = [1.0, 10.0, 3.0, 20.0, 5.0, 30.0];
+        let z = normalize_matrix(&data, 3, 2, 0, "zscore").unwrap();
+        let scale = (8.0f64 / 3.0).sqrt();
+        assert!((z[0] + 2.0 / scale).abs() < 1e-12);
+        assert_eq!(z[3], 0.0);
+        // offsets, then scales, one per column
+        assert_eq!(&z[6..8], &[3.0, 20.0]);
+        assert!((z[8] - scale).abs() < 1e-12);
+
+        let mm = normalize_matrix(&data, 3, 2, 1, "minmax").unwrap();
+        assert_eq!(&mm[..6], &[0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(&mm[6..9], &[1.0, 3.0, 5.0]);
+
+        let l1 = normalize_matrix(&[3.0, -1.0, 0.0, 0.0], 2, 2, 1, "l1").unwrap();
+        assert_eq!(&l1[..4], &[0.75, -0.25, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_pairwise_distances_metrics() {
+        let data = [0.0, 0.0, 3.0, 4.0, 1.0, 0.0];
+        let d = symmetric_pairwise(&data, 3, 2, |a, b| DistanceMetric::Euclidean.distance(a, b), 0.0);
+        assert_eq!(d[1], 5.0);
+        assert_eq!(d[3], 5.0);
+        assert_eq!(d[4], 0.0);
+        assert!((d[5] - 20f64.sqrt()).abs() < 1e-12);
+
+        assert_eq!(DistanceMetric::Manhattan.distance(&data[2..4], &data[4..6]), 6.0);
+        assert!((DistanceMetric::Cosine.distance(&data[2..4], &data[4..6]) - 0.4).abs() < 1e-12);
+        assert_eq!(DistanceMetric::Cosine.distance(&data[0..2], &data[4..6]), 1.0);
+
+        let g = symmetric_pairwise(&data, 3, 2, |a, b| a.iter().zip(b).map(|(x, y)| x * y).sum(), f64::NAN);
+        assert_eq!(g, vec![0.0, 0.0, 0.0, 0.0, 25.0, 3.0, 0.0, 3.0, 1.0]);
+    }
+
+    #[test]
+    fn test_block_helpers() {
+        // 3 x 4 matrix with value 10 * row + col
+        let m: Vec<f64> = (0..12).map(|i| (10 * (i / 4) + i % 4) as f64).collect();
+        assert_eq!(submatrix(&m, 3, 4, 1, 2, 2, 2).unwrap(), vec![12.0, 13.0, 22.0, 23.0]);
+        assert_eq!(submatrix(&m, 3, 4, 2, 0, 1, 4).unwrap(), vec![20.0, 21.0, 22.0, 23.0]);
+
+        let left = submatrix(&m, 3, 4, 0, 0, 3, 1).unwrap();
+        let right = submatrix(&m, 3, 4, 0, 1, 3, 3).unwrap();
+        assert_eq!(hstack(&left, 3, 1, &right, 3, 3).unwrap(), m);
+
+        let top = submatrix(&m, 3, 4, 0, 0, 1, 4).unwrap();
+        let bottom = submatrix(&m, 3, 4, 1, 0, 2, 4).unwrap();
+        assert_eq!(vstack(&top, 1, 4, &bottom, 2, 4).unwrap(), m);
+
+        let r = reshape(&m, 3, 4, 2, 6).unwrap();
+        assert_eq!(submatrix(&r, 2, 6, 1, 0, 1, 2).unwrap(), vec![12.0, 13.0]);
+    }
+
+    #[test]
+    fn test_diagonal_helpers() {
+        assert_eq!(eye(2), vec![1.0, 0.0, 0.0, 1.0]);
+        let d = diag_from(&[2.0, -1.0, 3.0]);
+        assert_eq!(trace(&d, 3).unwrap(), 4.0);
+        assert_eq!(diag_of(&d, 3, 3).unwrap(), vec![2.0, -1.0, 3.0]);
+        // Wide 2 x 3 matrix
+        assert_eq!(diag_of(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3).unwrap(), vec![1.0, 5.0]);
+        assert!(eye(0).is_empty());
+    }
+
+    #[test]
+    fn test_nearest_positive_definite_repairs_indefinite() {
+        // Eigenvalues 3 and -1; the nearest PSD matrix keeps only the 3 * [1 1; 1 1] / 2 part
+        let a = [1.0, 2.0, 2.0, 1.0];
+        assert!(!is_positive_definite(&a, 2));
+        let fixed = nearest_positive_definite(&a, 2).unwrap();
+        assert!(is_positive_definite(&fixed, 2));
+        for v in &fixed {
+            assert!((v - 1.5).abs() < 1e-8);
+        }
+
+        let spd = [4.0, 1.0, 0.5, 1.0, 3.0, 0.2, 0.5, 0.2, 2.0];
+        assert!(is_positive_definite(&spd, 3));
+        for (x, y) in nearest_positive_definite(&spd, 3).unwrap().iter().zip(&spd) {
+            assert!((x - y).abs() < 1e-12);
+        }
+        assert!(!is_positive_definite(&[1.0, 0.5, 0.4, 1.0], 2));
+    }
+
+    #[test]
+    fn test_rank_auto_tolerance_is_scale_invariant() {
+        // Rank-2 3x3 matrix scaled far below the old fixed 1e-10 cutoff
+        let base = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 5.0, 7.0, 9.0];
+        let tiny: Vec<f64> = base.iter().map(|v| v * 1e-12).collect();
+        assert_eq!(rank(&base, 3, 3, -1.0).unwrap(), 2);
+        assert_eq!(rank(&tiny, 3, 3, -1.0).unwrap(), 2);
+        assert_eq!(rank(&tiny, 3, 3, 1e-10).unwrap(), 0);
+
+        // A^+ of a diagonal matrix inverts the entries above the cutoff only
+        let d = [2.0, 0.0, 0.0, 1e-16];
+        assert_eq!(pseudo_inverse(&d, 2, 2, -1.0).unwrap(), vec![0.5, 0.0, 0.0, 0.0]);
+        let full = pseudo_inverse(&d, 2, 2, 0.0).unwrap();
+        assert!((full[3] / 1e16 - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_symmetric_eig_sorted_pairs() {
+        let a = [2.0, -1.0, 0.0, -1.0, 2.0, -1.0, 0.0, -1.0, 2.0];
+        let res = symmetric_eig(&a, 3, true).unwrap();
+        let expected = [2.0 - 2f64.sqrt(), 2.0, 2.0 + 2f64.sqrt()];
+        for (v, e) in res[..3].iter().zip(&expected) {
+            assert!((v - e).abs() < 1e-12);
+        }
+        for (k, x) in res[3..].chunks(3).enumerate() {
+            for (row, xi) in a.chunks(3).zip(x) {
+                let ax: f64 = row.iter().zip(x).map(|(p, q)| p * q).sum();
+                assert!((ax - res[k] * xi).abs() < 1e-12);
+            }
+        }
+        let desc = symmetric_eig(&a, 3, false).unwrap();
+        assert!((desc[0] - expected[2]).abs() < 1e-12);
+        assert_eq!(&desc[3..6], &res[9..12]);
+    }
+
+    #[test]
+    fn test_lu_factor_packed_solves_with_pivoting() {
+        // Zero leading entry forces a row swap
+        let a = [0.0, 2.0, 1.0, 1.0, 1.0, 0.0, 3.0, 0.0, 1.0];
+        let x_true = [1.0, -2.0, 0.5];
+        let b: Vec<f64> = a.chunks(3).map(|row| row.iter().zip(&x_true).map(|(u, v)| u * v).sum()).collect();
+        let (factors, perm) = lu_factor_packed(&a, 3).unwrap();
+        assert_ne!(perm, vec![0.0, 1.0, 2.0]);
+        let x = lu_substitute(&factors, &perm, &b, 3).unwrap();
+        x.iter().zip(&x_true).for_each(|(u, v)| assert!((u - v).abs() < 1e-14));
+        let refined = solve_refined(&a, &b, 3, 2).unwrap();
+        refined.iter().zip(&x_true).for_each(|(u, v)| assert!((u - v).abs() < 1e-14));
+    }
+}
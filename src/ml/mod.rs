@@ -14,15 +14,77 @@ pub fn relu(x: &[f64]) -> Vec<f64> {
 }
 
 /// Softmax activation function - Parallel
+/// Shifts by the maximum so large logits cannot overflow. If every input is `-inf` the result is
+/// uniform; if some are `+inf` they share all the probability mass equally. NaN inputs propagate.
 #[wasm_bindgen]
 pub fn softmax(x: &[f64]) -> Vec<f64> {
     if x.is_empty() { return vec![]; }
     let max = x.par_iter().fold(|| f64::NEG_INFINITY, |a, &b| a.max(b)).reduce(|| f64::NEG_INFINITY, |a, b| a.max(b));
+    if max.is_infinite() {
+        let mut out = x.to_vec();
+        softmax_in_place(&mut out);
+        return out;
+    }
     let exps: Vec<f64> = x.par_iter().map(|&v| (v - max).exp()).collect();
+    // The maximum contributes exp(0) = 1, so the sum cannot underflow to zero
     let sum: f64 = exps.par_iter().sum();
     exps.par_iter().map(|&v| v / sum).collect()
 }
 
+/// Softmax over one axis of a row-major `rows × cols` matrix - Parallel
+/// `axis = 1` normalizes each row (the usual choice for attention scores), `axis = 0` each column.
+#[wasm_bindgen(js_name = softmaxAxis)]
+pub fn softmax_axis(x: &[f64], rows: usize, cols: usize, axis: usize) -> Result<Vec<f64>, JsValue> {
+    if x.len() != rows * cols {
+        return Err(JsValue::from_str("Matrix dimensions do not match data length"));
+    }
+    match axis {
+        1 => {
+            let mut out = x.to_vec();
+            out.par_chunks_mut(cols.max(1)).for_each(softmax_in_place);
+            Ok(out)
+        }
+        0 => {
+            let columns: Vec<Vec<f64>> = (0..cols).into_par_iter().map(|j| {
+                let mut col: Vec<f64> = (0..rows).map(|i| x[i * cols + j]).collect();
+                softmax_in_place(&mut col);
+                col
+            }).collect();
+            let mut out = vec![0.0; x.len()];
+            for (j, col) in columns.iter().enumerate() {
+                for (i, &v) in col.iter().enumerate() {
+                    out[i * cols + j] = v;
+                }
+            }
+            Ok(out)
+        }
+        _ => Err(JsValue::from_str("axis must be 0 or 1")),
+    }
+}
+
+/// Sequential softmax with the same degenerate-input handling as `softmax`.
+fn softmax_in_place(v: &mut [f64]) {
+    if v.is_empty() { return; }
+    let max = v.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        // exp(-inf - -inf) is NaN; with no information every entry is equally likely
+        let p = 1.0 / v.len() as f64;
+        v.iter_mut().for_each(|e| *e = p);
+        return;
+    }
+    if max == f64::INFINITY {
+        let n_inf = v.iter().filter(|&&e| e == f64::INFINITY).count() as f64;
+        v.iter_mut().for_each(|e| *e = if *e == f64::INFINITY { 1.0 / n_inf } else { 0.0 });
+        return;
+    }
+    let mut sum = 0.0;
+    for e in v.iter_mut() {
+        *e = (*e - max).exp();
+        sum += *e;
+    }
+    v.iter_mut().for_each(|e| *e /= sum);
+}
+
 /// Linear (Dense) Layer: y = x @ W + b
 #[wasm_bindgen(js_name = linearLayer)]
 pub fn linear_layer(input: &[f64], weights: &[f64], bias: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
@@ -45,6 +107,28 @@ pub fn linear_layer(input: &[f64], weights: &[f64], bias: &[f64], rows: usize, c
     Ok(output)
 }
 
+/// Batched Linear (Dense) Layer: Y = X @ W + b - Parallel
+/// `inputs` is row-major `batch × in_features` and `weights` is `in_features × out_features`,
+/// the same layout as `linearLayer`. Returns `batch × out_features`.
+#[wasm_bindgen(js_name = linearLayerBatch)]
+pub fn linear_layer_batch(
+    inputs: &[f64], batch: usize, in_features: usize,
+    weights: &[f64], bias: &[f64], out_features: usize
+) -> Result<Vec<f64>, JsValue> {
+    if inputs.len() != batch * in_features {
+        return Err(JsValue::from_str("Input length must be batch * in_features"));
+    }
+    if weights.len() != in_features * out_features || bias.len() != out_features {
+        return Err(JsValue::from_str("Invalid weights or bias dimensions"));
+    }
+
+    let mut output = crate::linalg::matrix_multiply(inputs, batch, in_features, weights, in_features, out_features)?;
+    output.par_chunks_mut(out_features.max(1)).for_each(|row| {
+        row.iter_mut().zip(bias).for_each(|(y, b)| *y += b);
+    });
+    Ok(output)
+}
+
 /// Batch Normalization (Inference mode)
 #[wasm_bindgen(js_name = batchNorm)]
 pub fn batch_norm(x: &[f64], mean: &[f64], var: &[f64], gamma: &[f64], beta: &[f64], epsilon: f64) -> Result<Vec<f64>, JsValue> {
@@ -58,12 +142,39 @@ pub fn batch_norm(x: &[f64], mean: &[f64], var: &[f64], gamma: &[f64], beta: &[f
     }).collect())
 }
 
-/// Dropout (Inference mode = identity, or Training mode with mask)
+/// Layer Normalization: normalizes each row of a `rows × cols` matrix over its features - Parallel
+/// $y = \frac{x - \mu}{\sqrt{\sigma^2 + \epsilon}} \gamma + \beta$ with per-row $\mu$, $\sigma^2$ and per-feature $\gamma$, $\beta$.
+#[wasm_bindgen(js_name = layerNorm)]
+pub fn layer_norm(x: &[f64], rows: usize, cols: usize, gamma: &[f64], beta: &[f64], epsilon: f64) -> Result<Vec<f64>, JsValue> {
+    if x.len() != rows * cols || cols == 0 {
+        return Err(JsValue::from_str("Matrix dimensions do not match data length"));
+    }
+    if gamma.len() != cols || beta.len() != cols {
+        return Err(JsValue::from_str("gamma and beta must have one entry per feature"));
+    }
+
+    let mut out = x.to_vec();
+    out.par_chunks_mut(cols).for_each(|row| {
+        let mean = row.iter().sum::<f64>() / cols as f64;
+        let var = row.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / cols as f64;
+        let inv_std = 1.0 / (var + epsilon).sqrt();
+        for ((v, g), b) in row.iter_mut().zip(gamma).zip(beta) {
+            *v = (*v - mean) * inv_std * g + b;
+        }
+    });
+    Ok(out)
+}
+
+/// Dropout using the inverted-dropout convention
+/// In training mode each element is zeroed with probability `rate` and survivors are scaled by
+/// $\frac{1}{1 - rate}$, so the expected activation is unchanged. Because that scaling already
+/// happens at training time, inference mode (`training = false`) returns the input unchanged.
 #[wasm_bindgen]
-pub fn dropout(x: &[f64], rate: f64, seed: u32) -> Vec<f64> {
-    // In inference mode it's usually identity * (1-rate) if scaling was done during training
-    // Or just identity if scaling is done here.
-    // For this simple implementation, we'll just implement the mask for simulation/training
+pub fn dropout(x: &[f64], rate: f64, seed: u32, training: bool) -> Vec<f64> {
+    if !training {
+        return x.to_vec();
+    }
+
     use rand::prelude::*;
     use rand_chacha::ChaCha8Rng;
     
@@ -110,3 +221,343 @@ pub fn conv2d(
     
     Ok(output)
 }
+
+/// Shuffled row indices for a train/test split.
+///
+/// Returns a permutation of `0..n`: the first `n_test = round(n * test_fraction)` entries are the
+/// test rows and the rest are the training rows. Reproducible for a given `seed`.
+#[wasm_bindgen(js_name = trainTestSplitIndices)]
+pub fn train_test_split_indices(n: usize, test_fraction: f64, seed: u32) -> Result<Vec<u32>, JsValue> {
+    if !(0.0..=1.0).contains(&test_fraction) {
+        return Err(JsValue::from_str("test_fraction must be in [0, 1]"));
+    }
+    Ok(crate::utils::rng::shuffle_indices(n, seed))
+}
+
+/// Fold assignment (`0..k`) for each of `n` rows, with fold sizes differing by at most one.
+/// Reproducible for a given `seed`.
+#[wasm_bindgen(js_name = kfoldIndices)]
+pub fn kfold_indices(n: usize, k: usize, seed: u32) -> Result<Vec<u32>, JsValue> {
+    if k == 0 || k > n {
+        return Err(JsValue::from_str("k must be between 1 and n"));
+    }
+    let mut folds = vec![0u32; n];
+    for (pos, &row) in crate::utils::rng::shuffle_indices(n, seed).iter().enumerate() {
+        folds[row as usize] = (pos % k) as u32;
+    }
+    Ok(folds)
+}
+
+/// Gaussian Process Regression (1D inputs, fixed RBF kernel) - Parallel
+///
+/// Uses $k(x, x') = \exp\left(-\frac{(x - x')^2}{2\ell^2}\right)$ and factors $K + \sigma_n^2 I$
+/// once with Cholesky. Returns interleaved `[mean, variance]` for each query point, where the
+/// variance is that of the latent function (add `noise` for the variance of a new observation).
+#[wasm_bindgen(js_name = gprPredict)]
+pub fn gpr_predict(train_x: &[f64], train_y: &[f64], query_x: &[f64], length_scale: f64, noise: f64) -> Result<Vec<f64>, JsValue> {
+    let n = train_x.len();
+    if train_y.len() != n {
+        return Err(JsValue::from_str("train_x and train_y must have the same length"));
+    }
+    if length_scale <= 0.0 || !length_scale.is_finite() || noise < 0.0 || !noise.is_finite() {
+        return Err(JsValue::from_str("length_scale must be positive and noise non-negative"));
+    }
+
+    let inv_two_l2 = 1.0 / (2.0 * length_scale * length_scale);
+    let kernel = |a: f64, b: f64| (-(a - b) * (a - b) * inv_two_l2).exp();
+
+    let mut k = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..n {
+            k[i * n + j] = kernel(train_x[i], train_x[j]);
+        }
+        k[i * n + i] += noise;
+    }
+    // Column-major lower factor: L[i][j] = l[j * n + i]
+    let l = crate::linalg::cholesky(&k, n)?;
+
+    let forward = |b: &[f64]| -> Vec<f64> {
+        let mut z = vec![0.0; n];
+        for i in 0..n {
+            let s = (0..i).map(|j| l[j * n + i] * z[j]).sum::<f64>();
+            z[i] = (b[i] - s) / l[i * n + i];
+        }
+        z
+    };
+
+    // alpha = L^T \ (L \ y)
+    let mut alpha = forward(train_y);
+    for i in (0..n).rev() {
+        let s = (i + 1..n).map(|j| l[i * n + j] * alpha[j]).sum::<f64>();
+        alpha[i] = (alpha[i] - s) / l[i * n + i];
+    }
+
+    Ok(query_x.par_iter()
+        .with_min_len(64)
+        .flat_map_iter(|&q| {
+            let k_star: Vec<f64> = train_x.iter().map(|&x| kernel(q, x)).collect();
+            let mean = k_star.iter().zip(&alpha).map(|(a, b)| a * b).sum::<f64>();
+            let v = forward(&k_star);
+            let var = (1.0 - v.iter().map(|x| x * x).sum::<f64>()).max(0.0);
+            [mean, var]
+        })
+        .collect())
+}
+
+/// 1D Convolution layer (cross-correlation, no bias) - Parallel
+///
+/// Channel-major layouts as in PyTorch: `input` is `channels × length`, `kernel` is
+/// `out_channels × channels × k_len`, and the result is `out_channels × out_len`.
+/// `padding` is "valid" (no padding) or "same" (zero padding so that
+/// `out_len = ceil(length / stride)`, split evenly with any extra on the right).
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = conv1d)]
+pub fn conv1d(
+    input: &[f64], length: usize, channels: usize,
+    kernel: &[f64], k_len: usize, out_channels: usize,
+    stride: usize, padding: &str
+) -> Result<Vec<f64>, JsValue> {
+    if input.len() != channels * length {
+        return Err(JsValue::from_str("Input length must be channels * length"));
+    }
+    if kernel.len() != out_channels * channels * k_len || k_len == 0 {
+        return Err(JsValue::from_str("Kernel length must be out_channels * channels * k_len"));
+    }
+    if stride == 0 {
+        return Err(JsValue::from_str("stride must be at least 1"));
+    }
+
+    let (out_len, pad_left) = match padding {
+        "valid" => {
+            if length < k_len {
+                return Err(JsValue::from_str("Input must be larger than kernel"));
+            }
+            ((length - k_len) / stride + 1, 0)
+        }
+        "same" => {
+            let out_len = length.div_ceil(stride);
+            let total = ((out_len.max(1) - 1) * stride + k_len).saturating_sub(length);
+            (out_len, total / 2)
+        }
+        _ => return Err(JsValue::from_str("Padding must be \"same\" or \"valid\"")),
+    };
+
+    let mut output = vec![0.0; out_channels * out_len];
+    output.par_iter_mut().enumerate().for_each(|(idx, val)| {
+        let oc = idx / out_len;
+        let start = (idx % out_len) * stride;
+
+        let mut sum = 0.0;
+        for c in 0..channels {
+            let signal = &input[c * length..(c + 1) * length];
+            let taps = &kernel[(oc * channels + c) * k_len..(oc * channels + c + 1) * k_len];
+            for (k, &w) in taps.iter().enumerate() {
+                // Position in the unpadded signal; padded positions contribute zero
+                let pos = start + k;
+                if pos >= pad_left && pos - pad_left < length {
+                    sum += signal[pos - pad_left] * w;
+                }
+            }
+        }
+        *val = sum;
+    });
+
+    Ok(output)
+}
+
+/// Logistic Regression training by full-batch gradient descent - Parallel
+///
+/// Minimizes the mean binary cross-entropy plus $\frac{\lambda}{2}\|w\|^2$ (the bias is not
+/// regularized). `x` is row-major `rows × cols`, `y` holds labels in $[0, 1]$.
+/// Returns the learned weights followed by the bias: `[w_1, ..., w_cols, b]`.
+#[wasm_bindgen(js_name = logisticRegressionFit)]
+pub fn logistic_regression_fit(x: &[f64], y: &[f64], rows: usize, cols: usize, lr: f64, iters: usize, l2: f64) -> Result<Vec<f64>, JsValue> {
+    if x.len() != rows * cols || y.len() != rows || rows == 0 || cols == 0 {
+        return Err(JsValue::from_str("Invalid dimensions for logistic regression"));
+    }
+    if y.iter().any(|&v| !(0.0..=1.0).contains(&v)) {
+        return Err(JsValue::from_str("Labels must be in [0, 1]"));
+    }
+    if lr <= 0.0 || !lr.is_finite() || l2 < 0.0 || !l2.is_finite() {
+        return Err(JsValue::from_str("lr must be positive and l2 non-negative"));
+    }
+
+    let mut params = vec![0.0; cols + 1];
+    for _ in 0..iters {
+        let p = sigmoid(&logits(x, &params, cols));
+        // Gradient of the mean cross-entropy: X^T (p - y) / rows, and mean(p - y) for the bias
+        let grad = x.par_chunks(cols)
+            .zip(p.par_iter().zip(y.par_iter()))
+            .with_min_len(1024)
+            .fold(|| vec![0.0; cols + 1], |mut g, (row, (&pi, &yi))| {
+                let err = pi - yi;
+                for (gj, &xj) in g.iter_mut().zip(row) {
+                    *gj += err * xj;
+                }
+                g[cols] += err;
+                g
+            })
+            .reduce(|| vec![0.0; cols + 1], |mut a, b| {
+                a.iter_mut().zip(&b).for_each(|(u, v)| *u += v);
+                a
+            });
+
+        let inv_n = 1.0 / rows as f64;
+        for j in 0..cols {
+            params[j] -= lr * (grad[j] * inv_n + l2 * params[j]);
+        }
+        params[cols] -= lr * grad[cols] * inv_n;
+    }
+    Ok(params)
+}
+
+/// Class-1 probabilities for the row-major `rows × cols` matrix `x`, given the
+/// `[w_1, ..., w_cols, b]` vector returned by `logisticRegressionFit` - Parallel
+#[wasm_bindgen(js_name = logisticRegressionPredict)]
+pub fn logistic_regression_predict(weights: &[f64], x: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    if x.len() != rows * cols || weights.len() != cols + 1 || cols == 0 {
+        return Err(JsValue::from_str("Invalid dimensions for logistic regression"));
+    }
+    Ok(sigmoid(&logits(x, weights, cols)))
+}
+
+/// Linear scores $Xw + b$ for `params = [w..., b]`.
+fn logits(x: &[f64], params: &[f64], cols: usize) -> Vec<f64> {
+    let (w, b) = params.split_at(cols);
+    x.par_chunks(cols)
+        .with_min_len(1024)
+        .map(|row| row.iter().zip(w).map(|(a, c)| a * c).sum::<f64>() + b[0])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpr_interpolates_with_shrinking_variance() {
+        let train_x: Vec<f64> = (0..10).map(|i| i as f64 * 0.5).collect();
+        let train_y: Vec<f64> = train_x.iter().map(|x| x.sin()).collect();
+        let out = gpr_predict(&train_x, &train_y, &[1.0, 1.25, 50.0], 1.0, 1e-6).unwrap();
+
+        assert!((out[0] - 1f64.sin()).abs() < 1e-3);
+        assert!(out[1] < 1e-4);
+        assert!((out[2] - 1.25f64.sin()).abs() < 1e-3);
+        // Far from the data the prior takes over: zero mean, unit variance
+        assert!(out[4].abs() < 1e-9);
+        assert!((out[5] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_logistic_regression_separates_classes() {
+        // Class 1 when x0 + x1 > 1
+        let pts = [[0.0, 0.0], [0.2, 0.3], [0.4, 0.1], [0.1, 0.6], [1.0, 0.9], [0.8, 1.2], [1.5, 0.4], [0.9, 0.8]];
+        let x: Vec<f64> = pts.iter().flatten().copied().collect();
+        let y = [0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0];
+
+        let w = logistic_regression_fit(&x, &y, 8, 2, 1.0, 2000, 0.0).unwrap();
+        assert_eq!(w.len(), 3);
+        let p = logistic_regression_predict(&w, &x, 8, 2).unwrap();
+        for (pi, yi) in p.iter().zip(&y) {
+            assert_eq!((*pi > 0.5) as u8 as f64, *yi);
+        }
+
+        // L2 shrinks the weights
+        let shrunk = logistic_regression_fit(&x, &y, 8, 2, 1.0, 2000, 0.5).unwrap();
+        assert!(shrunk[0].abs() < w[0].abs());
+    }
+
+    #[test]
+    fn test_splits_are_reproducible_permutations() {
+        let a = train_test_split_indices(50, 0.2, 9).unwrap();
+        assert_eq!(a, train_test_split_indices(50, 0.2, 9).unwrap());
+        assert_ne!(a, train_test_split_indices(50, 0.2, 10).unwrap());
+        let mut sorted = a.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..50).collect::<Vec<u32>>());
+
+        let folds = kfold_indices(23, 5, 1).unwrap();
+        let mut sizes = [0; 5];
+        folds.iter().for_each(|&f| sizes[f as usize] += 1);
+        assert_eq!(sizes.iter().max().unwrap() - sizes.iter().min().unwrap(), 1);
+        assert_eq!(sizes.iter().sum::<usize>(), 23);
+    }
+
+    #[test]
+    fn test_linear_layer_batch_matches_single() {
+        let weights = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let bias = [0.5, -1.0];
+        let inputs = [1.0, 0.0, -1.0, 2.0, 1.0, 0.5];
+        let batched = linear_layer_batch(&inputs, 2, 3, &weights, &bias, 2).unwrap();
+        for (sample, expected) in inputs.chunks(3).zip(batched.chunks(2)) {
+            assert_eq!(linear_layer(sample, &weights, &bias, 3, 2).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_conv1d_padding_and_channels() {
+        // Two input channels, one output channel summing a 3-tap box over both
+        let input = [1.0, 2.0, 3.0, 4.0, 5.0, 10.0, 10.0, 10.0, 10.0, 10.0];
+        let kernel = [1.0; 6];
+
+        let valid = conv1d(&input, 5, 2, &kernel, 3, 1, 1, "valid").unwrap();
+        assert_eq!(valid, vec![36.0, 39.0, 42.0]);
+
+        let same = conv1d(&input, 5, 2, &kernel, 3, 1, 1, "same").unwrap();
+        assert_eq!(same, vec![23.0, 36.0, 39.0, 42.0, 29.0]);
+
+        let strided = conv1d(&input, 5, 2, &kernel, 3, 1, 2, "same").unwrap();
+        assert_eq!(strided, vec![23.0, 39.0, 29.0]);
+    }
+
+    #[test]
+    fn test_layer_norm_and_softmax_axis() {
+        let x = [1.0, 2.0, 3.0, 10.0, 20.0, 30.0];
+        let y = layer_norm(&x, 2, 3, &[1.0; 3], &[0.0; 3], 0.0).unwrap();
+        // Both rows standardize to the same values
+        for j in 0..3 {
+            assert!((y[j] - y[3 + j]).abs() < 1e-12);
+        }
+        assert!((y[2] - 1.5f64.sqrt()).abs() < 1e-12);
+
+        let rows = softmax_axis(&x, 2, 3, 1).unwrap();
+        assert_eq!(&rows[..3], &softmax(&x[..3])[..]);
+        let cols = softmax_axis(&x, 2, 3, 0).unwrap();
+        for j in 0..3 {
+            assert!((cols[j] + cols[3 + j] - 1.0).abs() < 1e-12);
+        }
+        assert!((cols[0] - 1.0 / (1.0 + 9f64.exp())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_softmax_large_and_degenerate_inputs() {
+        // Reference: exp([-2, -1, 0]) / sum, computed independently of the shift
+        let reference = [0.090_030_573_170_380_46, 0.244_728_471_054_797_67, 0.665_240_955_774_821_9];
+        for (p, r) in softmax(&[1000.0, 1001.0, 1002.0]).iter().zip(&reference) {
+            assert!((p - r).abs() < 1e-15);
+        }
+        for (p, r) in softmax(&[-1002.0, -1001.0, -1000.0]).iter().zip(&reference) {
+            assert!((p - r).abs() < 1e-15);
+        }
+
+        assert_eq!(softmax(&[42.0]), vec![1.0]);
+        assert_eq!(softmax(&[f64::NEG_INFINITY; 4]), vec![0.25; 4]);
+        assert_eq!(softmax(&[f64::INFINITY, 0.0, f64::INFINITY]), vec![0.5, 0.0, 0.5]);
+        assert_eq!(softmax(&[f64::NEG_INFINITY, 0.0]), vec![0.0, 1.0]);
+        assert_eq!(
+            softmax_axis(&[f64::NEG_INFINITY, 1.0, f64::NEG_INFINITY, 1.0], 2, 2, 0).unwrap(),
+            vec![0.5, 0.5, 0.5, 0.5]
+        );
+    }
+
+    #[test]
+    fn test_dropout_is_identity_at_inference() {
+        let x: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        assert_eq!(dropout(&x, 0.5, 1, false), x);
+
+        let train = dropout(&x, 0.5, 1, true);
+        assert_eq!(train, dropout(&x, 0.5, 1, true));
+        assert!(train.iter().zip(&x).all(|(t, v)| *t == 0.0 || *t == 2.0 * v));
+        assert!(train.iter().skip(1).any(|&t| t == 0.0));
+    }
+}
@@ -1,10 +1,23 @@
 use wasm_bindgen::prelude::*;
 use rayon::prelude::*;
+use crate::utils::AbortFlag;
 
 /// Nelder-Mead (Downhill Simplex) Optimization
 /// Finds the minimum of function `f(x)` starting from `x0`.
+/// Setting `abort` stops after the current iteration and returns the best vertex so far.
 #[wasm_bindgen]
-pub fn minimize_nelder_mead(f: &js_sys::Function, x0: &[f64], tol: f64, max_iters: usize) -> Result<Vec<f64>, JsValue> {
+pub fn minimize_nelder_mead(f: &js_sys::Function, x0: &[f64], tol: f64, max_iters: usize, abort: Option<AbortFlag>) -> Result<Vec<f64>, JsValue> {
+    run_nelder_mead(|x| call_f(f, x), x0, tol, max_iters, abort.as_ref())
+}
+
+/// Nelder-Mead core shared by the JS entry point and Rust callers (e.g. segmented regression).
+pub(crate) fn run_nelder_mead(
+    mut eval: impl FnMut(&[f64]) -> Result<f64, JsValue>,
+    x0: &[f64],
+    tol: f64,
+    max_iters: usize,
+    abort: Option<&AbortFlag>
+) -> Result<Vec<f64>, JsValue> {
     let n = x0.len();
     if n == 0 { return Ok(vec![]); }
     
@@ -20,13 +33,12 @@ pub fn minimize_nelder_mead(f: &js_sys::Function, x0: &[f64], tol: f64, max_iter
     
     let mut values = vec![0.0; n + 1];
     for i in 0..(n+1) {
-        let args = js_sys::Array::new();
-        for &v in &simplex[i] { args.push(&JsValue::from_f64(v)); }
-        values[i] = f.apply(&JsValue::NULL, &args)?
-            .as_f64().ok_or_else(|| JsValue::from_str("Minimize: Function must return a number"))?;
+        values[i] = eval(&simplex[i])?;
     }
     
     for _ in 0..max_iters {
+        if abort.is_some_and(|a| a.aborted()) { break; }
+
         // Sort simplex by values
         let mut indices: Vec<usize> = (0..(n+1)).collect();
         indices.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap());
@@ -48,7 +60,7 @@ pub fn minimize_nelder_mead(f: &js_sys::Function, x0: &[f64], tol: f64, max_iter
         
         // Reflection
         let reflected = reflect(&centroid, &simplex[worst_idx], 1.0);
-        let rf_val = call_f(f, &reflected)?;
+        let rf_val = eval(&reflected)?;
         
         if rf_val < values[indices[n-1]] && rf_val >= best_val {
             values[worst_idx] = rf_val;
@@ -56,7 +68,7 @@ pub fn minimize_nelder_mead(f: &js_sys::Function, x0: &[f64], tol: f64, max_iter
         } else if rf_val < best_val {
             // Expansion
             let expanded = reflect(&centroid, &simplex[worst_idx], 2.0);
-            let ex_val = call_f(f, &expanded)?;
+            let ex_val = eval(&expanded)?;
             if ex_val < rf_val {
                 values[worst_idx] = ex_val;
                 simplex[worst_idx] = expanded;
@@ -67,7 +79,7 @@ pub fn minimize_nelder_mead(f: &js_sys::Function, x0: &[f64], tol: f64, max_iter
         } else {
             // Contraction
             let contracted = reflect(&centroid, &simplex[worst_idx], 0.5);
-            let ct_val = call_f(f, &contracted)?;
+            let ct_val = eval(&contracted)?;
             if ct_val < worst_val {
                 values[worst_idx] = ct_val;
                 simplex[worst_idx] = contracted;
@@ -79,7 +91,7 @@ pub fn minimize_nelder_mead(f: &js_sys::Function, x0: &[f64], tol: f64, max_iter
                     for j in 0..n {
                         simplex[idx][j] = best[j] + 0.5 * (simplex[idx][j] - best[j]);
                     }
-                    values[idx] = call_f(f, &simplex[idx])?;
+                    values[idx] = eval(&simplex[idx])?;
                 }
             }
         }
@@ -108,6 +120,7 @@ fn call_f(f: &js_sys::Function, x: &[f64]) -> Result<f64, JsValue> {
 }
 
 /// Least Squares Solver for Ax = b
+/// Rank-deficient and underdetermined systems get the minimum-norm solution.
 #[wasm_bindgen]
 pub fn least_squares(a: &[f64], b: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
     use nalgebra::DMatrix;
@@ -118,13 +131,249 @@ pub fn least_squares(a: &[f64], b: &[f64], rows: usize, cols: usize) -> Result<V
     let ma = DMatrix::from_row_slice(rows, cols, a);
     let vb = nalgebra::DVector::from_row_slice(b);
     
-    if let Some(res) = ma.qr().solve(&vb) {
+    if let Some(res) = qr_solve(ma, &vb) {
         Ok(res.as_slice().to_vec())
     } else {
         Err(JsValue::from_str("Failed to solve least squares system"))
     }
 }
 
+/// Least squares solution followed by the residual norm: `[x_1, ..., x_cols, ||Ax - b||]`.
+#[wasm_bindgen(js_name = leastSquaresResidual)]
+pub fn least_squares_residual(a: &[f64], b: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    let mut x = least_squares(a, b, rows, cols)?;
+    let residual = a.par_chunks(cols.max(1))
+        .zip(b.par_iter())
+        .with_min_len(1024)
+        .map(|(row, &bi)| {
+            let r = row.iter().zip(&x).map(|(aij, xj)| aij * xj).sum::<f64>() - bi;
+            r * r
+        })
+        .sum::<f64>()
+        .sqrt();
+    x.push(residual);
+    Ok(x)
+}
+
+/// Least-squares solution of a (possibly overdetermined) system via thin QR: $R x = Q^T b$.
+/// `QR::solve` only accepts square systems, so the triangular solve is done here.
+/// When `R` has a (near-)zero diagonal, or there are fewer rows than columns, the
+/// minimum-norm solution is taken from the SVD instead.
+fn qr_solve(a: nalgebra::DMatrix<f64>, b: &nalgebra::DVector<f64>) -> Option<nalgebra::DVector<f64>> {
+    let scale = a.nrows().max(a.ncols()) as f64 * f64::EPSILON;
+    if a.nrows() >= a.ncols() {
+        let qr = a.clone().qr();
+        let r = qr.r();
+        let max_diag = r.diagonal().amax();
+        if max_diag > 0.0 && r.diagonal().iter().all(|d| d.abs() > scale * max_diag) {
+            let qtb = qr.q().transpose() * b;
+            return r.solve_upper_triangular(&qtb);
+        }
+    }
+    let svd = a.svd(true, true);
+    let eps = scale * svd.singular_values.max();
+    svd.solve(b, eps).ok()
+}
+
+/// Weighted Least Squares Solver for Ax = b
+/// Minimizes $\sum_i w_i (A_i x - b_i)^2$ by scaling each row of `A` and `b` by $\sqrt{w_i}$
+/// before the QR solve.
+#[wasm_bindgen(js_name = weightedLeastSquares)]
+pub fn weighted_least_squares(a: &[f64], b: &[f64], weights: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    if a.len() != rows * cols || b.len() != rows {
+        return Err(JsValue::from_str("Invalid dimensions for least squares"));
+    }
+    if weights.len() != rows {
+        return Err(JsValue::from_str("weights must have one entry per row"));
+    }
+    if weights.iter().any(|&w| w < 0.0 || !w.is_finite()) {
+        return Err(JsValue::from_str("weights must be finite and non-negative"));
+    }
+
+    let sqrt_w: Vec<f64> = weights.iter().map(|w| w.sqrt()).collect();
+    let scaled_a: Vec<f64> = a.par_chunks(cols.max(1))
+        .zip(sqrt_w.par_iter())
+        .flat_map_iter(|(row, &sw)| row.iter().map(move |v| v * sw))
+        .collect();
+    let scaled_b: Vec<f64> = b.iter().zip(&sqrt_w).map(|(v, sw)| v * sw).collect();
+    least_squares(&scaled_a, &scaled_b, rows, cols)
+}
+
+/// Ridge (Tikhonov) Regression
+/// Solves $(A^T A + \lambda I) x = A^T b$, i.e. minimizes $\|Ax - b\|^2 + \lambda \|x\|^2$.
+#[wasm_bindgen(js_name = ridgeRegression)]
+pub fn ridge_regression(a: &[f64], b: &[f64], rows: usize, cols: usize, lambda: f64) -> Result<Vec<f64>, JsValue> {
+    if a.len() != rows * cols || b.len() != rows || cols == 0 {
+        return Err(JsValue::from_str("Invalid dimensions for ridge regression"));
+    }
+    if lambda < 0.0 || !lambda.is_finite() {
+        return Err(JsValue::from_str("lambda must be finite and non-negative"));
+    }
+    let (mut ata, atb) = normal_equations(a, b, rows, cols);
+    for j in 0..cols {
+        ata[j * cols + j] += lambda;
+    }
+    crate::linalg::solve_linear_system(&ata, &atb, cols)
+}
+
+/// Lasso Regression via cyclic coordinate descent
+/// Minimizes $\frac{1}{2}\|Ax - b\|^2 + \lambda \|x\|_1$ using soft-thresholding updates,
+/// stopping after `iters` sweeps or once no coefficient moves by more than `1e-10`.
+#[wasm_bindgen(js_name = lassoRegression)]
+pub fn lasso_regression(a: &[f64], b: &[f64], rows: usize, cols: usize, lambda: f64, iters: usize) -> Result<Vec<f64>, JsValue> {
+    if a.len() != rows * cols || b.len() != rows || cols == 0 {
+        return Err(JsValue::from_str("Invalid dimensions for lasso regression"));
+    }
+    if lambda < 0.0 || !lambda.is_finite() {
+        return Err(JsValue::from_str("lambda must be finite and non-negative"));
+    }
+
+    let col_sq: Vec<f64> = (0..cols).into_par_iter()
+        .map(|j| (0..rows).map(|i| a[i * cols + j].powi(2)).sum())
+        .collect();
+    let mut x = vec![0.0; cols];
+    let mut residual = b.to_vec();
+
+    for _ in 0..iters {
+        let mut max_step: f64 = 0.0;
+        for j in 0..cols {
+            if col_sq[j] == 0.0 { continue; }
+            // Correlation of column j with the partial residual that excludes x_j
+            let rho: f64 = (0..rows).map(|i| a[i * cols + j] * residual[i]).sum::<f64>() + col_sq[j] * x[j];
+            let new_xj = rho.signum() * (rho.abs() - lambda).max(0.0) / col_sq[j];
+            let delta = new_xj - x[j];
+            if delta != 0.0 {
+                for i in 0..rows {
+                    residual[i] -= a[i * cols + j] * delta;
+                }
+                x[j] = new_xj;
+                max_step = max_step.max(delta.abs());
+            }
+        }
+        if max_step < 1e-10 { break; }
+    }
+    Ok(x)
+}
+
+/// Returns $A^T A$ (row-major `cols × cols`) and $A^T b$ - Parallel
+fn normal_equations(a: &[f64], b: &[f64], rows: usize, cols: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut ata = vec![0.0; cols * cols];
+    ata.par_chunks_mut(cols).enumerate().for_each(|(j, row)| {
+        for (k, v) in row.iter_mut().enumerate() {
+            *v = (0..rows).map(|i| a[i * cols + j] * a[i * cols + k]).sum();
+        }
+    });
+    let atb = (0..cols).into_par_iter()
+        .map(|j| (0..rows).map(|i| a[i * cols + j] * b[i]).sum())
+        .collect();
+    (ata, atb)
+}
+
+/// Bounded Levenberg-Marquardt for nonlinear least squares
+/// Minimizes $\frac{1}{2}\sum_i r_i(p)^2$ where `residual(params)` returns the residual vector.
+///
+/// Uses forward-difference Jacobians and Marquardt diagonal damping; every trial step is
+/// projected onto the box `[lower, upper]` (use `±Infinity` for unbounded parameters).
+/// Returns the optimized parameters.
+#[wasm_bindgen(js_name = levenbergMarquardt)]
+pub fn levenberg_marquardt(
+    residual: &js_sys::Function,
+    x0: &[f64],
+    lower: &[f64],
+    upper: &[f64],
+    max_iters: usize
+) -> Result<Vec<f64>, JsValue> {
+    run_levenberg_marquardt(|p| {
+        let p_js = unsafe { js_sys::Float64Array::view(p) };
+        let out = residual.call1(&JsValue::NULL, &p_js)?;
+        Ok(js_sys::Float64Array::new(&out).to_vec())
+    }, x0, lower, upper, max_iters)
+}
+
+pub(crate) fn run_levenberg_marquardt(
+    mut residual: impl FnMut(&[f64]) -> Result<Vec<f64>, JsValue>,
+    x0: &[f64],
+    lower: &[f64],
+    upper: &[f64],
+    max_iters: usize
+) -> Result<Vec<f64>, JsValue> {
+    let n = x0.len();
+    if lower.len() != n || upper.len() != n {
+        return Err(JsValue::from_str("lower and upper must have the same length as x0"));
+    }
+    if lower.iter().zip(upper).any(|(lo, hi)| lo.is_nan() || hi.is_nan() || lo > hi) {
+        return Err(JsValue::from_str("Bounds must not be NaN and each lower bound must not exceed its upper bound"));
+    }
+    if n == 0 { return Ok(vec![]); }
+
+    let project = |p: &mut [f64]| {
+        for j in 0..n { p[j] = p[j].clamp(lower[j], upper[j]); }
+    };
+    let cost = |r: &[f64]| r.iter().map(|v| v * v).sum::<f64>();
+
+    let mut p = x0.to_vec();
+    project(&mut p);
+    let mut r = residual(&p)?;
+    let m = r.len();
+    let mut current = cost(&r);
+    let mut lambda = 1e-3;
+
+    for _ in 0..max_iters {
+        // Forward-difference Jacobian (m x n, column-major), stepping inward at upper bounds
+        let mut jac = vec![0.0; m * n];
+        for j in 0..n {
+            let mut h = 1e-7 * p[j].abs().max(1.0);
+            if p[j] + h > upper[j] { h = -h; }
+            let mut shifted = p.clone();
+            shifted[j] += h;
+            let r_shift = residual(&shifted)?;
+            if r_shift.len() != m {
+                return Err(JsValue::from_str("Residual length changed between calls"));
+            }
+            for i in 0..m { jac[j * m + i] = (r_shift[i] - r[i]) / h; }
+        }
+
+        let mut jtj = vec![0.0; n * n];
+        let mut jtr = vec![0.0; n];
+        for a in 0..n {
+            let col_a = &jac[a * m..(a + 1) * m];
+            jtr[a] = -col_a.iter().zip(&r).map(|(j, ri)| j * ri).sum::<f64>();
+            for b in a..n {
+                let v: f64 = col_a.iter().zip(&jac[b * m..(b + 1) * m]).map(|(x, y)| x * y).sum();
+                jtj[a * n + b] = v;
+                jtj[b * n + a] = v;
+            }
+        }
+
+        // Increase damping until a step lowers the cost (or damping saturates)
+        let mut improved = false;
+        while lambda < 1e16 {
+            let mut damped = jtj.clone();
+            for j in 0..n { damped[j * n + j] += lambda * jtj[j * n + j].max(1e-12); }
+
+            if let Ok(delta) = crate::linalg::solve_linear_system(&damped, &jtr, n) {
+                let mut trial: Vec<f64> = p.iter().zip(&delta).map(|(a, d)| a + d).collect();
+                project(&mut trial);
+                let r_trial = residual(&trial)?;
+                let trial_cost = cost(&r_trial);
+                if trial_cost < current {
+                    let step: f64 = trial.iter().zip(&p).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+                    let gain = current - trial_cost;
+                    p = trial;
+                    r = r_trial;
+                    current = trial_cost;
+                    lambda = (lambda / 10.0).max(1e-12);
+                    improved = gain > 1e-14 * current.max(1e-300) && step > 1e-14;
+                    break;
+                }
+            }
+            lambda *= 10.0;
+        }
+        if !improved { break; }
+    }
+    Ok(p)
+}
+
 /// Constrained Optimization using Penalty Method
 /// f: objective function, constraints: list of functions that must be >= 0
 #[wasm_bindgen]
@@ -159,32 +408,61 @@ pub fn constrained_optimize(
 
 /// Simple Genetic Algorithm for optimization.
 /// bounds: flattened [min1, max1, min2, max2, ...]
+///
+/// `mutation_rate` is the per-gene probability: every gene of every non-elite individual is
+/// independently resampled within its bounds with that probability. (Earlier versions mutated
+/// at most one gene per individual, so high-dimensional problems barely mutated.)
+///
+/// Runs are reproducible: the same `seed` and inputs give the same result regardless of the
+/// number of threads, since parallel steps draw from per-individual generators derived from it.
+///
+/// `progress`, if given, is called with the completed fraction of generations in [0, 1].
+/// Setting `abort` stops after the current generation and returns the best individual so far.
+#[allow(clippy::too_many_arguments)]
 #[wasm_bindgen]
 pub fn genetic_algorithm(
     f: &js_sys::Function,
     bounds: &[f64],
     pop_size: usize,
     generations: usize,
-    mutation_rate: f64
+    mutation_rate: f64,
+    seed: u32,
+    progress: Option<js_sys::Function>,
+    abort: Option<AbortFlag>
+) -> Result<Vec<f64>, JsValue> {
+    let control = crate::utils::iteration_control(progress.as_ref(), abort.as_ref(), generations);
+    run_genetic_algorithm(|x| call_f(f, x), control, bounds, pop_size, generations, mutation_rate, seed)
+}
+
+/// `on_generation(completed)` runs after each generation; returning `Ok(false)` stops early.
+fn run_genetic_algorithm(
+    mut eval: impl FnMut(&[f64]) -> Result<f64, JsValue>,
+    mut on_generation: impl FnMut(usize) -> Result<bool, JsValue>,
+    bounds: &[f64],
+    pop_size: usize,
+    generations: usize,
+    mutation_rate: f64,
+    seed: u32
 ) -> Result<Vec<f64>, JsValue> {
     use rand::prelude::*;
+    use rand_chacha::ChaCha8Rng;
     
     let dim = bounds.len() / 2;
-    if dim == 0 { return Ok(vec![]); }
+    if dim == 0 || pop_size == 0 { return Ok(vec![]); }
     
-    let mut rng = rand::thread_rng();
+    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
     let mut population: Vec<Vec<f64>> = (0..pop_size).map(|_| {
         (0..dim).map(|i| rng.gen_range(bounds[2*i]..bounds[2*i+1])).collect()
     }).collect();
     
     let mut best_sol = population[0].clone();
-    let mut best_score = call_f(f, &best_sol)?;
+    let mut best_score = eval(&best_sol)?;
     
-    for _ in 0..generations {
+    for generation in 0..generations {
         // Evaluate
         let mut scores = Vec::with_capacity(pop_size);
         for ind in &population {
-            let s = call_f(f, ind)?;
+            let s = eval(ind)?;
             scores.push(s);
             if s < best_score {
                 best_score = s;
@@ -197,7 +475,7 @@ pub fn genetic_algorithm(
         let mut next_gen: Vec<Vec<f64>> = (0..pop_size).into_par_iter().map(|i| {
             if i == 0 { return best_sol.clone(); } // Elitism
             
-            let mut local_rng = rand::thread_rng();
+            let mut local_rng = sub_rng(seed, generation, i, 0);
             let mut winner_idx = local_rng.gen_range(0..pop_size);
             for _ in 1..tournament_size {
                 let contender = local_rng.gen_range(0..pop_size);
@@ -222,16 +500,252 @@ pub fn genetic_algorithm(
         }
         
         // 4. Mutation (Parallel)
-        next_gen.par_iter_mut().skip(1).for_each(|ind| {
-            let mut local_rng = rand::thread_rng();
-            if local_rng.gen_bool(mutation_rate) {
-                let idx = local_rng.gen_range(0..dim);
-                ind[idx] = local_rng.gen_range(bounds[2*idx]..bounds[2*idx+1]);
-            }
+        next_gen.par_iter_mut().enumerate().skip(1).for_each(|(i, ind)| {
+            mutate(ind, bounds, mutation_rate, &mut sub_rng(seed, generation, i, 1));
         });
         
         population = next_gen;
+        if !on_generation(generation + 1)? { break; }
     }
     
     Ok(best_sol)
 }
+
+/// Upper limit on the number of points evaluated by `grid_search`.
+const MAX_GRID_EVALUATIONS: usize = 1_000_000;
+
+/// Exhaustive search over a regular grid inside a box.
+/// bounds: flattened [min1, max1, min2, max2, ...]; steps: grid points per dimension
+/// (a single step samples the midpoint).
+///
+/// Grid points are generated in parallel, then `f` is evaluated on each on the calling thread.
+/// Returns the best point followed by its value: `[x1, ..., xn, f(x)]`. Fails if the grid
+/// would exceed 1,000,000 evaluations.
+#[wasm_bindgen(js_name = gridSearch)]
+pub fn grid_search(f: &js_sys::Function, bounds: &[f64], steps: &[usize]) -> Result<Vec<f64>, JsValue> {
+    let dim = bounds.len() / 2;
+    if dim == 0 || bounds.len() % 2 != 0 || steps.len() != dim {
+        return Err(JsValue::from_str("bounds must hold a [min, max] pair for every entry of steps"));
+    }
+    if steps.contains(&0) {
+        return Err(JsValue::from_str("Each dimension needs at least one step"));
+    }
+    let total = steps.iter().try_fold(1usize, |acc, &s| acc.checked_mul(s))
+        .filter(|&t| t <= MAX_GRID_EVALUATIONS)
+        .ok_or_else(|| JsValue::from_str("Grid too large: at most 1,000,000 evaluations"))?;
+
+    best_of(grid_points(bounds, steps, total), |x| call_f(f, x))
+}
+
+/// Cartesian product of per-dimension linspaces, first dimension varying fastest - Parallel
+fn grid_points(bounds: &[f64], steps: &[usize], total: usize) -> Vec<Vec<f64>> {
+    (0..total).into_par_iter()
+        .with_min_len(1024)
+        .map(|mut flat| {
+            steps.iter().enumerate().map(|(d, &n)| {
+                let k = flat % n;
+                flat /= n;
+                let (lo, hi) = (bounds[2 * d], bounds[2 * d + 1]);
+                if n == 1 { 0.5 * (lo + hi) } else { lo + (hi - lo) * k as f64 / (n - 1) as f64 }
+            }).collect()
+        })
+        .collect()
+}
+
+/// Random search with `n_samples` uniform points inside a box.
+/// bounds: flattened [min1, max1, min2, max2, ...]
+///
+/// Returns the best point followed by its value: `[x1, ..., xn, f(x)]`. The same `seed`
+/// always draws the same points.
+#[wasm_bindgen(js_name = randomSearch)]
+pub fn random_search(f: &js_sys::Function, bounds: &[f64], n_samples: usize, seed: u32) -> Result<Vec<f64>, JsValue> {
+    use rand::prelude::*;
+    use rand_chacha::ChaCha8Rng;
+
+    let dim = bounds.len() / 2;
+    if dim == 0 || bounds.len() % 2 != 0 {
+        return Err(JsValue::from_str("bounds must be flattened [min, max] pairs"));
+    }
+    if n_samples == 0 {
+        return Err(JsValue::from_str("n_samples must be positive"));
+    }
+    if bounds.chunks_exact(2).any(|b| !(b[0] <= b[1] && (b[1] - b[0]).is_finite())) {
+        return Err(JsValue::from_str("Each bound must be finite with min <= max"));
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+    let points: Vec<Vec<f64>> = (0..n_samples)
+        .map(|_| (0..dim).map(|d| rng.gen_range(bounds[2 * d]..=bounds[2 * d + 1])).collect())
+        .collect();
+    best_of(points, |x| call_f(f, x))
+}
+
+/// Evaluates every point and returns the lowest-scoring one with its value appended.
+fn best_of(
+    points: Vec<Vec<f64>>,
+    mut eval: impl FnMut(&[f64]) -> Result<f64, JsValue>
+) -> Result<Vec<f64>, JsValue> {
+    let mut best: Option<(Vec<f64>, f64)> = None;
+    for p in points {
+        let v = eval(&p)?;
+        if best.as_ref().is_none_or(|(_, b)| v < *b || b.is_nan()) {
+            best = Some((p, v));
+        }
+    }
+    let (mut point, value) = best.ok_or_else(|| JsValue::from_str("No points to evaluate"))?;
+    point.push(value);
+    Ok(point)
+}
+
+/// Resamples each gene uniformly within its bounds with probability `rate`.
+fn mutate(ind: &mut [f64], bounds: &[f64], rate: f64, rng: &mut impl rand::Rng) {
+    for (idx, gene) in ind.iter_mut().enumerate() {
+        if rng.gen_bool(rate) {
+            *gene = rng.gen_range(bounds[2*idx]..bounds[2*idx+1]);
+        }
+    }
+}
+
+/// Deterministic generator for one parallel task, derived from the run seed with SplitMix64.
+fn sub_rng(seed: u32, generation: usize, index: usize, stream: u64) -> rand_chacha::ChaCha8Rng {
+    use rand::SeedableRng;
+    let mut z = (seed as u64)
+        ^ (generation as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (index as u64).wrapping_mul(0xBF58_476D_1CE4_E5B9)
+        ^ stream.wrapping_mul(0x94D0_49BB_1331_11EB);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    rand_chacha::ChaCha8Rng::seed_from_u64(z ^ (z >> 31))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sphere(x: &[f64]) -> Result<f64, JsValue> {
+        Ok(x.iter().map(|v| v * v).sum())
+    }
+
+    #[test]
+    fn test_genetic_algorithm_is_reproducible_across_thread_counts() {
+        let bounds = [-5.0, 5.0, -5.0, 5.0, -5.0, 5.0];
+        let run = |threads: usize| {
+            rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap()
+                .install(|| run_genetic_algorithm(sphere, |_| Ok(true), &bounds, 40, 30, 0.2, 7).unwrap())
+        };
+        let single = run(1);
+        assert_eq!(single, run(4));
+        assert_ne!(single, run_genetic_algorithm(sphere, |_| Ok(true), &bounds, 40, 30, 0.2, 8).unwrap());
+        assert!(sphere(&single).unwrap() < 3.0);
+    }
+
+    #[test]
+    fn test_least_squares_residual_and_rank_deficiency() {
+        // y = 1 + 2x with one outlier; residual is the norm of the misfit
+        let a = [1.0, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0];
+        let b = [1.0, 3.0, 5.0, 8.0];
+        let out = least_squares_residual(&a, &b, 4, 2).unwrap();
+        assert_eq!(out.len(), 3);
+        let r: f64 = (0..4).map(|i| (out[0] + out[1] * i as f64 - b[i]).powi(2)).sum::<f64>().sqrt();
+        assert!((out[2] - r).abs() < 1e-12);
+
+        // Duplicated column: the minimum-norm solution splits the weight evenly
+        let a = [1.0, 1.0, 2.0, 2.0, 3.0, 3.0];
+        let b = [2.0, 4.0, 6.0];
+        let out = least_squares_residual(&a, &b, 3, 2).unwrap();
+        assert!((out[0] - 1.0).abs() < 1e-10 && (out[1] - 1.0).abs() < 1e-10);
+        assert!(out[2] < 1e-10);
+    }
+
+    #[test]
+    fn test_genetic_algorithm_stops_when_aborted() {
+        let bounds = [-5.0, 5.0, -5.0, 5.0];
+        let flag = AbortFlag::new();
+        flag.handle().abort();
+        let mut evals = 0;
+        let counting = |x: &[f64]| { evals += 1; sphere(x) };
+        let control = crate::utils::iteration_control(None, Some(&flag), 50);
+        let best = run_genetic_algorithm(counting, control, &bounds, 20, 50, 0.2, 3).unwrap();
+        assert_eq!(best.len(), 2);
+        // Initial best plus a single generation
+        assert_eq!(evals, 1 + 20);
+    }
+
+    #[test]
+    fn test_mutation_rate_is_per_gene() {
+        let dim = 2000;
+        let bounds: Vec<f64> = (0..dim).flat_map(|_| [1.0, 2.0]).collect();
+        let mut ind = vec![0.0; dim];
+        mutate(&mut ind, &bounds, 0.25, &mut sub_rng(1, 0, 0, 0));
+        let mutated = ind.iter().filter(|&&g| g != 0.0).count();
+        assert!((400..600).contains(&mutated));
+        assert!(ind.iter().all(|&g| g == 0.0 || (1.0..2.0).contains(&g)));
+    }
+
+    #[test]
+    fn test_grid_points_and_best_of() {
+        let points = grid_points(&[0.0, 1.0, -1.0, 1.0], &[3, 2], 6);
+        assert_eq!(points[0], vec![0.0, -1.0]);
+        assert_eq!(points[2], vec![1.0, -1.0]);
+        assert_eq!(points[5], vec![1.0, 1.0]);
+        assert_eq!(grid_points(&[2.0, 4.0], &[1], 1), vec![vec![3.0]]);
+
+        let best = best_of(points, |x| Ok((x[0] - 0.5).powi(2) + (x[1] - 1.0).powi(2))).unwrap();
+        assert_eq!(best, vec![0.5, 1.0, 0.0]);
+        let best = best_of(vec![vec![1.0], vec![2.0]], |x| Ok(if x[0] == 1.0 { f64::NAN } else { 3.0 })).unwrap();
+        assert_eq!(best, vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_weighted_least_squares_ignores_zero_weight_outlier() {
+        // y = 1 + 2x with a gross outlier at the last row
+        let a = [1.0, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0];
+        let b = [1.0, 3.0, 5.0, 100.0];
+        let x = weighted_least_squares(&a, &b, &[1.0, 1.0, 1.0, 0.0], 4, 2).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-10 && (x[1] - 2.0).abs() < 1e-10);
+
+        let unweighted = least_squares(&a, &b, 4, 2).unwrap();
+        let ones = weighted_least_squares(&a, &b, &[1.0; 4], 4, 2).unwrap();
+        assert!((unweighted[1] - ones[1]).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_ridge_and_lasso() {
+        // Orthonormal design: ridge shrinks by 1 / (1 + lambda), lasso soft-thresholds
+        let a = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        let b = [3.0, 0.5, 7.0];
+        let ridge = ridge_regression(&a, &b, 3, 2, 1.0).unwrap();
+        assert!((ridge[0] - 1.5).abs() < 1e-12 && (ridge[1] - 0.25).abs() < 1e-12);
+        let lasso = lasso_regression(&a, &b, 3, 2, 1.0, 100).unwrap();
+        assert_eq!(lasso, vec![2.0, 0.0]);
+
+        // Correlated design: lambda = 0 recovers ordinary least squares
+        let a = [1.0, 1.0, 1.0, 2.0, 1.0, 3.0, 1.0, 4.0];
+        let b = [3.1, 4.9, 7.2, 8.8];
+        let ols = least_squares(&a, &b, 4, 2).unwrap();
+        let lasso = lasso_regression(&a, &b, 4, 2, 0.0, 10_000).unwrap();
+        let ridge = ridge_regression(&a, &b, 4, 2, 0.0).unwrap();
+        for j in 0..2 {
+            assert!((lasso[j] - ols[j]).abs() < 1e-6);
+            assert!((ridge[j] - ols[j]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_levenberg_marquardt_fits_exponential_and_respects_bounds() {
+        let xs: Vec<f64> = (0..20).map(|i| i as f64 * 0.25).collect();
+        let ys: Vec<f64> = xs.iter().map(|x| 2.5 * (-0.8 * x).exp()).collect();
+        let model = |p: &[f64]| -> Result<Vec<f64>, JsValue> {
+            Ok(xs.iter().zip(&ys).map(|(x, y)| p[0] * (p[1] * x).exp() - y).collect())
+        };
+        let inf = f64::INFINITY;
+
+        let p = run_levenberg_marquardt(model, &[1.0, -0.1], &[-inf, -inf], &[inf, inf], 200).unwrap();
+        assert!((p[0] - 2.5).abs() < 1e-6 && (p[1] + 0.8).abs() < 1e-6);
+
+        // Capping the amplitude keeps it on the bound while the rate adapts
+        let p = run_levenberg_marquardt(model, &[1.0, -0.1], &[0.0, -inf], &[2.0, inf], 200).unwrap();
+        assert_eq!(p[0], 2.0);
+        assert!(p[1] < 0.0);
+    }
+}
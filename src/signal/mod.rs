@@ -1,4 +1,5 @@
 use rayon::prelude::*;
+use serde::Serialize;
 use wasm_bindgen::prelude::*;
 
 /// Performs a Fast Fourier Transform (FFT) on a real-valued signal - Parallel
@@ -43,6 +44,177 @@ pub fn magnitude(complex_data: &[f64]) -> Vec<f64> {
         .collect()
 }
 
+/// Sample frequencies of an `n`-point FFT at sampling rate `fs` (NumPy `fftfreq` order:
+/// non-negative frequencies first, then negative ones).
+#[wasm_bindgen(js_name = fftFreqs)]
+pub fn fft_freqs(n: usize, fs: f64) -> Vec<f64> {
+    let half = n.div_ceil(2);
+    (0..n).map(|k| {
+        let k = if k < half { k as f64 } else { k as f64 - n as f64 };
+        k * fs / n as f64
+    }).collect()
+}
+
+/// Frequencies of the `n / 2 + 1` non-negative bins of an `n`-point real FFT.
+#[wasm_bindgen(js_name = rfftFreqs)]
+pub fn rfft_freqs(n: usize, fs: f64) -> Vec<f64> {
+    (0..=n / 2).map(|k| k as f64 * fs / n as f64).collect()
+}
+
+/// Unwraps a phase sequence by removing jumps larger than $\pi$ between neighbors.
+#[wasm_bindgen(js_name = unwrapPhase)]
+pub fn unwrap_phase(phase: &[f64]) -> Vec<f64> {
+    let mut out = Vec::with_capacity(phase.len());
+    let mut offset = 0.0;
+    for (i, &p) in phase.iter().enumerate() {
+        if i > 0 {
+            offset -= phase_jump(p - phase[i - 1]);
+        }
+        out.push(p + offset);
+    }
+    out
+}
+
+/// Multiple of $2\pi$ to subtract from a phase difference to bring it into $[-\pi, \pi)$.
+fn phase_jump(delta: f64) -> f64 {
+    let two_pi = 2.0 * std::f64::consts::PI;
+    two_pi * ((delta + std::f64::consts::PI) / two_pi).floor()
+}
+
+/// Converts power values to decibels relative to `reference` - Parallel
+///
+/// $$ 10 \log_{10}(\max(p, 10^{-10}) / \text{ref}) $$
+#[wasm_bindgen(js_name = powerToDb)]
+pub fn power_to_db(power: &[f64], reference: f64) -> Vec<f64> {
+    power.par_iter()
+        .with_min_len(8192)
+        .map(|&p| 10.0 * (p.max(1e-10) / reference).log10())
+        .collect()
+}
+
+/// One-sided spectrum of a real signal, ready for plotting.
+#[derive(Serialize)]
+pub struct SpectrumAnalysis {
+    pub freqs: Vec<f64>,
+    pub magnitude: Vec<f64>,
+    pub phase: Vec<f64>,
+    pub power_db: Vec<f64>,
+}
+
+/// Real FFT of `data` (zero-padded to a power of two) returned as an object with
+/// `freqs`, `magnitude`, unwrapped `phase` and `power_db` for the `n / 2 + 1` bins.
+#[wasm_bindgen(js_name = analyzeSpectrum)]
+pub fn analyze_spectrum(data: &[f64], fs: f64) -> Result<JsValue, JsValue> {
+    let spectrum = spectrum_components(data, fs)?;
+    Ok(serde_wasm_bindgen::to_value(&spectrum)?)
+}
+
+fn spectrum_components(data: &[f64], fs: f64) -> Result<SpectrumAnalysis, JsValue> {
+    let complex = fft(data)?;
+    let n = complex.len() / 2;
+    // Keep the bins up to Nyquist; `fft` uses the e^{+i} kernel, so conjugate for the usual phase
+    let one_sided: Vec<f64> = complex[..2 * (n / 2 + 1)].chunks_exact(2)
+        .flat_map(|c| [c[0], -c[1]])
+        .collect();
+
+    let magnitude = magnitude(&one_sided);
+    let phase: Vec<f64> = one_sided.chunks_exact(2).map(|c| c[1].atan2(c[0])).collect();
+    let power: Vec<f64> = magnitude.iter().map(|m| m * m).collect();
+
+    Ok(SpectrumAnalysis {
+        freqs: rfft_freqs(n, fs),
+        magnitude,
+        phase: unwrap_phase(&phase),
+        power_db: power_to_db(&power, 1.0),
+    })
+}
+
+/// Exponentially averaged power spectrum of a stream of fixed-size frames.
+///
+/// Each frame is windowed and transformed with an internal [`FftPlan`](crate::fft::FftPlan),
+/// and its one-sided power $P_k$ is blended into the running spectrum
+/// $$ S_k \leftarrow (1 - \alpha) S_k + \alpha P_k $$
+/// (the first frame initializes $S$). $P_k = c_k |X_k|^2 / (\sum w)^2$ with $c_k = 2$ except
+/// at DC and Nyquist, so a sinusoid of amplitude $A$ centred on a bin reads $A^2 / 2$.
+/// All buffers are allocated once in the constructor.
+#[wasm_bindgen]
+pub struct SpectrumAverager {
+    plan: crate::fft::FftPlan,
+    window: Vec<f64>,
+    scale: f64,
+    alpha: f64,
+    re: Vec<f64>,
+    im: Vec<f64>,
+    spectrum: Vec<f64>,
+    frames: u32,
+}
+
+#[wasm_bindgen]
+impl SpectrumAverager {
+    /// `window_size` must be a power of two; `alpha` in (0, 1] weights the newest frame
+    /// (1 disables averaging). `window` defaults to `"hann"`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(window_size: usize, alpha: f64, window: Option<String>) -> Result<SpectrumAverager, JsValue> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err(JsValue::from_str("Averaging factor must be in (0, 1]"));
+        }
+        let plan = crate::fft::FftPlan::new(window_size)?;
+        let window = make_window(window_size, window.as_deref().unwrap_or("hann"), None)?;
+        let sum: f64 = window.iter().sum();
+        Ok(SpectrumAverager {
+            plan,
+            window,
+            scale: 1.0 / (sum * sum),
+            alpha,
+            re: vec![0.0; window_size],
+            im: vec![0.0; window_size],
+            spectrum: vec![0.0; window_size / 2 + 1],
+            frames: 0,
+        })
+    }
+
+    /// Adds one frame of exactly `window_size` samples to the running average.
+    #[wasm_bindgen(js_name = pushFrame)]
+    pub fn push_frame(&mut self, data: &[f64]) -> Result<(), JsValue> {
+        if data.len() != self.window.len() {
+            return Err(JsValue::from_str("Frame length must equal the window size"));
+        }
+        for ((r, i), (&x, &w)) in self.re.iter_mut().zip(self.im.iter_mut()).zip(data.iter().zip(&self.window)) {
+            *r = x * w;
+            *i = 0.0;
+        }
+        self.plan.execute(&mut self.re, &mut self.im, false);
+
+        let n = self.window.len();
+        let a = if self.frames == 0 { 1.0 } else { self.alpha };
+        for (k, s) in self.spectrum.iter_mut().enumerate() {
+            let one_sided = if k == 0 || 2 * k == n { 1.0 } else { 2.0 };
+            let p = one_sided * self.scale * (self.re[k] * self.re[k] + self.im[k] * self.im[k]);
+            *s += a * (p - *s);
+        }
+        self.frames = self.frames.saturating_add(1);
+        Ok(())
+    }
+
+    /// Averaged one-sided power spectrum (`window_size / 2 + 1` bins; bin `k` is `k * fs / window_size`).
+    #[wasm_bindgen(js_name = getSpectrum)]
+    pub fn get_spectrum(&self) -> Vec<f64> {
+        self.spectrum.clone()
+    }
+
+    /// Number of frames pushed since construction or the last `reset`.
+    #[wasm_bindgen(getter)]
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Clears the running average.
+    pub fn reset(&mut self) {
+        self.spectrum.fill(0.0);
+        self.frames = 0;
+    }
+}
+
 /// Applies a moving average filter to smoothing out a signal - Parallel
 #[wasm_bindgen(js_name = movingAverage)]
 pub fn moving_average(data: &[f64], window: usize) -> Vec<f64> {
@@ -98,19 +270,229 @@ pub fn moving_average(data: &[f64], window: usize) -> Vec<f64> {
     result
 }
 
-/// Simple peak detection based on local maxima and a threshold - Parallel
+/// Applies a moving maximum filter over a centered window - Parallel
+#[wasm_bindgen(js_name = movingMax)]
+pub fn moving_max(data: &[f64], window: usize) -> Vec<f64> {
+    moving_extreme(data, window, |a, b| a >= b)
+}
+
+/// Applies a moving minimum filter over a centered window - Parallel
+#[wasm_bindgen(js_name = movingMin)]
+pub fn moving_min(data: &[f64], window: usize) -> Vec<f64> {
+    moving_extreme(data, window, |a, b| a <= b)
+}
+
+/// Sliding-window extreme using a monotonic deque per chunk (O(n)).
+/// `dominates(a, b)` is true when `a` should evict `b` from the back of the deque.
+fn moving_extreme(data: &[f64], window: usize, dominates: fn(f64, f64) -> bool) -> Vec<f64> {
+    let n = data.len();
+    if n == 0 || window == 0 { return data.to_vec(); }
+
+    let mut result = vec![0.0; n];
+    let half = window / 2;
+    let chunk_size = 32768;
+    let res_ptr = result.as_mut_ptr() as usize;
+
+    (0..n).into_par_iter().step_by(chunk_size).for_each(|start| unsafe {
+        let end = (start + chunk_size).min(n);
+        let out = res_ptr as *mut f64;
+        let mut deque: std::collections::VecDeque<usize> = std::collections::VecDeque::with_capacity(window + 1);
+
+        // Prime the deque with the left half of the first window of this chunk
+        let mut next = start.saturating_sub(half);
+        for i in start..end {
+            let w_end = (i + half).min(n - 1);
+            while next <= w_end {
+                while let Some(&back) = deque.back() {
+                    if dominates(data[next], data[back]) { deque.pop_back(); } else { break; }
+                }
+                deque.push_back(next);
+                next += 1;
+            }
+            let w_start = i.saturating_sub(half);
+            while let Some(&front) = deque.front() {
+                if front < w_start { deque.pop_front(); } else { break; }
+            }
+            *out.add(i) = data[*deque.front().unwrap()];
+        }
+    });
+
+    result
+}
+
+/// Applies a moving (sample) standard deviation over a centered window - Parallel
+#[wasm_bindgen(js_name = movingStd)]
+pub fn moving_std(data: &[f64], window: usize) -> Vec<f64> {
+    let n = data.len();
+    if n == 0 || window == 0 { return vec![0.0; n]; }
+
+    let mut result = vec![0.0; n];
+    let half = window / 2;
+    let chunk_size = 32768;
+    let res_ptr = result.as_mut_ptr() as usize;
+
+    (0..n).into_par_iter().step_by(chunk_size).for_each(|start| unsafe {
+        let end = (start + chunk_size).min(n);
+        let out = res_ptr as *mut f64;
+
+        // Shift by the chunk's first sample to limit cancellation in the sum of squares
+        let shift = data[start];
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+        let mut w_start = start.saturating_sub(half);
+        let mut w_end = w_start;
+
+        for i in start..end {
+            let target_end = (i + half).min(n - 1) + 1;
+            while w_end < target_end {
+                let v = data[w_end] - shift;
+                sum += v;
+                sum_sq += v * v;
+                w_end += 1;
+            }
+            let target_start = i.saturating_sub(half);
+            while w_start < target_start {
+                let v = data[w_start] - shift;
+                sum -= v;
+                sum_sq -= v * v;
+                w_start += 1;
+            }
+
+            let count = (w_end - w_start) as f64;
+            *out.add(i) = if count > 1.0 {
+                ((sum_sq - sum * sum / count) / (count - 1.0)).max(0.0).sqrt()
+            } else {
+                0.0
+            };
+        }
+    });
+
+    result
+}
+
+/// Applies a JavaScript reducer to every full window of `window` samples.
+///
+/// `f` receives a fresh `Float64Array` copy of each window and must return a number; output
+/// `i` is `f(data[i..i + window])`, giving `len - window + 1` values. Each window crosses the
+/// JS boundary, so this runs sequentially and is far slower than the built-in
+/// `movingAverage` / `movingStd` / `movingMin` / `movingMax`; prefer those on hot paths.
+#[wasm_bindgen(js_name = rollingApply)]
+pub fn rolling_apply(data: &[f64], window: usize, f: &js_sys::Function) -> Result<Vec<f64>, JsValue> {
+    if window == 0 || window > data.len() {
+        return Err(JsValue::from_str("Window must be between 1 and the data length"));
+    }
+    data.windows(window)
+        .map(|w| {
+            let arr = js_sys::Float64Array::from(w);
+            f.call1(&JsValue::NULL, &arr)?
+                .as_f64()
+                .ok_or_else(|| JsValue::from_str("rollingApply: Function must return a number"))
+        })
+        .collect()
+}
+
+/// Exponential moving average (single-pole low-pass).
+///
+/// $$ y_i = \alpha x_i + (1 - \alpha) y_{i-1}, \quad y_0 = x_0 $$
+#[wasm_bindgen]
+pub fn ema(data: &[f64], alpha: f64) -> Result<Vec<f64>, JsValue> {
+    validate_alpha(alpha)?;
+    let mut out = Vec::with_capacity(data.len());
+    let mut y = match data.first() { Some(&v) => v, None => return Ok(out) };
+    for &x in data {
+        y = alpha * x + (1.0 - alpha) * y;
+        out.push(y);
+    }
+    Ok(out)
+}
+
+/// Exponential moving average - Parallel (Chunked with Warmup)
+///
+/// Each chunk replays enough preceding samples for the truncated history to decay below
+/// `1e-12`, so results match `ema` to within rounding on large offline arrays.
+#[wasm_bindgen(js_name = emaParallel)]
+pub fn ema_parallel(data: &[f64], alpha: f64) -> Result<Vec<f64>, JsValue> {
+    validate_alpha(alpha)?;
+    let n = data.len();
+    if n < 2048 { return ema(data, alpha); }
+
+    let decay = 1.0 - alpha;
+    let warmup = if decay > 0.0 { (1e-12f64.ln() / decay.ln()).ceil() as usize } else { 0 };
+    let chunk_size = 65536;
+    let mut out = vec![0.0; n];
+    let out_ptr = out.as_mut_ptr() as usize;
+
+    (0..n).into_par_iter().step_by(chunk_size).for_each(|start| unsafe {
+        let end = (start + chunk_size).min(n);
+        let p_out = out_ptr as *mut f64;
+
+        let seed = start.saturating_sub(warmup);
+        let mut y = data[seed];
+        for &x in &data[seed..start] {
+            y = alpha * x + decay * y;
+        }
+        for (i, &x) in data[start..end].iter().enumerate() {
+            y = alpha * x + decay * y;
+            *p_out.add(start + i) = y;
+        }
+    });
+
+    Ok(out)
+}
+
+fn validate_alpha(alpha: f64) -> Result<(), JsValue> {
+    if !(alpha > 0.0 && alpha <= 1.0) {
+        return Err(JsValue::from_str("Alpha must be in (0, 1]"));
+    }
+    Ok(())
+}
+
+/// Removes a constant offset (`"constant"`) or a least-squares line (`"linear"`) - Parallel
+#[wasm_bindgen]
+pub fn detrend(data: &[f64], mode: &str) -> Result<Vec<f64>, JsValue> {
+    if data.is_empty() { return Ok(vec![]); }
+
+    let (slope, intercept) = match mode {
+        "constant" => (0.0, crate::stats::mean(data)),
+        "linear" => {
+            let x: Vec<f64> = (0..data.len()).map(|i| i as f64).collect();
+            let (slope, intercept, _) = crate::fitting::fit_linear(&x, data);
+            (slope, intercept)
+        }
+        _ => return Err(JsValue::from_str("Mode must be 'constant' or 'linear'")),
+    };
+
+    Ok(data.par_iter()
+        .enumerate()
+        .with_min_len(8192)
+        .map(|(i, &v)| v - (slope * i as f64 + intercept))
+        .collect())
+}
+
+/// Peak detection based on local maxima and a threshold - Parallel
+///
+/// Optionally filters by `prominence` and enforces a `min_distance` (in samples) between peaks,
+/// sharing the prominence and distance logic of `analysis::find_peaks`.
 #[wasm_bindgen(js_name = findPeaksSimple)]
-pub fn find_peaks(data: &[f64], threshold: f64) -> Vec<usize> {
+pub fn find_peaks(data: &[f64], threshold: f64, prominence: Option<f64>, min_distance: Option<usize>) -> Vec<usize> {
     let n = data.len();
     if n < 3 { return vec![]; }
 
-    (1..n-1).into_par_iter()
+    let mut peaks: Vec<usize> = (1..n-1).into_par_iter()
         .with_min_len(8192)
         .filter(|&i| {
             let val = data[i];
             val > data[i-1] && val > data[i+1] && val >= threshold
         })
-        .collect()
+        .collect();
+
+    if let Some(prom) = prominence.filter(|&p| p > 0.0) {
+        peaks.retain(|&i| crate::analysis::peak_prominence(data, i) >= prom);
+    }
+    if let Some(dist) = min_distance {
+        peaks = crate::analysis::filter_min_distance(data, &peaks, dist);
+    }
+    peaks
 }
 /// Calculates the cross-correlation of two signals - Parallel
 #[wasm_bindgen(js_name = crossCorrelation)]
@@ -135,6 +517,181 @@ pub fn cross_correlation(a: &[f64], b: &[f64]) -> Vec<f64> {
     result
 }
 
+/// Linear convolution via zero-padded FFTs, returning the full `n + m - 1` samples.
+#[wasm_bindgen(js_name = fftConvolve)]
+pub fn fft_convolve(a: &[f64], b: &[f64]) -> Result<Vec<f64>, JsValue> {
+    if a.is_empty() || b.is_empty() {
+        return Err(JsValue::from_str("Inputs must not be empty"));
+    }
+    let out_len = a.len() + b.len() - 1;
+    let size = out_len.next_power_of_two();
+
+    let mut a_re = vec![0.0; size];
+    let mut a_im = vec![0.0; size];
+    let mut b_re = vec![0.0; size];
+    let mut b_im = vec![0.0; size];
+    a_re[..a.len()].copy_from_slice(a);
+    b_re[..b.len()].copy_from_slice(b);
+
+    rayon::join(
+        || crate::fft::fft_radix2(&mut a_re, &mut a_im, false),
+        || crate::fft::fft_radix2(&mut b_re, &mut b_im, false),
+    );
+
+    a_re.par_iter_mut().zip(a_im.par_iter_mut())
+        .zip(b_re.par_iter().zip(b_im.par_iter()))
+        .with_min_len(8192)
+        .for_each(|((ar, ai), (&br, &bi))| {
+            let re = *ar * br - *ai * bi;
+            *ai = *ar * bi + *ai * br;
+            *ar = re;
+        });
+
+    crate::fft::fft_radix2(&mut a_re, &mut a_im, true);
+    a_re.truncate(out_len);
+    Ok(a_re)
+}
+
+/// Lag that best aligns `b` with `a`, returned as `[best_lag, peak_correlation]`.
+///
+/// Maximizes $r(L) = \sum_n a_n b_{n+L}$ over $|L| \le$ `max_lag` using `fft_convolve`. A positive
+/// lag means `b` is a delayed copy of `a`; a negative lag means `b` leads `a`. The peak is
+/// normalized by $\sqrt{\sum a^2 \sum b^2}$, so it is 1 for an exact shifted copy. With
+/// `interpolate` (default `false`) a parabola through the peak and its neighbours refines
+/// the lag to sub-sample precision.
+#[wasm_bindgen(js_name = estimateLag)]
+pub fn estimate_lag(a: &[f64], b: &[f64], max_lag: usize, interpolate: Option<bool>) -> Result<Vec<f64>, JsValue> {
+    if a.is_empty() || b.is_empty() {
+        return Err(JsValue::from_str("Inputs must not be empty"));
+    }
+    let reversed: Vec<f64> = a.iter().rev().copied().collect();
+    // corr[L + a.len() - 1] = r(L) for L in -(a.len() - 1) ..= b.len() - 1
+    let corr = fft_convolve(&reversed, b)?;
+    let zero = a.len() - 1;
+    let lo = zero - max_lag.min(zero);
+    let hi = zero.saturating_add(max_lag).min(corr.len() - 1);
+
+    let mut best = lo;
+    for i in lo..=hi {
+        if corr[i] > corr[best] { best = i; }
+    }
+    let (mut lag, mut peak) = (best as f64 - zero as f64, corr[best]);
+    if interpolate.unwrap_or(false) && best > 0 && best + 1 < corr.len() {
+        let (offset, value) = parabolic_peak(corr[best - 1], corr[best], corr[best + 1]);
+        lag += offset;
+        peak = value;
+    }
+
+    let energy = (a.iter().map(|v| v * v).sum::<f64>() * b.iter().map(|v| v * v).sum::<f64>()).sqrt();
+    Ok(vec![lag, if energy > 0.0 { peak / energy } else { 0.0 }])
+}
+
+/// Generalized cross-correlation with PHAT weighting, returned as `[delay_seconds, peak]`.
+///
+/// Both signals are zero-padded to a power of two of at least `a.len() + b.len()`, and their
+/// cross-spectrum is whitened before the inverse FFT:
+/// $$ R(f) = \frac{A^*(f) B(f)}{|A^*(f) B(f)|} $$
+/// Because only phase is kept, the correlation collapses to a sharp peak even with
+/// reverberation or coloured noise. The peak is searched within $\pm$`max_tau` seconds. The sign
+/// convention matches `estimateLag`, so a positive delay means `b` lags `a`. `peak` is in
+/// $[0, 1]$ and serves as a confidence measure.
+#[wasm_bindgen(js_name = gccPhat)]
+pub fn gcc_phat(a: &[f64], b: &[f64], fs: f64, max_tau: f64) -> Result<Vec<f64>, JsValue> {
+    if a.is_empty() || b.is_empty() {
+        return Err(JsValue::from_str("Inputs must not be empty"));
+    }
+    if !(fs.is_finite() && fs > 0.0) || max_tau.is_nan() || max_tau < 0.0 {
+        return Err(JsValue::from_str("fs must be positive and max_tau non-negative"));
+    }
+    let size = (a.len() + b.len()).next_power_of_two();
+    let mut a_re = vec![0.0; size];
+    let mut a_im = vec![0.0; size];
+    let mut b_re = vec![0.0; size];
+    let mut b_im = vec![0.0; size];
+    a_re[..a.len()].copy_from_slice(a);
+    b_re[..b.len()].copy_from_slice(b);
+
+    rayon::join(
+        || crate::fft::fft_radix2(&mut a_re, &mut a_im, false),
+        || crate::fft::fft_radix2(&mut b_re, &mut b_im, false),
+    );
+
+    a_re.par_iter_mut().zip(a_im.par_iter_mut())
+        .zip(b_re.par_iter().zip(b_im.par_iter()))
+        .with_min_len(8192)
+        .for_each(|((ar, ai), (&br, &bi))| {
+            // conj(A) * B, whitened
+            let re = *ar * br + *ai * bi;
+            let im = *ar * bi - *ai * br;
+            let mag = re.hypot(im);
+            let (re, im) = if mag > 1e-300 { (re / mag, im / mag) } else { (0.0, 0.0) };
+            *ar = re;
+            *ai = im;
+        });
+    crate::fft::fft_radix2(&mut a_re, &mut a_im, true);
+
+    // a_re[L mod size] holds the correlation at lag L
+    let max_shift = ((max_tau * fs).floor() as usize).min(size / 2);
+    let mut best_lag = 0i64;
+    let mut peak = a_re[0];
+    for shift in 1..=max_shift {
+        for lag in [shift as i64, -(shift as i64)] {
+            let v = a_re[lag.rem_euclid(size as i64) as usize];
+            if v > peak {
+                peak = v;
+                best_lag = lag;
+            }
+        }
+    }
+    Ok(vec![best_lag as f64 / fs, peak])
+}
+
+/// Vertex of the parabola through `(-1, y0)`, `(0, y1)`, `(1, y2)` as `(offset, value)`.
+fn parabolic_peak(y0: f64, y1: f64, y2: f64) -> (f64, f64) {
+    let denom = y0 - 2.0 * y1 + y2;
+    if denom.abs() < 1e-300 {
+        return (0.0, y1);
+    }
+    let offset = (0.5 * (y0 - y2) / denom).clamp(-0.5, 0.5);
+    (offset, y1 - 0.25 * (y0 - y2) * offset)
+}
+
+/// Linear convolution with SciPy/NumPy-style output modes - Parallel
+///
+/// * `"full"` - all `n + m - 1` samples.
+/// * `"same"` - the central `max(n, m)` samples of the full result.
+/// * `"valid"` - only positions where the inputs overlap completely (`max(n, m) - min(n, m) + 1`).
+///
+/// Large inputs use `fft_convolve`; small ones are summed directly.
+#[wasm_bindgen]
+pub fn convolve(a: &[f64], b: &[f64], mode: &str) -> Result<Vec<f64>, JsValue> {
+    if a.is_empty() || b.is_empty() {
+        return Err(JsValue::from_str("Inputs must not be empty"));
+    }
+    let (n, m) = (a.len(), b.len());
+    let (long, short) = (n.max(m), n.min(m));
+    let (start, len) = match mode.to_ascii_lowercase().as_str() {
+        "full" => (0, n + m - 1),
+        "same" => ((short - 1) / 2, long),
+        "valid" => (short - 1, long - short + 1),
+        _ => return Err(JsValue::from_str("Mode must be \"full\", \"same\" or \"valid\"")),
+    };
+
+    if short > 64 && n * m > 1 << 16 {
+        let full = fft_convolve(a, b)?;
+        return Ok(full[start..start + len].to_vec());
+    }
+
+    Ok((start..start + len).into_par_iter()
+        .with_min_len(1024)
+        .map(|k| {
+            let lo = (k + 1).saturating_sub(m);
+            let hi = k.min(n - 1);
+            (lo..=hi).map(|i| a[i] * b[k - i]).sum()
+        })
+        .collect())
+}
+
 /// Calculates the auto-correlation of a signal - Parallel
 #[wasm_bindgen(js_name = autoCorrelation)]
 pub fn auto_correlation(data: &[f64]) -> Vec<f64> {
@@ -155,11 +712,7 @@ pub fn stft(data: &[f64], window_size: usize, hop_size: usize) -> Result<Vec<f64
     let n_frames = (n - window_size) / hop_size + 1;
     let mut result = vec![0.0; n_frames * window_size * 2];
     
-    // Hanning window
-    let mut window = vec![0.0; window_size];
-    for i in 0..window_size {
-        window[i] = 0.5 * (1.0 - (2.0 * std::f64::consts::PI * i as f64 / (window_size - 1) as f64).cos());
-    }
+    let window = make_window(window_size, "hann", None)?;
     
     let res_ptr = result.as_mut_ptr() as usize;
     let data_ptr = data.as_ptr() as usize;
@@ -247,6 +800,50 @@ pub fn resample(data: &[f64], new_len: usize) -> Vec<f64> {
     out
 }
 
+/// Linear interpolation of `(x, y)` onto an arbitrary ascending grid `x_new` - Parallel
+///
+/// Points outside `[x[0], x[n-1]]` follow `extrapolate`: `"clamp"` holds the edge values,
+/// `"nan"` yields `NaN` and `"linear"` extends the first/last segment.
+#[wasm_bindgen(js_name = resampleToGrid)]
+pub fn resample_to_grid(x: &[f64], y: &[f64], x_new: &[f64], extrapolate: &str) -> Result<Vec<f64>, JsValue> {
+    if x.len() != y.len() || x.len() < 2 {
+        return Err(JsValue::from_str("x and y must have the same length (at least 2)"));
+    }
+    #[derive(Clone, Copy)]
+    enum Policy { Clamp, Nan, Linear }
+    let policy = match extrapolate.to_ascii_lowercase().as_str() {
+        "clamp" => Policy::Clamp,
+        "nan" => Policy::Nan,
+        "linear" => Policy::Linear,
+        _ => return Err(JsValue::from_str("Extrapolation must be \"clamp\", \"nan\" or \"linear\"")),
+    };
+
+    let n = x.len();
+    let segment = |i: usize, val: f64| {
+        let t = (val - x[i]) / (x[i + 1] - x[i]);
+        y[i] * (1.0 - t) + y[i + 1] * t
+    };
+
+    Ok(x_new.par_iter()
+        .with_min_len(4096)
+        .map(|&val| {
+            if val.is_nan() { return f64::NAN; }
+            let outside = val < x[0] || val > x[n - 1];
+            if outside {
+                match policy {
+                    Policy::Nan => return f64::NAN,
+                    Policy::Clamp => return if val < x[0] { y[0] } else { y[n - 1] },
+                    Policy::Linear => return if val < x[0] { segment(0, val) } else { segment(n - 2, val) },
+                }
+            }
+            // First index with x[idx] >= val, so the interval is [idx - 1, idx]
+            let idx = x.partition_point(|&v| v < val);
+            let i = idx.clamp(1, n - 1) - 1;
+            segment(i, val)
+        })
+        .collect())
+}
+
 /// Inverse Short-Time Fourier Transform (ISTFT)
 #[wasm_bindgen]
 pub fn istft(stft_data: &[f64], window_size: usize, hop_size: usize) -> Result<Vec<f64>, JsValue> {
@@ -255,11 +852,7 @@ pub fn istft(stft_data: &[f64], window_size: usize, hop_size: usize) -> Result<V
     let mut out = vec![0.0; out_len];
     let mut window_sum = vec![0.0; out_len];
     
-    // Hanning window
-    let mut window = vec![0.0; window_size];
-    for i in 0..window_size {
-        window[i] = 0.5 * (1.0 - (2.0 * std::f64::consts::PI * i as f64 / (window_size - 1) as f64).cos());
-    }
+    let window = make_window(window_size, "hann", None)?;
 
     for f in 0..n_frames {
         let mut re = vec![0.0; window_size];
@@ -290,6 +883,121 @@ pub fn istft(stft_data: &[f64], window_size: usize, hop_size: usize) -> Result<V
     Ok(out)
 }
 
+/// Phase-vocoder time stretching: changes duration by `factor` without changing pitch.
+///
+/// The signal is analysed with [`stft`] at hop `round(hop / factor)` and resynthesized with
+/// [`istft`] at hop `hop`. For each bin the frame-to-frame phase advance, minus the advance
+/// expected for the bin centre and wrapped to $[-\pi, \pi)$, gives the instantaneous frequency
+/// $$ \omega = \omega_k + \frac{\Delta\phi - \omega_k H_a - 2\pi m}{H_a} $$
+/// and the output phase accumulates $\omega H_s$ per frame. Because the analysis hop is an
+/// integer, the realised stretch is `hop / round(hop / factor)`; the output has
+/// `round(len * stretch)` samples. `window_size` must be a power of two and `hop <= window_size`
+/// (`window_size / 4` is a good default).
+#[wasm_bindgen(js_name = timeStretch)]
+pub fn time_stretch(data: &[f64], factor: f64, window_size: usize, hop: usize) -> Result<Vec<f64>, JsValue> {
+    use std::f64::consts::PI;
+    if !(factor.is_finite() && factor > 0.0) {
+        return Err(JsValue::from_str("Stretch factor must be positive"));
+    }
+    if hop == 0 || hop > window_size {
+        return Err(JsValue::from_str("Hop must be in [1, window_size]"));
+    }
+    if data.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let analysis_hop = ((hop as f64 / factor).round() as usize).max(1);
+    let stretch = hop as f64 / analysis_hop as f64;
+    // Trailing zeros so the last samples fall inside a full frame
+    let mut padded = data.to_vec();
+    padded.resize(data.len() + window_size, 0.0);
+    let mut frames = stft(&padded, window_size, analysis_hop)?;
+
+    let n = window_size;
+    let half = n / 2;
+    let mut prev_phase = vec![0.0; half + 1];
+    let mut synth_phase = vec![0.0; half + 1];
+    for (f, frame) in frames.chunks_exact_mut(2 * n).enumerate() {
+        for k in 0..=half {
+            let (re, im) = (frame[2 * k], frame[2 * k + 1]);
+            let mag = re.hypot(im);
+            // `stft` uses the e^{+i} kernel; conjugate to get the conventional phase
+            let phase = (-im).atan2(re);
+            if f == 0 {
+                synth_phase[k] = phase;
+            } else {
+                let omega = 2.0 * PI * k as f64 / n as f64;
+                let deviation = phase - prev_phase[k] - omega * analysis_hop as f64;
+                let deviation = deviation - phase_jump(deviation);
+                synth_phase[k] += (omega + deviation / analysis_hop as f64) * hop as f64;
+            }
+            prev_phase[k] = phase;
+            frame[2 * k] = mag * synth_phase[k].cos();
+            frame[2 * k + 1] = -mag * synth_phase[k].sin();
+        }
+        // Conjugate symmetry keeps the resynthesized frame real
+        for k in 1..half {
+            frame[2 * (n - k)] = frame[2 * k];
+            frame[2 * (n - k) + 1] = -frame[2 * k + 1];
+        }
+    }
+
+    let mut out = istft(&frames, window_size, hop)?;
+    out.resize((data.len() as f64 * stretch).round() as usize, 0.0);
+    Ok(out)
+}
+
+/// Pitch shifting by `semitones` (within $\pm 24$) at constant duration.
+///
+/// Time-stretches by the pitch ratio $r = 2^{s/12}$ with [`time_stretch`], then resamples the
+/// result back to the input length with a windowed-sinc interpolator, which scales every
+/// frequency by $r$. `fs` is only validated (the shift is relative); `window_size` and `hop`
+/// are passed to the phase vocoder.
+#[wasm_bindgen(js_name = pitchShift)]
+pub fn pitch_shift(data: &[f64], semitones: f64, fs: f64, window_size: usize, hop: usize) -> Result<Vec<f64>, JsValue> {
+    if !(-24.0..=24.0).contains(&semitones) {
+        return Err(JsValue::from_str("Semitones must be within [-24, 24]"));
+    }
+    if !(fs.is_finite() && fs > 0.0) {
+        return Err(JsValue::from_str("Sampling rate must be positive"));
+    }
+    let ratio = 2f64.powf(semitones / 12.0);
+    let stretched = time_stretch(data, ratio, window_size, hop)?;
+    Ok(resample_sinc(&stretched, data.len()))
+}
+
+/// Band-limited resampling to `new_len` samples with a Blackman-windowed sinc - Parallel
+///
+/// Output sample `i` is taken at input position `i * len / new_len`. When shrinking, the
+/// kernel cutoff drops to the new Nyquist rate so the result is anti-aliased. Samples
+/// outside the input are treated as zero.
+pub(crate) fn resample_sinc(data: &[f64], new_len: usize) -> Vec<f64> {
+    use std::f64::consts::PI;
+    const ZERO_CROSSINGS: f64 = 16.0;
+    let n = data.len();
+    if n == 0 || new_len == 0 { return vec![0.0; new_len]; }
+    if n == new_len { return data.to_vec(); }
+
+    let step = n as f64 / new_len as f64;
+    let cutoff = (1.0 / step).min(1.0);
+    let half_width = ZERO_CROSSINGS / cutoff;
+    (0..new_len).into_par_iter()
+        .with_min_len(1024)
+        .map(|i| {
+            let t = i as f64 * step;
+            let lo = (t - half_width).ceil().max(0.0) as usize;
+            let hi = ((t + half_width).floor() as usize).min(n - 1);
+            (lo..=hi).map(|j| {
+                let x = t - j as f64;
+                let sinc = if x == 0.0 { 1.0 } else { (PI * cutoff * x).sin() / (PI * cutoff * x) };
+                let u = x / half_width; // in [-1, 1]
+                let window = 0.42 + 0.5 * (PI * u).cos() + 0.08 * (2.0 * PI * u).cos();
+                data[j] * cutoff * sinc * window
+            }).sum()
+        })
+        .collect()
+}
+
 /// Computes a Spectrogram (magnitudes of STFT)
 #[wasm_bindgen]
 pub fn spectrogram(data: &[f64], window_size: usize, hop_size: usize) -> Result<Vec<f64>, JsValue> {
@@ -302,3 +1010,710 @@ pub fn spectrogram(data: &[f64], window_size: usize, hop_size: usize) -> Result<
     }
     Ok(spec)
 }
+
+/// Spectrogram in decibels: `20 log10(|X|) - ref_db`, with magnitudes floored at `1e-5`
+/// (-100 dB) so silent bins stay finite. Same layout as `spectrogram`.
+#[wasm_bindgen(js_name = spectrogramDb)]
+pub fn spectrogram_db(data: &[f64], window_size: usize, hop_size: usize, ref_db: f64) -> Result<Vec<f64>, JsValue> {
+    let power: Vec<f64> = spectrogram(data, window_size, hop_size)?
+        .par_iter()
+        .with_min_len(8192)
+        .map(|m| m * m)
+        .collect();
+    Ok(power_to_db(&power, 1.0).into_iter().map(|db| db - ref_db).collect())
+}
+
+/// Converts a frequency in Hz to the (HTK) mel scale: $2595 \log_{10}(1 + f / 700)$.
+pub fn hz_to_mel(hz: f64) -> f64 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+/// Converts a mel value back to Hz.
+pub fn mel_to_hz(mel: f64) -> f64 {
+    700.0 * (10f64.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular mel filterbank spanning `0..fs/2`.
+///
+/// Returns a row-major `n_mels × (window_size / 2 + 1)` matrix; each filter peaks at 1 on its
+/// center frequency and falls linearly to 0 at the neighboring centers.
+#[wasm_bindgen(js_name = melFilterbank)]
+pub fn mel_filterbank(n_mels: usize, window_size: usize, fs: f64) -> Result<Vec<f64>, JsValue> {
+    if n_mels == 0 || window_size < 2 {
+        return Err(JsValue::from_str("n_mels and window_size must be positive"));
+    }
+    if fs <= 0.0 || !fs.is_finite() {
+        return Err(JsValue::from_str("Sampling frequency must be positive"));
+    }
+    let n_freq = window_size / 2 + 1;
+    let mel_max = hz_to_mel(fs / 2.0);
+    let edges: Vec<f64> = (0..n_mels + 2)
+        .map(|i| mel_to_hz(mel_max * i as f64 / (n_mels + 1) as f64))
+        .collect();
+    let freqs = rfft_freqs(window_size, fs);
+
+    let mut bank = vec![0.0; n_mels * n_freq];
+    bank.par_chunks_mut(n_freq).enumerate().for_each(|(m, row)| {
+        let (lo, center, hi) = (edges[m], edges[m + 1], edges[m + 2]);
+        for (w, &f) in row.iter_mut().zip(&freqs) {
+            *w = if f > lo && f <= center {
+                (f - lo) / (center - lo)
+            } else if f > center && f < hi {
+                (hi - f) / (hi - center)
+            } else {
+                0.0
+            };
+        }
+    });
+    Ok(bank)
+}
+
+/// Mel-scaled power spectrogram - Parallel
+///
+/// Applies `mel_filterbank` to the power spectrum (`|X|^2`) of each Hann-windowed STFT frame.
+/// Returns the flattened `n_frames × n_mels` matrix.
+#[wasm_bindgen(js_name = melSpectrogram)]
+pub fn mel_spectrogram(data: &[f64], window_size: usize, hop_size: usize, fs: f64, n_mels: usize) -> Result<Vec<f64>, JsValue> {
+    let bank = mel_filterbank(n_mels, window_size, fs)?;
+    let stft_res = stft(data, window_size, hop_size)?;
+    let n_freq = window_size / 2 + 1;
+
+    Ok(stft_res.par_chunks(window_size * 2)
+        .flat_map_iter(|frame| {
+            let power: Vec<f64> = frame[..n_freq * 2].chunks_exact(2)
+                .map(|c| c[0] * c[0] + c[1] * c[1])
+                .collect();
+            bank.chunks(n_freq)
+                .map(move |filter| filter.iter().zip(&power).map(|(w, p)| w * p).sum::<f64>())
+                .collect::<Vec<f64>>()
+        })
+        .collect())
+}
+
+/// Mel-frequency cepstral coefficients - Parallel
+///
+/// Converts each frame of `mel_spectrogram` to dB (`10 log10`, floored at -100 dB) and applies
+/// the orthonormal `dct2` along the mel axis, keeping the first `n_coeffs` coefficients.
+/// Returns the flattened `n_frames × n_coeffs` matrix.
+#[wasm_bindgen]
+pub fn mfcc(data: &[f64], window_size: usize, hop_size: usize, fs: f64, n_mels: usize, n_coeffs: usize) -> Result<Vec<f64>, JsValue> {
+    if n_coeffs == 0 || n_coeffs > n_mels {
+        return Err(JsValue::from_str("n_coeffs must be between 1 and n_mels"));
+    }
+    let mel = mel_spectrogram(data, window_size, hop_size, fs, n_mels)?;
+    // `JsValue` is not `Send`, so DCT failures are carried out of the pool as `None`
+    let frames: Vec<Vec<f64>> = mel.par_chunks(n_mels)
+        .map(|frame| crate::fft::dct2(&power_to_db(frame, 1.0)).ok())
+        .collect::<Option<_>>()
+        .ok_or_else(|| JsValue::from_str("DCT of mel frame failed"))?;
+    Ok(frames.into_iter().flat_map(|c| c.into_iter().take(n_coeffs)).collect())
+}
+
+/// Generates a symmetric window of length `n`.
+///
+/// Supported windows: `"hann"`, `"hamming"`, `"blackman"`, `"blackman-harris"`, `"flattop"`,
+/// `"kaiser"` (shape set by `beta`, default 8.6) and `"rectangular"`.
+pub fn make_window(n: usize, window: &str, beta: Option<f64>) -> Result<Vec<f64>, JsValue> {
+    use std::f64::consts::PI;
+    if n == 0 { return Ok(vec![]); }
+    if n == 1 { return Ok(vec![1.0]); }
+
+    let denom = (n - 1) as f64;
+    let cosine_sum = |a: &[f64]| -> Vec<f64> {
+        (0..n).map(|i| {
+            let phase = 2.0 * PI * i as f64 / denom;
+            a.iter().enumerate().fold(0.0, |acc, (k, &ak)| {
+                let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                acc + sign * ak * (k as f64 * phase).cos()
+            })
+        }).collect()
+    };
+
+    let w = match window.to_ascii_lowercase().as_str() {
+        "hann" | "hanning" => cosine_sum(&[0.5, 0.5]),
+        "hamming" => cosine_sum(&[0.54, 0.46]),
+        "blackman" => cosine_sum(&[0.42, 0.5, 0.08]),
+        "blackman-harris" | "blackmanharris" => cosine_sum(&[0.35875, 0.48829, 0.14128, 0.01168]),
+        "flattop" => cosine_sum(&[0.21557895, 0.41663158, 0.277263158, 0.083578947, 0.006947368]),
+        "kaiser" => {
+            let beta = beta.unwrap_or(8.6);
+            let norm = crate::basic::bessel_i0(beta);
+            (0..n).map(|i| {
+                let r = 2.0 * i as f64 / denom - 1.0;
+                crate::basic::bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / norm
+            }).collect()
+        }
+        "rectangular" | "boxcar" => vec![1.0; n],
+        _ => return Err(JsValue::from_str("Unknown window type")),
+    };
+    Ok(w)
+}
+
+/// Generates a window function of length `n`.
+/// `beta` is only used by the Kaiser window.
+#[wasm_bindgen(js_name = windowFunction)]
+pub fn window_function(n: usize, window: &str, beta: Option<f64>) -> Result<Vec<f64>, JsValue> {
+    make_window(n, window, beta)
+}
+
+/// Multiplies a signal by a window function of the same length - Parallel
+#[wasm_bindgen(js_name = applyWindow)]
+pub fn apply_window(data: &[f64], window: &str, beta: Option<f64>) -> Result<Vec<f64>, JsValue> {
+    let w = make_window(data.len(), window, beta)?;
+    Ok(data.par_iter().zip(w.par_iter())
+        .with_min_len(8192)
+        .map(|(&x, &wi)| x * wi)
+        .collect())
+}
+
+/// Returns `[coherent_gain, power_gain]` of a window, i.e. `sum(w)/n` and `sum(w^2)/n`.
+/// Divide amplitudes by the coherent gain (or powers by the power gain) to undo windowing.
+#[wasm_bindgen(js_name = windowGains)]
+pub fn window_gains(n: usize, window: &str, beta: Option<f64>) -> Result<Vec<f64>, JsValue> {
+    if n == 0 {
+        return Err(JsValue::from_str("Window length must be positive"));
+    }
+    let w = make_window(n, window, beta)?;
+    let coherent = w.iter().sum::<f64>() / n as f64;
+    let power = w.iter().map(|v| v * v).sum::<f64>() / n as f64;
+    Ok(vec![coherent, power])
+}
+
+/// Magnitude-squared coherence between two signals (Welch method) - Parallel
+///
+/// $$ C_{xy}(f) = \frac{|P_{xy}(f)|^2}{P_{xx}(f) P_{yy}(f)} $$
+///
+/// Spectra are averaged over Hann-windowed, mean-removed segments of `window_size` samples
+/// (a power of two) overlapping by `overlap` samples. Returns `window_size / 2 + 1` values in
+/// `[0, 1]`; bin `k` corresponds to frequency `k * fs / window_size`.
+#[wasm_bindgen]
+pub fn coherence(x: &[f64], y: &[f64], window_size: usize, overlap: usize, fs: f64) -> Result<Vec<f64>, JsValue> {
+    let spectra = welch_spectra(x, y, window_size, overlap, fs)?;
+    Ok((0..spectra.pxx.len()).map(|k| {
+        let denom = spectra.pxx[k] * spectra.pyy[k];
+        if denom > 0.0 {
+            ((spectra.pxy_re[k].powi(2) + spectra.pxy_im[k].powi(2)) / denom).min(1.0)
+        } else {
+            0.0
+        }
+    }).collect())
+}
+
+/// Transfer-function estimate between an excitation and a response (H1 estimator) - Parallel
+///
+/// $$ H_1(f) = \frac{P_{xy}(f)}{P_{xx}(f)} $$
+///
+/// Uses the same Welch segmentation as `coherence`. Returns `window_size / 2 + 1` complex values
+/// as interleaved `[re, im, ...]`; bins where the input has no power are zero.
+#[wasm_bindgen]
+pub fn tfestimate(input: &[f64], output: &[f64], window_size: usize, overlap: usize, fs: f64) -> Result<Vec<f64>, JsValue> {
+    let spectra = welch_spectra(input, output, window_size, overlap, fs)?;
+    let mut result = Vec::with_capacity(spectra.pxx.len() * 2);
+    for k in 0..spectra.pxx.len() {
+        let pxx = spectra.pxx[k];
+        if pxx > 0.0 {
+            result.push(spectra.pxy_re[k] / pxx);
+            result.push(spectra.pxy_im[k] / pxx);
+        } else {
+            result.push(0.0);
+            result.push(0.0);
+        }
+    }
+    Ok(result)
+}
+
+/// Averaged one-sided auto- and cross-spectral densities of two signals.
+struct WelchSpectra {
+    pxx: Vec<f64>,
+    pyy: Vec<f64>,
+    pxy_re: Vec<f64>,
+    pxy_im: Vec<f64>,
+}
+
+/// Welch estimate of `Pxx`, `Pyy` and `Pxy = conj(X) Y`, averaged over segments in parallel.
+fn welch_spectra(x: &[f64], y: &[f64], window_size: usize, overlap: usize, fs: f64) -> Result<WelchSpectra, JsValue> {
+    if x.len() != y.len() {
+        return Err(JsValue::from_str("Signals must have the same length"));
+    }
+    if !window_size.is_power_of_two() {
+        return Err(JsValue::from_str("Window size must be a power of two"));
+    }
+    if overlap >= window_size {
+        return Err(JsValue::from_str("Overlap must be smaller than the window size"));
+    }
+    if fs <= 0.0 || !fs.is_finite() {
+        return Err(JsValue::from_str("Sampling frequency must be positive"));
+    }
+    if x.len() < window_size {
+        return Err(JsValue::from_str("Signal is shorter than the window size"));
+    }
+
+    let step = window_size - overlap;
+    let n_segments = (x.len() - window_size) / step + 1;
+    let n_freq = window_size / 2 + 1;
+    let window = make_window(window_size, "hann", None)?;
+
+    let segment_fft = |data: &[f64]| {
+        let mean = data.iter().sum::<f64>() / window_size as f64;
+        let mut re: Vec<f64> = data.iter().zip(&window).map(|(&v, &w)| (v - mean) * w).collect();
+        let mut im = vec![0.0; window_size];
+        crate::fft::fft_radix2(&mut re, &mut im, false);
+        // `fft_radix2` uses the e^{+i} kernel: negate to get the conventional spectrum
+        im.iter_mut().for_each(|v| *v = -*v);
+        (re, im)
+    };
+
+    let zero = || WelchSpectra {
+        pxx: vec![0.0; n_freq],
+        pyy: vec![0.0; n_freq],
+        pxy_re: vec![0.0; n_freq],
+        pxy_im: vec![0.0; n_freq],
+    };
+
+    let mut sums = (0..n_segments).into_par_iter()
+        .with_min_len(1)
+        .fold(zero, |mut acc, s| {
+            let start = s * step;
+            let (xr, xi) = segment_fft(&x[start..start + window_size]);
+            let (yr, yi) = segment_fft(&y[start..start + window_size]);
+            for k in 0..n_freq {
+                acc.pxx[k] += xr[k] * xr[k] + xi[k] * xi[k];
+                acc.pyy[k] += yr[k] * yr[k] + yi[k] * yi[k];
+                acc.pxy_re[k] += xr[k] * yr[k] + xi[k] * yi[k];
+                acc.pxy_im[k] += xr[k] * yi[k] - xi[k] * yr[k];
+            }
+            acc
+        })
+        .reduce(zero, |mut a, b| {
+            for k in 0..n_freq {
+                a.pxx[k] += b.pxx[k];
+                a.pyy[k] += b.pyy[k];
+                a.pxy_re[k] += b.pxy_re[k];
+                a.pxy_im[k] += b.pxy_im[k];
+            }
+            a
+        });
+
+    // Density scaling, with interior bins doubled for the one-sided spectrum
+    let base = 1.0 / (fs * window.iter().map(|w| w * w).sum::<f64>() * n_segments as f64);
+    for k in 0..n_freq {
+        let scale = if k == 0 || k == window_size / 2 { base } else { 2.0 * base };
+        sums.pxx[k] *= scale;
+        sums.pyy[k] *= scale;
+        sums.pxy_re[k] *= scale;
+        sums.pxy_im[k] *= scale;
+    }
+    Ok(sums)
+}
+
+fn check_generator(fs: f64) -> Result<(), JsValue> {
+    if !(fs.is_finite() && fs > 0.0) {
+        return Err(JsValue::from_str("Sampling rate must be positive"));
+    }
+    Ok(())
+}
+
+/// Samples `n` points of a periodic waveform of `freq` Hz at `fs` Hz - Parallel
+/// `shape` maps the cycle fraction in `[0, 1)` (offset by `phase` radians) to a value.
+fn periodic_wave(freq: f64, phase: f64, fs: f64, n: usize, shape: impl Fn(f64) -> f64 + Sync) -> Vec<f64> {
+    let offset = phase / (2.0 * std::f64::consts::PI);
+    (0..n).into_par_iter()
+        .with_min_len(8192)
+        .map(|i| {
+            let cycles = freq * i as f64 / fs + offset;
+            shape(cycles - cycles.floor())
+        })
+        .collect()
+}
+
+/// Sine wave $A \sin(2\pi f t + \phi)$ with $t = i / f_s$ - Parallel
+#[wasm_bindgen(js_name = generateSine)]
+pub fn generate_sine(freq: f64, amplitude: f64, phase: f64, fs: f64, n: usize) -> Result<Vec<f64>, JsValue> {
+    check_generator(fs)?;
+    Ok((0..n).into_par_iter()
+        .with_min_len(8192)
+        .map(|i| amplitude * (2.0 * std::f64::consts::PI * freq * i as f64 / fs + phase).sin())
+        .collect())
+}
+
+/// Unit-amplitude linear chirp sweeping from `f0` at the first sample to `f1` at the last - Parallel
+///
+/// $$ x(t) = \sin\left(2\pi \left(f_0 t + \frac{f_1 - f_0}{2T} t^2\right)\right), \quad T = (n - 1) / f_s $$
+#[wasm_bindgen(js_name = generateChirp)]
+pub fn generate_chirp(f0: f64, f1: f64, fs: f64, n: usize) -> Result<Vec<f64>, JsValue> {
+    check_generator(fs)?;
+    let duration = n.saturating_sub(1).max(1) as f64 / fs;
+    let rate = (f1 - f0) / duration;
+    Ok((0..n).into_par_iter()
+        .with_min_len(8192)
+        .map(|i| {
+            let t = i as f64 / fs;
+            (2.0 * std::f64::consts::PI * (f0 * t + 0.5 * rate * t * t)).sin()
+        })
+        .collect())
+}
+
+/// Square wave alternating between `+amplitude` and `-amplitude` - Parallel
+///
+/// Each cycle is high for the first `duty` fraction (default 0.5). `phase` is in radians, as
+/// for `generateSine`.
+#[wasm_bindgen(js_name = generateSquare)]
+pub fn generate_square(freq: f64, amplitude: f64, phase: f64, fs: f64, n: usize, duty: Option<f64>) -> Result<Vec<f64>, JsValue> {
+    check_generator(fs)?;
+    let duty = duty.unwrap_or(0.5);
+    if !(0.0..=1.0).contains(&duty) {
+        return Err(JsValue::from_str("Duty cycle must be in [0, 1]"));
+    }
+    Ok(periodic_wave(freq, phase, fs, n, |frac| if frac < duty { amplitude } else { -amplitude }))
+}
+
+/// Sawtooth wave rising linearly from `-amplitude` to `+amplitude` each cycle - Parallel
+#[wasm_bindgen(js_name = generateSawtooth)]
+pub fn generate_sawtooth(freq: f64, amplitude: f64, phase: f64, fs: f64, n: usize) -> Result<Vec<f64>, JsValue> {
+    check_generator(fs)?;
+    Ok(periodic_wave(freq, phase, fs, n, |frac| amplitude * (2.0 * frac - 1.0)))
+}
+
+/// Gaussian white noise with standard deviation `std` - Parallel
+///
+/// Box-Muller transform over a seeded `ChaCha8Rng` stream (see `randomNormal`); a given
+/// `seed` gives the same samples regardless of thread count.
+#[wasm_bindgen(js_name = generateWhiteNoise)]
+pub fn generate_white_noise(std: f64, seed: u32, n: usize) -> Vec<f64> {
+    crate::utils::rng::random_normal(n, 0.0, std, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic uniform noise in [-1, 1) for spectral tests.
+    fn noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        (0..n).map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+        }).collect()
+    }
+
+    #[test]
+    fn test_coherence_linear_system_and_independent_noise() {
+        let x = noise(16384, 1);
+        let y: Vec<f64> = (0..x.len())
+            .map(|i| 0.5 * x[i] + if i > 0 { 0.3 * x[i - 1] } else { 0.0 })
+            .collect();
+        let c = coherence(&x, &y, 256, 128, 1000.0).unwrap();
+        assert_eq!(c.len(), 129);
+        assert!(c[1..128].iter().all(|&v| v > 0.98 && v <= 1.0));
+
+        let z = noise(16384, 2);
+        let c = coherence(&x, &z, 256, 128, 1000.0).unwrap();
+        let mean = c[1..128].iter().sum::<f64>() / 127.0;
+        assert!(mean < 0.1);
+    }
+
+    #[test]
+    fn test_tfestimate_gain_and_delay() {
+        let x = noise(32768, 3);
+        // y[i] = 2 x[i - 1]: |H| = 2 and phase = -2*pi*k/N
+        let y: Vec<f64> = (0..x.len()).map(|i| if i > 0 { 2.0 * x[i - 1] } else { 0.0 }).collect();
+        let n = 256;
+        let h = tfestimate(&x, &y, n, 128, 1.0).unwrap();
+        assert_eq!(h.len(), 2 * (n / 2 + 1));
+        for k in 1..n / 4 {
+            let (re, im) = (h[2 * k], h[2 * k + 1]);
+            let gain = (re * re + im * im).sqrt();
+            let phase = im.atan2(re);
+            let expected = -2.0 * std::f64::consts::PI * k as f64 / n as f64;
+            assert!((gain - 2.0).abs() < 0.05, "gain {} at bin {}", gain, k);
+            assert!((phase - expected).abs() < 0.02, "phase {} at bin {}", phase, k);
+        }
+    }
+
+    #[test]
+    fn test_resample_to_grid_extrapolation() {
+        let x = [0.0, 1.0, 3.0];
+        let y = [0.0, 2.0, 6.0];
+        let grid = [-1.0, 0.5, 2.0, 3.0, 4.0];
+        assert_eq!(resample_to_grid(&x, &y, &grid, "clamp").unwrap(), vec![0.0, 1.0, 4.0, 6.0, 6.0]);
+        assert_eq!(resample_to_grid(&x, &y, &grid, "linear").unwrap(), vec![-2.0, 1.0, 4.0, 6.0, 8.0]);
+        let r = resample_to_grid(&x, &y, &grid, "nan").unwrap();
+        assert!(r[0].is_nan() && r[4].is_nan());
+        assert_eq!(&r[1..4], &[1.0, 4.0, 6.0]);
+    }
+
+    #[test]
+    fn test_convolve_modes_and_fft_path() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [1.0, 1.0, 1.0, 1.0];
+        assert_eq!(convolve(&a, &b, "full").unwrap(), vec![1.0, 3.0, 6.0, 10.0, 14.0, 12.0, 9.0, 5.0]);
+        assert_eq!(convolve(&a, &b, "same").unwrap(), vec![3.0, 6.0, 10.0, 14.0, 12.0]);
+        assert_eq!(convolve(&a, &b, "valid").unwrap(), vec![10.0, 14.0]);
+        assert_eq!(convolve(&b, &a, "valid").unwrap(), vec![10.0, 14.0]);
+
+        let x = noise(1000, 4);
+        let k = noise(300, 5);
+        let fast = convolve(&x, &k, "same").unwrap();
+        let direct = cross_correlation(&x, &k);
+        assert_eq!(fast.len(), 1000);
+        for (i, v) in fast.iter().enumerate() {
+            assert!((v - direct[i + 149]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_spectrum_helpers() {
+        assert_eq!(fft_freqs(5, 10.0), vec![0.0, 2.0, 4.0, -4.0, -2.0]);
+        assert_eq!(rfft_freqs(4, 8.0), vec![0.0, 2.0, 4.0]);
+
+        let wrapped: Vec<f64> = (0..20).map(|i| {
+            let p = 0.9 * i as f64;
+            (p + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI) - std::f64::consts::PI
+        }).collect();
+        for (i, v) in unwrap_phase(&wrapped).iter().enumerate() {
+            assert!((v - 0.9 * i as f64).abs() < 1e-12);
+        }
+
+        assert_eq!(power_to_db(&[100.0, 0.0], 1.0), vec![20.0, -100.0]);
+
+        // Cosine at bin 2 of 16 delayed by one sample: phase -2*pi*2/16 = -pi/4
+        let data: Vec<f64> = (0..16).map(|i| (2.0 * std::f64::consts::PI * 2.0 * (i as f64 - 1.0) / 16.0).cos()).collect();
+        let s = spectrum_components(&data, 16.0).unwrap();
+        assert_eq!(s.freqs.len(), 9);
+        assert!((s.freqs[2] - 2.0).abs() < 1e-12);
+        assert!((s.magnitude[2] - 8.0).abs() < 1e-9);
+        assert!((s.phase[2] + std::f64::consts::PI / 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mel_filterbank_and_spectrogram() {
+        assert!((mel_to_hz(hz_to_mel(1234.5)) - 1234.5).abs() < 1e-9);
+
+        let (n_mels, window, fs) = (20, 512, 16000.0);
+        let bank = mel_filterbank(n_mels, window, fs).unwrap();
+        let n_freq = window / 2 + 1;
+        // Adjacent triangles overlap to a flat unit response between the first and last centers
+        let center_lo = mel_to_hz(hz_to_mel(fs / 2.0) / (n_mels + 1) as f64);
+        let center_hi = mel_to_hz(hz_to_mel(fs / 2.0) * n_mels as f64 / (n_mels + 1) as f64);
+        for (k, f) in rfft_freqs(window, fs).iter().enumerate() {
+            if *f > center_lo && *f < center_hi {
+                let total: f64 = (0..n_mels).map(|m| bank[m * n_freq + k]).sum();
+                assert!((total - 1.0).abs() < 1e-9);
+            }
+        }
+
+        let tone: Vec<f64> = (0..4096).map(|i| (2.0 * std::f64::consts::PI * 1000.0 * i as f64 / fs).sin()).collect();
+        let mel = mel_spectrogram(&tone, window, 256, fs, n_mels).unwrap();
+        assert_eq!(mel.len(), ((4096 - window) / 256 + 1) * n_mels);
+        let frame = &mel[..n_mels];
+        let loudest = (0..n_mels).max_by(|&a, &b| frame[a].partial_cmp(&frame[b]).unwrap()).unwrap();
+        let peak_filter = (0..n_mels).max_by(|&a, &b| {
+            bank[a * n_freq + 32].partial_cmp(&bank[b * n_freq + 32]).unwrap()
+        }).unwrap();
+        assert_eq!(loudest, peak_filter);
+
+        let db = spectrogram_db(&tone, window, 256, 10.0).unwrap();
+        assert!(db.iter().all(|v| v.is_finite() && *v >= -110.0));
+    }
+
+    #[test]
+    fn test_mfcc_shape_and_energy_coefficient() {
+        let fs = 8000.0;
+        let tone: Vec<f64> = (0..2048).map(|i| (2.0 * std::f64::consts::PI * 440.0 * i as f64 / fs).sin()).collect();
+        let coeffs = mfcc(&tone, 256, 128, fs, 26, 13).unwrap();
+        let n_frames = (2048 - 256) / 128 + 1;
+        assert_eq!(coeffs.len(), n_frames * 13);
+
+        // The 0th coefficient is sqrt(n_mels) times the mean log-mel energy of the frame
+        let mel = mel_spectrogram(&tone, 256, 128, fs, 26).unwrap();
+        let mean_db = power_to_db(&mel[..26], 1.0).iter().sum::<f64>() / 26.0;
+        assert!((coeffs[0] - mean_db * 26f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spectrum_averager_tone_power_and_decay() {
+        let n = 64;
+        let tone: Vec<f64> = (0..n).map(|i| 2.0 * (2.0 * std::f64::consts::PI * 8.0 * i as f64 / n as f64).cos()).collect();
+        let mut avg = SpectrumAverager::new(n, 0.5, Some("rectangular".into())).unwrap();
+        avg.push_frame(&tone).unwrap();
+        let s = avg.get_spectrum();
+        assert_eq!(s.len(), n / 2 + 1);
+        assert!((s[8] - 2.0).abs() < 1e-12);
+        assert!(s.iter().enumerate().all(|(k, &p)| k == 8 || p < 1e-20));
+
+        // A silent frame halves the running spectrum with alpha = 0.5
+        avg.push_frame(&vec![0.0; n]).unwrap();
+        assert!((avg.get_spectrum()[8] - 1.0).abs() < 1e-12);
+        assert_eq!(avg.frames(), 2);
+
+        avg.reset();
+        avg.push_frame(&tone).unwrap();
+        assert!((avg.get_spectrum()[8] - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_time_stretch_keeps_pitch() {
+        let n = 4096;
+        let freq = 0.05; // cycles per sample
+        let tone: Vec<f64> = (0..n).map(|i| (2.0 * std::f64::consts::PI * freq * i as f64).sin()).collect();
+        let out = time_stretch(&tone, 1.5, 512, 128).unwrap();
+        // Analysis hop round(128 / 1.5) = 85 gives a realised stretch of 128 / 85
+        assert_eq!(out.len(), (n as f64 * 128.0 / 85.0).round() as usize);
+
+        // Away from the edges the rate of zero crossings (2 per cycle) matches the input
+        let mid = &out[1024..out.len() - 1024];
+        let crossings = mid.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        let measured = crossings as f64 / mid.len() as f64;
+        assert!((measured - freq).abs() < 0.002, "measured {}", measured);
+        let rms = (mid.iter().map(|v| v * v).sum::<f64>() / mid.len() as f64).sqrt();
+        assert!((rms - 0.5f64.sqrt()).abs() < 0.1, "rms {}", rms);
+    }
+
+    #[test]
+    fn test_resample_sinc_preserves_band_limited_tone() {
+        let tone = |n: usize, f: f64| -> Vec<f64> {
+            (0..n).map(|i| (2.0 * std::f64::consts::PI * f * i as f64).sin()).collect()
+        };
+        // 0.05 cycles/sample at 1000 samples is 0.025 cycles/sample at 2000
+        let up = resample_sinc(&tone(1000, 0.05), 2000);
+        let expected = tone(2000, 0.025);
+        for i in 100..1900 {
+            assert!((up[i] - expected[i]).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_pitch_shift_octave_up() {
+        let n = 4096;
+        let freq = 0.03;
+        let tone: Vec<f64> = (0..n).map(|i| (2.0 * std::f64::consts::PI * freq * i as f64).sin()).collect();
+        let out = pitch_shift(&tone, 12.0, 44100.0, 512, 128).unwrap();
+        assert_eq!(out.len(), n);
+        let mid = &out[512..n - 512];
+        let crossings = mid.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        let measured = crossings as f64 / mid.len() as f64;
+        assert!((measured - 2.0 * freq).abs() < 0.003, "measured {}", measured);
+    }
+
+    #[test]
+    fn test_estimate_lag_both_directions() {
+        let a = noise(500, 3);
+        let mut b = vec![0.0; 7];
+        b.extend_from_slice(&a[..493]);
+
+        let res = estimate_lag(&a, &b, 50, None).unwrap();
+        assert_eq!(res[0], 7.0);
+        assert!(res[1] > 0.95);
+        let res = estimate_lag(&b, &a, 50, Some(true)).unwrap();
+        assert!((res[0] + 7.0).abs() < 0.2);
+
+        // The true lag lies outside the search window
+        let res = estimate_lag(&a, &b, 3, None).unwrap();
+        assert!(res[0].abs() <= 3.0);
+
+        // An unbounded window searches every lag
+        let res = estimate_lag(&a, &b, usize::MAX, None).unwrap();
+        assert_eq!(res[0], 7.0);
+    }
+
+    #[test]
+    fn test_gcc_phat_recovers_delay_under_coloured_noise() {
+        let fs = 1000.0;
+        // Low-pass "source" so that plain correlation has a broad peak
+        let src: Vec<f64> = noise(2048, 11).windows(8).map(|w| w.iter().sum::<f64>()).collect();
+        let interference = noise(src.len(), 99);
+        let a: Vec<f64> = src.iter().zip(&interference).map(|(s, n)| s + 0.3 * n).collect();
+        let mut b = vec![0.0; 25];
+        b.extend_from_slice(&src[..src.len() - 25]);
+
+        let res = gcc_phat(&a, &b, fs, 0.1).unwrap();
+        assert!((res[0] - 0.025).abs() < 1e-12);
+        assert!(res[1] > 0.0 && res[1] <= 1.0);
+        let res = gcc_phat(&b, &a, fs, 0.1).unwrap();
+        assert!((res[0] + 0.025).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_periodic_generators() {
+        let fs = 8.0;
+        let sine = generate_sine(1.0, 2.0, 0.0, fs, 8).unwrap();
+        assert!(sine[0].abs() < 1e-12 && (sine[2] - 2.0).abs() < 1e-12 && (sine[6] + 2.0).abs() < 1e-12);
+
+        let square = generate_square(1.0, 1.0, 0.0, fs, 8, Some(0.25)).unwrap();
+        assert_eq!(square, vec![1.0, 1.0, -1.0, -1.0, -1.0, -1.0, -1.0, -1.0]);
+        let saw = generate_sawtooth(1.0, 1.0, 0.0, fs, 5).unwrap();
+        assert_eq!(saw, vec![-1.0, -0.75, -0.5, -0.25, 0.0]);
+
+        // Sweeping 0 -> 0.2 cycles/sample: later quarter crosses zero far more often
+        let chirp = generate_chirp(0.0, 0.2, 1.0, 2001).unwrap();
+        let crossings = |v: &[f64]| v.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        assert!(crossings(&chirp[1500..]) > 5 * crossings(&chirp[..500]));
+    }
+
+    #[test]
+    fn test_white_noise_is_seeded_and_chunk_independent() {
+        use rand::{Rng, SeedableRng};
+        let n = 20_001;
+        let noise = generate_white_noise(2.0, 7, n);
+        assert_eq!(noise, generate_white_noise(2.0, 7, n));
+        assert_ne!(noise[..10], generate_white_noise(2.0, 8, 10)[..]);
+
+        // Same values as one sequential Box-Muller stream
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(7);
+        for i in (0..n).step_by(2) {
+            let u1: f64 = 1.0 - rng.gen::<f64>();
+            let u2: f64 = rng.gen();
+            let z = 2.0 * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+            assert_eq!(noise[i], z);
+        }
+
+        let mean = noise.iter().sum::<f64>() / n as f64;
+        let var = noise.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!(mean.abs() < 0.05 && (var.sqrt() - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_moving_extremes_and_std() {
+        let data = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        assert_eq!(moving_max(&data, 3), vec![3.0, 4.0, 4.0, 5.0, 9.0, 9.0, 9.0, 6.0]);
+        assert_eq!(moving_min(&data, 3), vec![1.0, 1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0]);
+        let s = moving_std(&[1.0, 2.0, 3.0, 10.0], 3);
+        assert!((s[0] - 0.5f64.sqrt()).abs() < 1e-12 && (s[1] - 1.0).abs() < 1e-12);
+
+        // Chunk boundaries (32768) against a brute-force centered window
+        let n = 70_000;
+        let long = noise(n, 3);
+        let (mx, mn, sd) = (moving_max(&long, 5), moving_min(&long, 5), moving_std(&long, 5));
+        for i in [0, 1, 32_767, 32_768, 32_769, 65_536, n - 1] {
+            let w = &long[i.saturating_sub(2)..(i + 3).min(n)];
+            let m = w.iter().sum::<f64>() / w.len() as f64;
+            let v = w.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (w.len() - 1) as f64;
+            assert_eq!(mx[i], w.iter().cloned().fold(f64::MIN, f64::max));
+            assert_eq!(mn[i], w.iter().cloned().fold(f64::MAX, f64::min));
+            assert!((sd[i] - v.sqrt()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_ema_known_answer_and_parallel_warmup() {
+        let y = ema(&[2.0, 4.0, 4.0], 0.5).unwrap();
+        assert_eq!(y, vec![2.0, 3.0, 3.5]);
+
+        // Several 65536-sample chunks with a long memory (small alpha)
+        let data: Vec<f64> = noise(200_000, 5).iter().enumerate().map(|(i, v)| v + (i as f64 * 1e-4).sin()).collect();
+        let seq = ema(&data, 0.001).unwrap();
+        let par = ema_parallel(&data, 0.001).unwrap();
+        let max_err = seq.iter().zip(&par).map(|(a, b)| (a - b).abs()).fold(0.0, f64::max);
+        assert!(max_err < 1e-10);
+    }
+
+    #[test]
+    fn test_detrend_modes() {
+        let line: Vec<f64> = (0..10).map(|i| 3.0 + 0.5 * i as f64).collect();
+        assert!(detrend(&line, "linear").unwrap().iter().all(|v| v.abs() < 1e-12));
+
+        let c = detrend(&[1.0, 2.0, 6.0], "constant").unwrap();
+        assert_eq!(c, vec![-2.0, -1.0, 3.0]);
+        assert!(detrend(&[], "linear").unwrap().is_empty());
+    }
+}
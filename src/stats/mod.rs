@@ -20,8 +20,13 @@ pub fn mean(data: &[f64]) -> f64 {
 /// Calculates the sample variance of a numeric sequence.
 ///
 /// $$ s^2 = \frac{1}{n-1} \sum_{i=1}^n (x_i - \bar{x})^2 $$
+///
+/// Two passes (mean, then squared deviations) by default. Pass `stable = true` to use the
+/// single-pass Welford path of `varianceStable` instead, which keeps full precision when a
+/// large offset makes the computed mean inexact relative to the spread.
 #[wasm_bindgen]
-pub fn variance(data: &[f64]) -> f64 {
+pub fn variance(data: &[f64], stable: Option<bool>) -> f64 {
+    if stable.unwrap_or(false) { return variance_stable(data); }
     let n = data.len();
     if n < 2 { return 0.0; }
     
@@ -34,12 +39,129 @@ pub fn variance(data: &[f64]) -> f64 {
     ss_tot / (n - 1) as f64
 }
 
+/// Calculates the sample variance in a single pass using Welford's algorithm - Parallel
+///
+/// Each chunk accumulates a `(count, mean, M2)` triple which are merged with Chan's formula:
+///
+/// $$ M_2 = M_{2,a} + M_{2,b} + \delta^2 \frac{n_a n_b}{n_a + n_b} $$
+///
+/// Preferred over `variance` for streaming-sized inputs with a large offset and small spread.
+#[wasm_bindgen(js_name = varianceStable)]
+pub fn variance_stable(data: &[f64]) -> f64 {
+    let (count, _, m2) = welford(data);
+    if count < 2.0 { return 0.0; }
+    m2 / (count - 1.0)
+}
+
+/// Single-pass `(count, mean, M2)` accumulation with a parallel merge.
+///
+/// Samples are shifted by the first value so that a large common offset never enters the
+/// running mean, where it would swamp the small updates.
+pub(crate) fn welford(data: &[f64]) -> (f64, f64, f64) {
+    let shift = data.first().copied().unwrap_or(0.0);
+    let (n, mean, m2) = data.par_iter()
+        .with_min_len(8192)
+        .fold(|| (0.0, 0.0, 0.0), |(n, mean, m2), &x| {
+            let x = x - shift;
+            let n1 = n + 1.0;
+            let delta = x - mean;
+            let mean1 = mean + delta / n1;
+            (n1, mean1, m2 + delta * (x - mean1))
+        })
+        .reduce(|| (0.0, 0.0, 0.0), welford_merge);
+    (n, mean + shift, m2)
+}
+
+fn welford_merge(a: (f64, f64, f64), b: (f64, f64, f64)) -> (f64, f64, f64) {
+    if a.0 == 0.0 { return b; }
+    if b.0 == 0.0 { return a; }
+    let n = a.0 + b.0;
+    let delta = b.1 - a.1;
+    let mean = a.1 + delta * b.0 / n;
+    let m2 = a.2 + b.2 + delta * delta * a.0 * b.0 / n;
+    (n, mean, m2)
+}
+
+/// Calculates the Pearson correlation in a single pass using a bivariate Welford update - Parallel
+///
+/// Each chunk tracks counts, means, $M_2$ for both series and the co-moment
+/// $C = \sum (x_i - \bar{x})(y_i - \bar{y})$, updated as $C \mathrel{+}= (x - \bar{x}_{old})(y - \bar{y}_{new})$
+/// and merged with
+///
+/// $$ C = C_a + C_b + \delta_x \delta_y \frac{n_a n_b}{n_a + n_b} $$
+///
+/// Both series are first shifted by their first sample, which `r` is invariant to. Preferred over
+/// `correlation` for poorly centred data with a large offset and small spread, where the two-pass
+/// mean can be off by a sizeable fraction of the spread.
+#[wasm_bindgen(js_name = correlationStable)]
+pub fn correlation_stable(x: &[f64], y: &[f64]) -> Result<f64, JsValue> {
+    if x.len() != y.len() {
+        return Err(JsValue::from_str("Vectors must have the same length"));
+    }
+    // Correlation is shift-invariant; moving the first sample to the origin keeps the
+    // running means small, as in `welford`
+    let (sx, sy) = (x.first().copied().unwrap_or(0.0), y.first().copied().unwrap_or(0.0));
+    let m = x.par_iter().zip(y.par_iter())
+        .with_min_len(8192)
+        .fold(CoMoments::default, |acc, (&xi, &yi)| acc.push(xi - sx, yi - sy))
+        .reduce(CoMoments::default, CoMoments::merge);
+
+    if m.n < 2.0 || m.m2x == 0.0 || m.m2y == 0.0 { return Ok(0.0); }
+    Ok(m.cxy / (m.m2x * m.m2y).sqrt())
+}
+
+/// Running count, means, second moments and co-moment of a pair of series.
+#[derive(Clone, Copy, Default)]
+struct CoMoments {
+    n: f64,
+    mx: f64,
+    my: f64,
+    m2x: f64,
+    m2y: f64,
+    cxy: f64,
+}
+
+impl CoMoments {
+    fn push(self, x: f64, y: f64) -> CoMoments {
+        let n = self.n + 1.0;
+        let dx = x - self.mx;
+        let dy = y - self.my;
+        let mx = self.mx + dx / n;
+        let my = self.my + dy / n;
+        CoMoments {
+            n,
+            mx,
+            my,
+            m2x: self.m2x + dx * (x - mx),
+            m2y: self.m2y + dy * (y - my),
+            cxy: self.cxy + dx * (y - my),
+        }
+    }
+
+    fn merge(a: CoMoments, b: CoMoments) -> CoMoments {
+        if a.n == 0.0 { return b; }
+        if b.n == 0.0 { return a; }
+        let n = a.n + b.n;
+        let dx = b.mx - a.mx;
+        let dy = b.my - a.my;
+        let w = a.n * b.n / n;
+        CoMoments {
+            n,
+            mx: a.mx + dx * b.n / n,
+            my: a.my + dy * b.n / n,
+            m2x: a.m2x + b.m2x + dx * dx * w,
+            m2y: a.m2y + b.m2y + dy * dy * w,
+            cxy: a.cxy + b.cxy + dx * dy * w,
+        }
+    }
+}
+
 /// Calculates the sample standard deviation of a numeric sequence.
 ///
 /// $$ s = \sqrt{s^2} $$
 #[wasm_bindgen(js_name = standardDeviation)]
 pub fn standard_deviation(data: &[f64]) -> f64 {
-    variance(data).sqrt()
+    variance(data, None).sqrt()
 }
 
 /// Finds the median value in a data set.
@@ -102,46 +224,444 @@ pub fn correlation(x: &[f64], y: &[f64]) -> Result<f64, JsValue> {
     Ok(cov / (sx * sy))
 }
 
-/// Calculates a histogram of the data - Parallel
+/// Calculates the sample covariance matrix of a row-major `rows×cols` data matrix - Parallel
+///
+/// Columns are variables and rows are observations. Returns the symmetric `cols×cols` matrix.
+#[wasm_bindgen(js_name = covarianceMatrix)]
+pub fn covariance_matrix(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Matrix dimensions do not match data length"));
+    }
+    if rows < 2 { return Ok(vec![0.0; cols * cols]); }
+
+    let means = means_by_column(data, rows, cols);
+    Ok(covariance_from_means(data, rows, cols, &means))
+}
+
+/// Calculates the Pearson correlation matrix of a row-major `rows×cols` data matrix - Parallel
+///
+/// Columns are variables. The diagonal is exactly 1.0; pairs involving a constant column are 0.0.
+#[wasm_bindgen(js_name = correlationMatrix)]
+pub fn correlation_matrix(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    let cov = covariance_matrix(data, rows, cols)?;
+    Ok(correlation_from_covariance(&cov, cols))
+}
+
+fn correlation_from_covariance(cov: &[f64], cols: usize) -> Vec<f64> {
+    let std: Vec<f64> = (0..cols).map(|i| cov[i * cols + i].sqrt()).collect();
+
+    let mut corr = vec![0.0; cols * cols];
+    for i in 0..cols {
+        corr[i * cols + i] = 1.0;
+        for j in (i + 1)..cols {
+            let denom = std[i] * std[j];
+            let r = if denom > 0.0 { (cov[i * cols + j] / denom).clamp(-1.0, 1.0) } else { 0.0 };
+            corr[i * cols + j] = r;
+            corr[j * cols + i] = r;
+        }
+    }
+    corr
+}
+
+/// Streaming sample covariance of data that arrives in row-major chunks - Parallel per chunk
+///
+/// Each chunk's mean and centred co-moment matrix are merged into the running totals with the
+/// pairwise update of Chan et al.:
+/// $$ M_{AB} = M_A + M_B + \delta \delta^T \frac{n_A n_B}{n_A + n_B}, \quad \delta = \bar{x}_B - \bar{x}_A $$
+/// which is exact (chunking does not change the result beyond rounding) and avoids the
+/// cancellation of accumulating raw sums of squares.
 #[wasm_bindgen]
-pub fn histogram(data: &[f64], bins: usize) -> Vec<u32> {
-    if data.is_empty() || bins == 0 { return vec![]; }
-    
-    let mut min = data[0];
-    let mut max = data[0];
-    for &x in data {
-        if x < min { min = x; }
-        if x > max { max = x; }
+pub struct CovarianceAccumulator {
+    cols: usize,
+    count: usize,
+    mean: Vec<f64>,
+    comoment: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl CovarianceAccumulator {
+    /// Accumulator for observations of `cols` variables.
+    #[wasm_bindgen(constructor)]
+    pub fn new(cols: usize) -> CovarianceAccumulator {
+        CovarianceAccumulator { cols, count: 0, mean: vec![0.0; cols], comoment: vec![0.0; cols * cols] }
     }
-    
-    if min == max {
-        let mut res = vec![0; bins];
-        res[0] = data.len() as u32;
-        return res;
+
+    /// Adds a chunk of `rows` observations (row-major `rows×cols`).
+    pub fn update(&mut self, data: &[f64], rows: usize, cols: usize) -> Result<(), JsValue> {
+        check_matrix(data, rows, cols)?;
+        if cols != self.cols {
+            return Err(JsValue::from_str("Chunk column count does not match the accumulator"));
+        }
+        if rows == 0 { return Ok(()); }
+
+        let chunk_mean = means_by_column(data, rows, cols);
+        let chunk_comoment = comoment_matrix(data, rows, cols, &chunk_mean);
+        let (na, nb) = (self.count as f64, rows as f64);
+        let n = na + nb;
+        let delta: Vec<f64> = chunk_mean.iter().zip(&self.mean).map(|(b, a)| b - a).collect();
+        let weight = na * nb / n;
+        for i in 0..cols {
+            for j in 0..cols {
+                self.comoment[i * cols + j] += chunk_comoment[i * cols + j] + delta[i] * delta[j] * weight;
+            }
+        }
+        for (m, d) in self.mean.iter_mut().zip(&delta) {
+            *m += d * nb / n;
+        }
+        self.count += rows;
+        Ok(())
     }
-    
-    let range = max - min;
-    let bin_width = range / bins as f64;
-    
-    // Parallel counting using atomic-like structure or just chunks + merge
-    // For now, simple thread-local histograms and then merge
-    let chunk_size = (data.len() / rayon::current_num_threads().max(1)).max(1024);
-    
-    data.par_chunks(chunk_size)
-        .map(|chunk| {
-            let mut local_counts = vec![0u32; bins];
-            for &x in chunk {
-                let bin_idx = (((x - min) / bin_width).floor() as usize).min(bins - 1);
-                local_counts[bin_idx] += 1;
+
+    /// Sample covariance matrix ($n-1$) of everything seen so far; zeros with fewer than 2 rows.
+    pub fn finalize(&self) -> Vec<f64> {
+        if self.count < 2 { return vec![0.0; self.cols * self.cols]; }
+        let scale = 1.0 / (self.count - 1) as f64;
+        self.comoment.iter().map(|v| v * scale).collect()
+    }
+
+    /// Pearson correlation matrix of everything seen so far (same conventions as `correlationMatrix`).
+    pub fn correlation(&self) -> Vec<f64> {
+        correlation_from_covariance(&self.finalize(), self.cols)
+    }
+
+    /// Running column means.
+    #[wasm_bindgen(getter)]
+    pub fn means(&self) -> Vec<f64> {
+        self.mean.clone()
+    }
+
+    /// Number of observations accumulated.
+    #[wasm_bindgen(getter)]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.mean.fill(0.0);
+        self.comoment.fill(0.0);
+    }
+}
+
+fn means_by_column(data: &[f64], rows: usize, cols: usize) -> Vec<f64> {
+    fold_columns(data, cols, 0.0, |a, _, v| a + v, |a, b| a + b)
+        .into_iter()
+        .map(|s| s / rows as f64)
+        .collect()
+}
+
+/// Per-column reduction of a row-major matrix: rows are folded in parallel with
+/// `step(acc, column, value)` and the partial results combined with `merge`.
+fn fold_columns(
+    data: &[f64],
+    cols: usize,
+    init: f64,
+    step: impl Fn(f64, usize, f64) -> f64 + Sync,
+    merge: impl Fn(f64, f64) -> f64 + Sync,
+) -> Vec<f64> {
+    data.par_chunks(cols.max(1))
+        .with_min_len(1024)
+        .fold(|| vec![init; cols], |mut acc, row| {
+            for (j, (a, &v)) in acc.iter_mut().zip(row).enumerate() { *a = step(*a, j, v); }
+            acc
+        })
+        .reduce(|| vec![init; cols], |mut a, b| {
+            for (x, y) in a.iter_mut().zip(b) { *x = merge(*x, y); }
+            a
+        })
+}
+
+fn check_matrix(data: &[f64], rows: usize, cols: usize) -> Result<(), JsValue> {
+    if data.len() != rows * cols {
+        return Err(JsValue::from_str("Matrix dimensions do not match data length"));
+    }
+    Ok(())
+}
+
+/// Mean of each column of a row-major `rows×cols` matrix - Parallel
+#[wasm_bindgen(js_name = columnMeans)]
+pub fn column_means(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    check_matrix(data, rows, cols)?;
+    Ok(means_by_column(data, rows, cols))
+}
+
+/// Sample standard deviation ($n-1$) of each column of a row-major `rows×cols` matrix - Parallel
+#[wasm_bindgen(js_name = columnStd)]
+pub fn column_std(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    check_matrix(data, rows, cols)?;
+    if rows < 2 { return Ok(vec![0.0; cols]); }
+    let means = means_by_column(data, rows, cols);
+    Ok(fold_columns(data, cols, 0.0, |a, j, v| a + (v - means[j]).powi(2), |a, b| a + b)
+        .into_iter()
+        .map(|ss| (ss / (rows - 1) as f64).sqrt())
+        .collect())
+}
+
+/// Minimum of each column of a row-major `rows×cols` matrix - Parallel
+#[wasm_bindgen(js_name = columnMin)]
+pub fn column_min(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    check_matrix(data, rows, cols)?;
+    Ok(fold_columns(data, cols, f64::INFINITY, |a, _, v| a.min(v), f64::min))
+}
+
+/// Maximum of each column of a row-major `rows×cols` matrix - Parallel
+#[wasm_bindgen(js_name = columnMax)]
+pub fn column_max(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    check_matrix(data, rows, cols)?;
+    Ok(fold_columns(data, cols, f64::NEG_INFINITY, |a, _, v| a.max(v), f64::max))
+}
+
+/// Sum of each row of a row-major `rows×cols` matrix - Parallel
+#[wasm_bindgen(js_name = rowSums)]
+pub fn row_sums(data: &[f64], rows: usize, cols: usize) -> Result<Vec<f64>, JsValue> {
+    check_matrix(data, rows, cols)?;
+    if cols == 0 { return Ok(vec![0.0; rows]); }
+    Ok(data.par_chunks(cols)
+        .with_min_len(1024)
+        .map(|row| row.iter().sum())
+        .collect())
+}
+
+/// Sample covariance matrix around the given column means, parallel over the upper triangle.
+fn covariance_from_means(data: &[f64], rows: usize, cols: usize, means: &[f64]) -> Vec<f64> {
+    let scale = 1.0 / (rows - 1) as f64;
+    comoment_matrix(data, rows, cols, means).into_iter().map(|v| v * scale).collect()
+}
+
+/// Centred cross-product sums $\sum_r (x_{ri} - m_i)(x_{rj} - m_j)$, parallel over the upper triangle.
+fn comoment_matrix(data: &[f64], rows: usize, cols: usize, means: &[f64]) -> Vec<f64> {
+    let pairs: Vec<(usize, usize)> = (0..cols)
+        .flat_map(|i| (i..cols).map(move |j| (i, j)))
+        .collect();
+
+    let values: Vec<f64> = pairs.par_iter()
+        .map(|&(i, j)| {
+            let (mi, mj) = (means[i], means[j]);
+            let mut sum = 0.0;
+            for r in 0..rows {
+                let row = &data[r * cols..(r + 1) * cols];
+                sum += (row[i] - mi) * (row[j] - mj);
             }
-            local_counts
+            sum
+        })
+        .collect();
+
+    let mut cov = vec![0.0; cols * cols];
+    for (&(i, j), v) in pairs.iter().zip(values) {
+        cov[i * cols + j] = v;
+        cov[j * cols + i] = v;
+    }
+    cov
+}
+
+/// Calculates the Pearson correlation over a trailing window of length `window` - Parallel
+///
+/// Output `i` covers samples `i + 1 - window ..= i`; the first `window - 1` outputs are NaN.
+/// Windows where either series is constant yield 0.0.
+#[wasm_bindgen(js_name = rollingCorrelation)]
+pub fn rolling_correlation(x: &[f64], y: &[f64], window: usize) -> Result<Vec<f64>, JsValue> {
+    let n = x.len();
+    if n != y.len() {
+        return Err(JsValue::from_str("Vectors must have the same length"));
+    }
+    if window < 2 {
+        return Err(JsValue::from_str("Window must be at least 2"));
+    }
+
+    let mut result = vec![f64::NAN; n];
+    if n < window { return Ok(result); }
+
+    let chunk_size = 32768;
+    let res_ptr = result.as_mut_ptr() as usize;
+    let w = window as f64;
+
+    (window - 1..n).into_par_iter().step_by(chunk_size).for_each(|start| unsafe {
+        let end = (start + chunk_size).min(n);
+        let out = res_ptr as *mut f64;
+
+        // Shift by the first sample of the window to limit cancellation
+        let first = start + 1 - window;
+        let (sx0, sy0) = (x[first], y[first]);
+        let (mut sx, mut sy, mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for j in first..start {
+            let (a, b) = (x[j] - sx0, y[j] - sy0);
+            sx += a; sy += b; sxy += a * b; sxx += a * a; syy += b * b;
+        }
+
+        for i in start..end {
+            let (a, b) = (x[i] - sx0, y[i] - sy0);
+            sx += a; sy += b; sxy += a * b; sxx += a * a; syy += b * b;
+
+            let cov = sxy - sx * sy / w;
+            let vx = sxx - sx * sx / w;
+            let vy = syy - sy * sy / w;
+            let denom = (vx * vy).sqrt();
+            *out.add(i) = if vx > 0.0 && vy > 0.0 && denom > 0.0 { (cov / denom).clamp(-1.0, 1.0) } else { 0.0 };
+
+            let old = i + 1 - window;
+            let (a, b) = (x[old] - sx0, y[old] - sy0);
+            sx -= a; sy -= b; sxy -= a * b; sxx -= a * a; syy -= b * b;
+        }
+    });
+
+    Ok(result)
+}
+
+/// Bin counts and the edges they were computed against, returned by `histogram`.
+#[wasm_bindgen]
+pub struct HistogramResult {
+    counts: Vec<u32>,
+    edges: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl HistogramResult {
+    /// Number of samples in each bin.
+    #[wasm_bindgen(getter)]
+    pub fn counts(&self) -> Vec<u32> {
+        self.counts.clone()
+    }
+
+    /// The `bins + 1` bin edges.
+    #[wasm_bindgen(getter)]
+    pub fn edges(&self) -> Vec<f64> {
+        self.edges.clone()
+    }
+}
+
+/// Calculates an equal-width histogram over the data range - Parallel
+///
+/// Bins are half-open, $[e_i, e_{i+1})$, except the last, which also includes the maximum.
+/// Non-finite values are ignored. If all values are equal the range is widened to
+/// $[v - 0.5, v + 0.5]$ so they land in the middle bin; with no finite values it is $[0, 1]$.
+#[wasm_bindgen]
+pub fn histogram(data: &[f64], bins: usize) -> HistogramResult {
+    let (counts, edges) = histogram_counts(data, bins);
+    HistogramResult { counts, edges }
+}
+
+pub(crate) fn histogram_counts(data: &[f64], bins: usize) -> (Vec<u32>, Vec<f64>) {
+    if bins == 0 { return (vec![], vec![]); }
+
+    let (lo, hi) = data.par_iter()
+        .with_min_len(8192)
+        .filter(|x| x.is_finite())
+        .fold(|| (f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| (lo.min(x), hi.max(x)))
+        .reduce(|| (f64::INFINITY, f64::NEG_INFINITY), |a, b| (a.0.min(b.0), a.1.max(b.1)));
+    let (min, max) = if lo > hi {
+        (0.0, 1.0)
+    } else if lo == hi {
+        (lo - 0.5, hi + 0.5)
+    } else {
+        (lo, hi)
+    };
+
+    let bin_width = (max - min) / bins as f64;
+    let mut edges: Vec<f64> = (0..bins).map(|i| min + i as f64 * bin_width).collect();
+    edges.push(max);
+
+    let counts = data.par_iter()
+        .with_min_len(8192)
+        .filter(|x| x.is_finite())
+        .fold(|| vec![0u32; bins], |mut acc, &x| {
+            let mut i = (((x - min) / bin_width).floor() as usize).min(bins - 1);
+            // Nudge across rounding so membership always agrees with the returned edges
+            if i > 0 && x < edges[i] { i -= 1; }
+            if i + 1 < bins && x >= edges[i + 1] { i += 1; }
+            acc[i] += 1;
+            acc
         })
         .reduce(|| vec![0u32; bins], |mut acc, local| {
-            for i in 0..bins {
-                acc[i] += local[i];
-            }
+            acc.iter_mut().zip(local).for_each(|(a, l)| *a += l);
+            acc
+        });
+    (counts, edges)
+}
+
+/// Calculates the Shannon entropy of the data's histogram, in nats.
+///
+/// $$ H = -\sum_i p_i \ln p_i $$
+///
+/// Divide by $\ln 2$ for bits.
+#[wasm_bindgen(js_name = shannonEntropy)]
+pub fn shannon_entropy(data: &[f64], bins: usize) -> f64 {
+    let (counts, _) = histogram_counts(data, bins);
+    let total = counts.iter().map(|&c| c as f64).sum::<f64>();
+    counts.iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / total;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+/// Estimates the mutual information between two series from their joint histogram, in nats.
+///
+/// $$ I(X;Y) = \sum_{i,j} p_{ij} \ln \frac{p_{ij}}{p_i p_j} $$
+///
+/// Divide by $\ln 2$ for bits. As in `histogram`, pairs with a non-finite value are ignored.
+#[wasm_bindgen(js_name = mutualInformation)]
+pub fn mutual_information(x: &[f64], y: &[f64], bins: usize) -> Result<f64, JsValue> {
+    if x.len() != y.len() {
+        return Err(JsValue::from_str("Vectors must have the same length"));
+    }
+    let (x, y): (Vec<f64>, Vec<f64>) = x.iter().zip(y)
+        .filter(|(a, b)| a.is_finite() && b.is_finite())
+        .map(|(&a, &b)| (a, b))
+        .unzip();
+    let n = x.len();
+    if n == 0 || bins == 0 { return Ok(0.0); }
+
+    let bx = bin_indices(&x, bins);
+    let by = bin_indices(&y, bins);
+
+    let joint = bx.par_iter().zip(by.par_iter())
+        .with_min_len(8192)
+        .fold(|| vec![0u32; bins * bins], |mut acc, (&i, &j)| {
+            acc[i * bins + j] += 1;
             acc
         })
+        .reduce(|| vec![0u32; bins * bins], |mut a, b| {
+            for (p, q) in a.iter_mut().zip(b) { *p += q; }
+            a
+        });
+
+    let mut px = vec![0.0; bins];
+    let mut py = vec![0.0; bins];
+    for i in 0..bins {
+        for j in 0..bins {
+            let c = joint[i * bins + j] as f64;
+            px[i] += c;
+            py[j] += c;
+        }
+    }
+
+    let total = n as f64;
+    let mut mi = 0.0;
+    for i in 0..bins {
+        for j in 0..bins {
+            let c = joint[i * bins + j];
+            if c == 0 { continue; }
+            let pxy = c as f64 / total;
+            mi += pxy * (pxy * total * total / (px[i] * py[j])).ln();
+        }
+    }
+    Ok(mi.max(0.0))
+}
+
+/// Maps each (finite) sample to its equal-width bin over the data range, like `histogram` for
+/// non-constant data. Constant data all maps to bin 0, which leaves the mutual information at zero.
+fn bin_indices(data: &[f64], bins: usize) -> Vec<usize> {
+    let min = data.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if min == max { return vec![0; data.len()]; }
+
+    let bin_width = (max - min) / bins as f64;
+    data.par_iter()
+        .with_min_len(8192)
+        .map(|&x| (((x - min) / bin_width).floor() as usize).min(bins - 1))
+        .collect()
 }
 
 /// Calculates the p-th percentile of a data set.
@@ -197,7 +717,12 @@ pub fn mode(data: &[f64]) -> f64 {
     mode_val
 }
 
-/// Calculates the skewness of a data set.
+/// Calculates the sample skewness of a data set (adjusted Fisher-Pearson, $G_1$).
+///
+/// $$ G_1 = \frac{n}{(n-1)(n-2)} \sum_{i=1}^n \left(\frac{x_i - \bar{x}}{s}\right)^3 $$
+///
+/// Matches Excel's `SKEW` and SciPy's `skew(bias=False)`. Returns 0.0 for `n < 3`.
+/// See `skewnessPopulation` for the biased moment estimator.
 #[wasm_bindgen]
 pub fn skewness(data: &[f64]) -> f64 {
     let n = data.len();
@@ -214,10 +739,17 @@ pub fn skewness(data: &[f64]) -> f64 {
     (n as f64 / ((n - 1) as f64 * (n - 2) as f64)) * sum_cubed
 }
 
-/// Calculates the kurtosis (excess) of a data set.
+/// Calculates the sample excess kurtosis of a data set ($G_2$).
+///
+/// $$ G_2 = \frac{n(n+1)}{(n-1)(n-2)(n-3)} \sum_{i=1}^n \left(\frac{x_i - \bar{x}}{s}\right)^4 - \frac{3(n-1)^2}{(n-2)(n-3)} $$
+///
+/// Matches Excel's `KURT` and SciPy's `kurtosis(bias=False)`. The correction is undefined
+/// for `n < 4` (the `n - 3` denominator vanishes), so 0.0 is returned in that case.
+/// See `kurtosisPopulation` for the biased moment estimator.
 #[wasm_bindgen]
 pub fn kurtosis(data: &[f64]) -> f64 {
     let n = data.len();
+    // Guard the (n - 2)(n - 3) denominators
     if n < 4 { return 0.0; }
     
     let m = mean(data);
@@ -234,6 +766,47 @@ pub fn kurtosis(data: &[f64]) -> f64 {
     term1 * sum_fourth - term2
 }
 
+/// Calculates the population skewness of a data set (Fisher's moment coefficient, $g_1$).
+///
+/// $$ g_1 = \frac{m_3}{m_2^{3/2}}, \quad m_k = \frac{1}{n} \sum_{i=1}^n (x_i - \bar{x})^k $$
+///
+/// Matches SciPy's default `skew`. Returns 0.0 for empty or constant data.
+#[wasm_bindgen(js_name = skewnessPopulation)]
+pub fn skewness_population(data: &[f64]) -> f64 {
+    let (m2, m3, _) = central_moments(data);
+    if m2 <= 0.0 { return 0.0; }
+    m3 / m2.powf(1.5)
+}
+
+/// Calculates the population excess kurtosis of a data set (Fisher's $g_2$).
+///
+/// $$ g_2 = \frac{m_4}{m_2^2} - 3, \quad m_k = \frac{1}{n} \sum_{i=1}^n (x_i - \bar{x})^k $$
+///
+/// Matches SciPy's default `kurtosis`. Returns 0.0 for empty or constant data.
+#[wasm_bindgen(js_name = kurtosisPopulation)]
+pub fn kurtosis_population(data: &[f64]) -> f64 {
+    let (m2, _, m4) = central_moments(data);
+    if m2 <= 0.0 { return 0.0; }
+    m4 / (m2 * m2) - 3.0
+}
+
+/// Biased central moments `(m2, m3, m4)` - Parallel
+fn central_moments(data: &[f64]) -> (f64, f64, f64) {
+    let n = data.len();
+    if n == 0 { return (0.0, 0.0, 0.0); }
+    let m = mean(data);
+    let (s2, s3, s4) = data.par_iter()
+        .with_min_len(8192)
+        .fold(|| (0.0, 0.0, 0.0), |acc, &x| {
+            let d = x - m;
+            let d2 = d * d;
+            (acc.0 + d2, acc.1 + d2 * d, acc.2 + d2 * d2)
+        })
+        .reduce(|| (0.0, 0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2));
+    let nf = n as f64;
+    (s2 / nf, s3 / nf, s4 / nf)
+}
+
 /// Finds the minimum value in a data set.
 #[wasm_bindgen]
 pub fn min(data: &[f64]) -> f64 {
@@ -252,6 +825,78 @@ pub fn max(data: &[f64]) -> f64 {
         .reduce(|| f64::NEG_INFINITY, f64::max)
 }
 
+/// Arithmetic mean ignoring non-finite values (`NaN`, `±Inf`) - Parallel
+///
+/// Returns `NaN` if no finite values remain. The number of values used is
+/// `data.length - countNan(data)`.
+#[wasm_bindgen]
+pub fn nanmean(data: &[f64]) -> f64 {
+    let (sum, count) = data.par_iter()
+        .with_min_len(8192)
+        .filter(|x| x.is_finite())
+        .fold(|| (0.0, 0usize), |(s, c), &x| (s + x, c + 1))
+        .reduce(|| (0.0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+    if count == 0 { return f64::NAN; }
+    sum / count as f64
+}
+
+/// Sample variance ignoring non-finite values - Parallel
+#[wasm_bindgen]
+pub fn nanvar(data: &[f64]) -> f64 {
+    variance(&finite_values(data), None)
+}
+
+/// Sample standard deviation ignoring non-finite values - Parallel
+#[wasm_bindgen]
+pub fn nanstd(data: &[f64]) -> f64 {
+    nanvar(data).sqrt()
+}
+
+/// Median ignoring non-finite values; `NaN` if no finite values remain.
+#[wasm_bindgen]
+pub fn nanmedian(data: &[f64]) -> f64 {
+    median(&finite_values(data))
+}
+
+/// Minimum ignoring non-finite values; `NaN` if no finite values remain.
+#[wasm_bindgen]
+pub fn nanmin(data: &[f64]) -> f64 {
+    let m = data.par_iter()
+        .with_min_len(8192)
+        .cloned()
+        .filter(|x| x.is_finite())
+        .reduce(|| f64::INFINITY, f64::min);
+    if m.is_finite() { m } else { f64::NAN }
+}
+
+/// Maximum ignoring non-finite values; `NaN` if no finite values remain.
+#[wasm_bindgen]
+pub fn nanmax(data: &[f64]) -> f64 {
+    let m = data.par_iter()
+        .with_min_len(8192)
+        .cloned()
+        .filter(|x| x.is_finite())
+        .reduce(|| f64::NEG_INFINITY, f64::max);
+    if m.is_finite() { m } else { f64::NAN }
+}
+
+/// Counts the non-finite values (`NaN`, `±Inf`) skipped by the `nan*` statistics - Parallel
+#[wasm_bindgen(js_name = countNan)]
+pub fn count_nan(data: &[f64]) -> usize {
+    data.par_iter()
+        .with_min_len(8192)
+        .filter(|x| !x.is_finite())
+        .count()
+}
+
+fn finite_values(data: &[f64]) -> Vec<f64> {
+    data.par_iter()
+        .with_min_len(8192)
+        .cloned()
+        .filter(|x| x.is_finite())
+        .collect()
+}
+
 /// Detects anomalies in a data set using Z-score.
 /// Returns a flattened array of [index, score, index, score, ...].
 #[wasm_bindgen(js_name = detectAnomalies)]
@@ -275,3 +920,383 @@ pub fn detect_anomalies(data: &[f64], threshold: f64) -> Vec<f64> {
         .flatten()
         .collect()
 }
+
+/// Median absolute deviation, scaled for consistency with the standard deviation of
+/// normally distributed data.
+///
+/// $$ \text{MAD} = 1.4826 \cdot \text{median}(|x_i - \text{median}(x)|) $$
+#[wasm_bindgen]
+pub fn mad(data: &[f64]) -> f64 {
+    if data.is_empty() { return f64::NAN; }
+    let med = median(data);
+    let deviations: Vec<f64> = data.par_iter()
+        .with_min_len(8192)
+        .map(|&x| (x - med).abs())
+        .collect();
+    1.4826 * median(&deviations)
+}
+
+/// Robust Z-scores using the median and MAD in place of the mean and standard deviation.
+///
+/// $$ z_i = \frac{x_i - \text{median}(x)}{\text{MAD}} $$
+///
+/// Returns all zeros when the MAD is zero.
+#[wasm_bindgen(js_name = robustZscore)]
+pub fn robust_zscore(data: &[f64]) -> Vec<f64> {
+    if data.is_empty() { return vec![]; }
+    let med = median(data);
+    let scale = mad(data);
+    if scale == 0.0 { return vec![0.0; data.len()]; }
+    data.par_iter()
+        .with_min_len(8192)
+        .map(|&x| (x - med) / scale)
+        .collect()
+}
+
+/// Detects anomalies using robust (median/MAD) Z-scores.
+/// Returns a flattened array of [index, score, index, score, ...].
+#[wasm_bindgen(js_name = detectAnomaliesRobust)]
+pub fn detect_anomalies_robust(data: &[f64], threshold: f64) -> Vec<f64> {
+    robust_zscore(data).par_iter()
+        .enumerate()
+        .filter_map(|(i, &z)| {
+            let score = z.abs();
+            if score > threshold {
+                Some(vec![i as f64, score])
+            } else {
+                None
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+/// Calculates the cumulative sum of a data set - Parallel (Two-Pass Scan)
+#[wasm_bindgen]
+pub fn cumsum(data: &[f64]) -> Vec<f64> {
+    parallel_scan(data, 0.0, |a, b| a + b)
+}
+
+/// Calculates the cumulative product of a data set - Parallel (Two-Pass Scan)
+#[wasm_bindgen]
+pub fn cumprod(data: &[f64]) -> Vec<f64> {
+    parallel_scan(data, 1.0, |a, b| a * b)
+}
+
+/// Calculates the running maximum of a data set - Parallel (Two-Pass Scan)
+#[wasm_bindgen]
+pub fn cummax(data: &[f64]) -> Vec<f64> {
+    parallel_scan(data, f64::NEG_INFINITY, f64::max)
+}
+
+/// Calculates the running minimum of a data set - Parallel (Two-Pass Scan)
+#[wasm_bindgen]
+pub fn cummin(data: &[f64]) -> Vec<f64> {
+    parallel_scan(data, f64::INFINITY, f64::min)
+}
+
+/// Inclusive scan with an associative `op`: chunks are scanned independently, then each
+/// chunk is offset by the combined totals of the chunks before it.
+fn parallel_scan(data: &[f64], identity: f64, op: fn(f64, f64) -> f64) -> Vec<f64> {
+    let n = data.len();
+    let mut out = data.to_vec();
+    let scan_seq = |chunk: &mut [f64]| {
+        let mut acc = identity;
+        for v in chunk.iter_mut() {
+            acc = op(acc, *v);
+            *v = acc;
+        }
+        acc
+    };
+
+    if n < 65536 {
+        scan_seq(&mut out);
+        return out;
+    }
+
+    let chunk_size = 32768;
+    let totals: Vec<f64> = out.par_chunks_mut(chunk_size).map(scan_seq).collect();
+
+    let mut offsets = Vec::with_capacity(totals.len());
+    let mut acc = identity;
+    for t in totals {
+        offsets.push(acc);
+        acc = op(acc, t);
+    }
+
+    out.par_chunks_mut(chunk_size)
+        .zip(offsets.par_iter())
+        .skip(1)
+        .for_each(|(chunk, &offset)| {
+            for v in chunk.iter_mut() { *v = op(offset, *v); }
+        });
+    out
+}
+
+/// Calculates the `order`-th successive differences of a data set - Parallel
+///
+/// $$ \Delta x_i = x_{i+1} - x_i $$
+///
+/// Applied `order` times; the output has length `n - order` (empty if `order >= n`).
+/// Unlike `diff5Pt`, no division by the sample spacing is performed.
+#[wasm_bindgen]
+pub fn diff(data: &[f64], order: usize) -> Vec<f64> {
+    if order >= data.len() { return vec![]; }
+    let mut current = data.to_vec();
+    for _ in 0..order {
+        current = current.par_windows(2)
+            .with_min_len(8192)
+            .map(|w| w[1] - w[0])
+            .collect();
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_edges_and_degenerate_input() {
+        let data = [-1.0, -0.5, 0.0, 0.5, 1.0, f64::NAN];
+        let (counts, edges) = histogram_counts(&data, 4);
+        assert_eq!(edges, vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+        // Half-open bins, with the maximum in the last one; NaN is ignored
+        assert_eq!(counts, vec![1, 1, 1, 2]);
+
+        let (counts, edges) = histogram_counts(&[3.0; 7], 5);
+        assert_eq!(counts, vec![0, 0, 7, 0, 0]);
+        assert_eq!(edges[0], 2.5);
+        assert_eq!(edges[5], 3.5);
+
+        let (counts, edges) = histogram_counts(&[], 2);
+        assert_eq!(counts, vec![0, 0]);
+        assert_eq!(edges, vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_column_and_row_statistics() {
+        let data = [1.0, 10.0, -2.0, 3.0, 20.0, 0.0, 5.0, 30.0, 2.0];
+        assert_eq!(column_means(&data, 3, 3).unwrap(), vec![3.0, 20.0, 0.0]);
+        assert_eq!(column_std(&data, 3, 3).unwrap(), vec![2.0, 10.0, 2.0]);
+        assert_eq!(column_min(&data, 3, 3).unwrap(), vec![1.0, 10.0, -2.0]);
+        assert_eq!(column_max(&data, 3, 3).unwrap(), vec![5.0, 30.0, 2.0]);
+        assert_eq!(row_sums(&data, 3, 3).unwrap(), vec![9.0, 23.0, 37.0]);
+    }
+
+    #[test]
+    fn test_cumsum_differences_recover_input() {
+        let data: Vec<f64> = (0..200_000).map(|i| ((i % 17) as f64) - 8.0).collect();
+        let c = cumsum(&data);
+        assert_eq!(c.len(), data.len());
+        assert_eq!(c[0], data[0]);
+        for i in 1..data.len() {
+            assert!((c[i] - c[i - 1] - data[i]).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_cumulative_extrema_and_product() {
+        let data = vec![3.0, 1.0, 4.0, 1.0, 5.0];
+        assert_eq!(cummax(&data), vec![3.0, 3.0, 4.0, 4.0, 5.0]);
+        assert_eq!(cummin(&data), vec![3.0, 1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(cumprod(&data), vec![3.0, 3.0, 12.0, 12.0, 60.0]);
+        assert!(cumsum(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_diff_orders() {
+        let data = vec![1.0, 4.0, 9.0, 16.0, 25.0];
+        assert_eq!(diff(&data, 1), vec![3.0, 5.0, 7.0, 9.0]);
+        assert_eq!(diff(&data, 2), vec![2.0, 2.0, 2.0]);
+        assert_eq!(diff(&data, 0), data);
+        assert!(diff(&data, 5).is_empty());
+    }
+
+    #[test]
+    fn test_population_moments() {
+        let data = vec![1.0, 2.0, 3.0, 10.0];
+        // m2 = 12.5, m3 = 45, m4 = 348.5 (mean 4)
+        assert!((skewness_population(&data) - 45.0 / 12.5f64.powf(1.5)).abs() < 1e-12);
+        assert!((kurtosis_population(&data) - (348.5 / 156.25 - 3.0)).abs() < 1e-12);
+        assert_eq!(kurtosis(&[1.0, 2.0, 4.0]), 0.0);
+        assert_eq!(skewness_population(&[2.0, 2.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_variance_stable_large_offset() {
+        let data = vec![1e9, 1e9 + 1.0, 1e9 + 2.0];
+        assert_eq!(variance_stable(&data), 1.0);
+
+        // Naive sum-of-squares loses every digit here
+        let n = data.len() as f64;
+        let sum: f64 = data.iter().sum();
+        let sum_sq: f64 = data.iter().map(|x| x * x).sum();
+        let naive = (sum_sq - sum * sum / n) / (n - 1.0);
+        assert!((naive - 1.0).abs() > 1e-3);
+
+        // Offset values a few ulps apart: the two-pass mean error swamps the spread
+        let h = 2f64.powi(-22);
+        let mut state = 12345u64;
+        let k: Vec<f64> = (0..1_000_000).map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((state >> 33) % 17) as f64 - 8.0
+        }).collect();
+        let data: Vec<f64> = k.iter().map(|&k| 123456789.123 + k * h).collect();
+        let exact = variance(&k, None) * h * h;
+        assert!(((variance_stable(&data) - exact) / exact).abs() < 1e-12);
+        assert!(((variance(&data, None) - exact) / exact).abs() > 1e-3);
+        assert_eq!(variance(&data, Some(true)), variance_stable(&data));
+    }
+
+    #[test]
+    fn test_variance_stable_parallel_merge() {
+        let data: Vec<f64> = (0..100_000).map(|i| 1e6 + ((i * 7919) % 1000) as f64 * 0.01).collect();
+        assert!((variance_stable(&data) - variance(&data, None)).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_correlation_stable_large_offset() {
+        // Small spread exactly representable on top of a 1e8 offset
+        let a: Vec<f64> = (0..50_000).map(|i| ((i * 7919) % 1000) as f64 * 0.25).collect();
+        let b: Vec<f64> = a.iter().enumerate().map(|(i, v)| v + ((i * 104_729) % 800) as f64 * 0.25).collect();
+        let x: Vec<f64> = a.iter().map(|v| 1e8 + v).collect();
+        let y: Vec<f64> = b.iter().map(|v| 1e8 + v).collect();
+        let reference = correlation(&a, &b).unwrap();
+        assert!((correlation_stable(&x, &y).unwrap() - reference).abs() < 1e-9);
+
+        // Textbook single-pass sums lose the signal entirely
+        let n = x.len() as f64;
+        let (sx, sy) = (x.iter().sum::<f64>(), y.iter().sum::<f64>());
+        let sxy: f64 = x.iter().zip(&y).map(|(p, q)| p * q).sum();
+        let sxx: f64 = x.iter().map(|p| p * p).sum();
+        let syy: f64 = y.iter().map(|q| q * q).sum();
+        let naive = (n * sxy - sx * sy) / ((n * sxx - sx * sx) * (n * syy - sy * sy)).sqrt();
+        assert!(!((naive - reference).abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_parallel_scan_matches_sequential_extrema() {
+        let data: Vec<f64> = (0..150_000).map(|i| ((i as f64) * 0.001).sin() * i as f64).collect();
+        let c = cummax(&data);
+        let mut acc = f64::NEG_INFINITY;
+        for (i, &v) in data.iter().enumerate() {
+            acc = acc.max(v);
+            assert_eq!(c[i], acc);
+        }
+    }
+
+    #[test]
+    fn test_nan_statistics_skip_non_finite() {
+        let data = [1.0, f64::NAN, 2.0, f64::INFINITY, 3.0, f64::NEG_INFINITY, 4.0];
+        assert_eq!(count_nan(&data), 3);
+        assert!((nanmean(&data) - 2.5).abs() < 1e-12);
+        assert!((nanvar(&data) - 5.0 / 3.0).abs() < 1e-12);
+        assert!((nanstd(&data) - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(nanmedian(&data), 2.5);
+        assert_eq!(nanmin(&data), 1.0);
+        assert_eq!(nanmax(&data), 4.0);
+
+        let empty = [f64::NAN, f64::INFINITY];
+        assert!(nanmean(&empty).is_nan());
+        assert!(nanmedian(&empty).is_nan());
+        assert!(nanmin(&empty).is_nan() && nanmax(&empty).is_nan());
+    }
+
+    #[test]
+    fn test_mad_and_robust_zscore() {
+        let data = [1.0, 2.0, 3.0, 4.0, 100.0];
+        // median = 3, |x - 3| = [2, 1, 0, 1, 97] -> median 1
+        assert!((mad(&data) - 1.4826).abs() < 1e-12);
+        let z = robust_zscore(&data);
+        assert!((z[0] + 2.0 / 1.4826).abs() < 1e-12);
+        assert_eq!(detect_anomalies_robust(&data, 3.5), vec![4.0, 97.0 / 1.4826]);
+        assert_eq!(robust_zscore(&[2.0, 2.0, 2.0]), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn test_covariance_accumulator_matches_batch() {
+        let (rows, cols) = (9, 3);
+        let data: Vec<f64> = (0..rows * cols)
+            .map(|i| 1e6 + ((i * 37) % 11) as f64 + 0.1 * (i % cols) as f64 * (i / cols) as f64)
+            .collect();
+        let batch = covariance_matrix(&data, rows, cols).unwrap();
+
+        let mut acc = CovarianceAccumulator::new(cols);
+        acc.update(&data[..2 * cols], 2, cols).unwrap();
+        acc.update(&data[2 * cols..3 * cols], 1, cols).unwrap();
+        acc.update(&data[3 * cols..], 6, cols).unwrap();
+        assert_eq!(acc.count(), rows);
+        for (a, b) in acc.finalize().iter().zip(&batch) {
+            assert!((a - b).abs() < 1e-8 * b.abs().max(1.0), "{} vs {}", a, b);
+        }
+        let corr = acc.correlation();
+        assert!((corr[0] - 1.0).abs() < 1e-12 && corr[1].abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_covariance_and_correlation_matrix() {
+        // Columns x = [1, 2, 3], y = [2, 4, 7]
+        let data = [1.0, 2.0, 2.0, 4.0, 3.0, 7.0];
+        let cov = covariance_matrix(&data, 3, 2).unwrap();
+        let expected = [1.0, 2.5, 2.5, 57.0 / 9.0];
+        assert!(cov.iter().zip(&expected).all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let corr = correlation_matrix(&data, 3, 2).unwrap();
+        let r = 2.5 / (57.0f64 / 9.0).sqrt();
+        assert!((corr[0] - 1.0).abs() < 1e-12 && (corr[3] - 1.0).abs() < 1e-12);
+        assert!((corr[1] - r).abs() < 1e-12 && (corr[2] - r).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rolling_correlation_against_window_pearson() {
+        let r = rolling_correlation(&[1.0, 2.0, 3.0, 4.0], &[2.0, 4.0, 6.0, 5.0], 3).unwrap();
+        assert!(r[0].is_nan() && r[1].is_nan());
+        assert!((r[2] - 1.0).abs() < 1e-12);
+        assert!((r[3] - 0.5).abs() < 1e-12);
+
+        // Across the 32768 chunk boundary against `correlation` on each window
+        let n = 40_000;
+        let x: Vec<f64> = (0..n).map(|i| (i as f64 * 0.37).sin()).collect();
+        let y: Vec<f64> = (0..n).map(|i| (i as f64 * 0.37).sin() + (i as f64 * 1.3).cos()).collect();
+        let r = rolling_correlation(&x, &y, 50).unwrap();
+        for i in [49, 32_816, 32_817, 32_818, n - 1] {
+            let reference = correlation(&x[i - 49..=i], &y[i - 49..=i]).unwrap();
+            assert!((r[i] - reference).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_entropy_and_mutual_information() {
+        let ln4 = 4.0f64.ln();
+        assert!((shannon_entropy(&[0.0, 1.0, 2.0, 3.0], 4) - ln4).abs() < 1e-12);
+        assert_eq!(shannon_entropy(&[5.0; 10], 4), 0.0);
+
+        // Identical series share all their information; independent ones share none
+        let x = [0.0, 1.0, 2.0, 3.0];
+        assert!((mutual_information(&x, &x, 4).unwrap() - ln4).abs() < 1e-12);
+        let mi = mutual_information(&[0.0, 0.0, 1.0, 1.0], &[0.0, 1.0, 0.0, 1.0], 2).unwrap();
+        assert!(mi.abs() < 1e-12);
+
+        // Non-finite pairs are dropped rather than stretching the bins
+        let xs = [0.0, 1.0, 2.0, 3.0, f64::INFINITY, 1.0];
+        let ys = [0.0, 1.0, 2.0, 3.0, 2.0, f64::NAN];
+        assert!((mutual_information(&xs, &ys, 4).unwrap() - ln4).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_correlation_stable_beats_two_pass() {
+        // Integer steps of 2^-22 on a ~1e8 offset: exact inputs, but the two-pass mean is off
+        // by a sizeable fraction of the spread
+        let n = 1_000_000usize;
+        let step = 2f64.powi(-22);
+        let kx: Vec<f64> = (0..n).map(|k| (k % 1000) as f64).collect();
+        let ky: Vec<f64> = (0..n).map(|k| ((k % 1000) + (k * 7919) % 13) as f64).collect();
+        let x: Vec<f64> = kx.iter().map(|k| 123456789.123 + k * step).collect();
+        let y: Vec<f64> = ky.iter().map(|k| 98765432.1 + k * step).collect();
+        let truth = correlation(&kx, &ky).unwrap();
+
+        assert!((correlation_stable(&x, &y).unwrap() - truth).abs() < 1e-12);
+        assert!((correlation(&x, &y).unwrap() - truth).abs() > 1e-2);
+    }
+}
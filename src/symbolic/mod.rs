@@ -1,7 +1,8 @@
 use wasm_bindgen::prelude::*;
-use std::collections::HashMap;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     Number(f64),
     Variable(String),
@@ -92,6 +93,120 @@ impl Expr {
         }
     }
 
+    /// Taylor polynomial of order `order` about `var = about`:
+    /// $$ \sum_{k=0}^{n} \frac{f^{(k)}(a)}{k!} (x - a)^k $$
+    /// Other variables are evaluated as 0 when computing the coefficients.
+    pub fn taylor(&self, var: &str, about: f64, order: usize) -> Expr {
+        let mut vars = HashMap::new();
+        vars.insert(var.to_string(), about);
+
+        let shifted = if about == 0.0 {
+            Expr::Variable(var.to_string())
+        } else {
+            Expr::Sub(Box::new(Expr::Variable(var.to_string())), Box::new(Expr::Number(about)))
+        };
+
+        let mut derivative = self.clone();
+        let mut factorial = 1.0;
+        let mut series = Expr::Number(0.0);
+        for k in 0..=order {
+            if k > 0 {
+                derivative = derivative.diff(var).simplify();
+                factorial *= k as f64;
+            }
+            let coeff = derivative.eval(&vars) / factorial;
+            let term = match k {
+                0 => Expr::Number(coeff),
+                1 => Expr::Mul(Box::new(Expr::Number(coeff)), Box::new(shifted.clone())),
+                _ => Expr::Mul(
+                    Box::new(Expr::Number(coeff)),
+                    Box::new(Expr::Pow(Box::new(shifted.clone()), Box::new(Expr::Number(k as f64)))),
+                ),
+            };
+            series = Expr::Add(Box::new(series), Box::new(term));
+        }
+        series.simplify()
+    }
+
+    /// Canonical form: constants folded, `a - b` rewritten as `a + (-1 * b)`, sums and products
+    /// flattened with like terms (`2x + 3x`) and like factors (`x * x^2`) combined, and
+    /// commutative operands sorted by their string form.
+    ///
+    /// Best effort only: it does not expand products, apply trigonometric/logarithmic
+    /// identities, or cancel across divisions beyond `a / a`, so equivalent expressions may
+    /// still have different canonical forms.
+    pub fn canonicalize(&self) -> Expr {
+        match self {
+            Expr::Number(_) | Expr::Variable(_) => self.clone(),
+            Expr::Add(l, r) => canonical_sum(vec![l.canonicalize(), r.canonicalize()]),
+            Expr::Sub(l, r) => canonical_sum(vec![
+                l.canonicalize(),
+                canonical_product(vec![Expr::Number(-1.0), r.canonicalize()]),
+            ]),
+            Expr::Mul(l, r) => canonical_product(vec![l.canonicalize(), r.canonicalize()]),
+            Expr::Div(l, r) => match (l.canonicalize(), r.canonicalize()) {
+                (Expr::Number(a), Expr::Number(b)) => Expr::Number(a / b),
+                (Expr::Number(0.0), _) => Expr::Number(0.0),
+                (e, Expr::Number(1.0)) => e,
+                (a, b) if a == b => Expr::Number(1.0),
+                (a, b) => Expr::Div(Box::new(a), Box::new(b)),
+            },
+            Expr::Pow(l, r) => match (l.canonicalize(), r.canonicalize()) {
+                (Expr::Number(a), Expr::Number(b)) => Expr::Number(a.powf(b)),
+                (_, Expr::Number(0.0)) => Expr::Number(1.0),
+                (e, Expr::Number(1.0)) => e,
+                (Expr::Number(1.0), _) => Expr::Number(1.0),
+                (a, b) => Expr::Pow(Box::new(a), Box::new(b)),
+            },
+            Expr::Sin(e) => canonical_unary(e, Expr::Sin, f64::sin),
+            Expr::Cos(e) => canonical_unary(e, Expr::Cos, f64::cos),
+            Expr::Exp(e) => canonical_unary(e, Expr::Exp, f64::exp),
+            Expr::Ln(e) => canonical_unary(e, Expr::Ln, f64::ln),
+        }
+    }
+
+    /// Best-effort equivalence: equal canonical forms, or agreement (relative tolerance `1e-9`)
+    /// at 16 fixed pseudo-random points in `[-2, 2]` for every variable. Points where either
+    /// side is not finite are skipped; fewer than 3 usable points means "not equal".
+    ///
+    /// The numeric check can report false positives for expressions that only differ outside
+    /// the sampled region or by less than the tolerance.
+    pub fn equivalent(&self, other: &Expr) -> bool {
+        if self.canonicalize() == other.canonicalize() { return true; }
+
+        let mut names = BTreeSet::new();
+        self.collect_variables(&mut names);
+        other.collect_variables(&mut names);
+
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 * 4.0 - 2.0
+        };
+
+        let mut usable = 0;
+        for _ in 0..16 {
+            let vars: HashMap<String, f64> = names.iter().map(|n| (n.clone(), next())).collect();
+            let (a, b) = (self.eval(&vars), other.eval(&vars));
+            if !a.is_finite() || !b.is_finite() { continue; }
+            if (a - b).abs() > 1e-9 * a.abs().max(b.abs()).max(1.0) { return false; }
+            usable += 1;
+        }
+        usable >= 3
+    }
+
+    fn collect_variables(&self, out: &mut BTreeSet<String>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Variable(v) => { out.insert(v.clone()); }
+            Expr::Add(l, r) | Expr::Sub(l, r) | Expr::Mul(l, r) | Expr::Div(l, r) | Expr::Pow(l, r) => {
+                l.collect_variables(out);
+                r.collect_variables(out);
+            }
+            Expr::Sin(e) | Expr::Cos(e) | Expr::Exp(e) | Expr::Ln(e) => e.collect_variables(out),
+        }
+    }
+
     pub fn eval(&self, vars: &HashMap<String, f64>) -> f64 {
         match self {
             Expr::Number(n) => *n,
@@ -108,6 +223,69 @@ impl Expr {
         }
     }
 
+    /// Evaluates with a single bound variable; any other variable evaluates to 0 as in `eval`.
+    fn eval_at(&self, var: &str, x: f64) -> f64 {
+        match self {
+            Expr::Number(n) => *n,
+            Expr::Variable(v) => if v == var { x } else { 0.0 },
+            Expr::Add(l, r) => l.eval_at(var, x) + r.eval_at(var, x),
+            Expr::Sub(l, r) => l.eval_at(var, x) - r.eval_at(var, x),
+            Expr::Mul(l, r) => l.eval_at(var, x) * r.eval_at(var, x),
+            Expr::Div(l, r) => l.eval_at(var, x) / r.eval_at(var, x),
+            Expr::Pow(l, r) => l.eval_at(var, x).powf(r.eval_at(var, x)),
+            Expr::Sin(e) => e.eval_at(var, x).sin(),
+            Expr::Cos(e) => e.eval_at(var, x).cos(),
+            Expr::Exp(e) => e.eval_at(var, x).exp(),
+            Expr::Ln(e) => e.eval_at(var, x).ln(),
+        }
+    }
+
+    /// JavaScript function body with common subexpressions hoisted into numbered temporaries,
+    /// e.g. `const t0 = Math.sin(x); const t1 = (t0 * t0); return t1;` for `sin(x) * sin(x)`.
+    pub fn to_js_expr(&self) -> String {
+        self.to_js_body(&[])
+    }
+
+    /// Like `to_js_expr`, but temporaries also avoid the `reserved` names (e.g. the parameter
+    /// of the compiled function). Names used by the expression's variables are always skipped.
+    fn to_js_body(&self, reserved: &[&str]) -> String {
+        let mut taken = BTreeSet::new();
+        self.collect_variables(&mut taken);
+        taken.extend(reserved.iter().map(|s| s.to_string()));
+
+        let mut emitter = JsEmitter { temps: HashMap::new(), taken, next: 0, body: String::new() };
+        let result = self.emit_js(&mut emitter);
+        let mut body = emitter.body;
+        body.push_str(&format!("return {};", result));
+        body
+    }
+
+    /// Emits `const` bindings for this subtree and returns the name (or literal) that holds its
+    /// value. Identical subtrees map to the same code and therefore the same temporary, since
+    /// children are referenced by their already-numbered names.
+    fn emit_js(&self, em: &mut JsEmitter) -> String {
+        let code = match self {
+            Expr::Number(n) => return js_number(*n),
+            Expr::Variable(v) => return v.clone(),
+            Expr::Add(l, r) => format!("({} + {})", l.emit_js(em), r.emit_js(em)),
+            Expr::Sub(l, r) => format!("({} - {})", l.emit_js(em), r.emit_js(em)),
+            Expr::Mul(l, r) => format!("({} * {})", l.emit_js(em), r.emit_js(em)),
+            Expr::Div(l, r) => format!("({} / {})", l.emit_js(em), r.emit_js(em)),
+            Expr::Pow(l, r) => format!("Math.pow({}, {})", l.emit_js(em), r.emit_js(em)),
+            Expr::Sin(e) => format!("Math.sin({})", e.emit_js(em)),
+            Expr::Cos(e) => format!("Math.cos({})", e.emit_js(em)),
+            Expr::Exp(e) => format!("Math.exp({})", e.emit_js(em)),
+            Expr::Ln(e) => format!("Math.log({})", e.emit_js(em)),
+        };
+        if let Some(name) = em.temps.get(&code) {
+            return name.clone();
+        }
+        let name = em.fresh_name();
+        em.body.push_str(&format!("const {} = {};\n", name, code));
+        em.temps.insert(code, name.clone());
+        name
+    }
+
     pub fn to_latex_internal(&self) -> String {
         match self {
             Expr::Number(n) => n.to_string(),
@@ -141,6 +319,112 @@ impl Expr {
     }
 }
 
+/// State for `Expr::emit_js`: hoisted code by text, names that temporaries must avoid and the
+/// body emitted so far.
+struct JsEmitter {
+    temps: HashMap<String, String>,
+    taken: BTreeSet<String>,
+    next: usize,
+    body: String,
+}
+
+impl JsEmitter {
+    /// Next `t{k}` name not already bound to a variable or reserved.
+    fn fresh_name(&mut self) -> String {
+        loop {
+            let name = format!("t{}", self.next);
+            self.next += 1;
+            if !self.taken.contains(&name) { return name; }
+        }
+    }
+}
+
+fn js_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".into()
+    } else if n.is_infinite() {
+        if n > 0.0 { "Infinity".into() } else { "(-Infinity)".into() }
+    } else if n < 0.0 {
+        format!("({})", n)
+    } else {
+        n.to_string()
+    }
+}
+
+fn canonical_unary(e: &Expr, wrap: fn(Box<Expr>) -> Expr, apply: fn(f64) -> f64) -> Expr {
+    match e.canonicalize() {
+        Expr::Number(n) => Expr::Number(apply(n)),
+        c => wrap(Box::new(c)),
+    }
+}
+
+fn flatten_into(e: Expr, is_sum: bool, out: &mut Vec<Expr>) {
+    match e {
+        Expr::Add(l, r) if is_sum => { flatten_into(*l, true, out); flatten_into(*r, true, out); }
+        Expr::Mul(l, r) if !is_sum => { flatten_into(*l, false, out); flatten_into(*r, false, out); }
+        other => out.push(other),
+    }
+}
+
+fn left_fold(items: Vec<Expr>, join: fn(Box<Expr>, Box<Expr>) -> Expr, empty: f64) -> Expr {
+    items.into_iter()
+        .reduce(|acc, e| join(Box::new(acc), Box::new(e)))
+        .unwrap_or(Expr::Number(empty))
+}
+
+/// Sum of canonical terms: constants folded, like terms merged, remaining terms sorted.
+fn canonical_sum(terms: Vec<Expr>) -> Expr {
+    let mut flat = Vec::new();
+    for t in terms { flatten_into(t, true, &mut flat); }
+
+    let mut constant = 0.0;
+    let mut groups: BTreeMap<String, (Expr, f64)> = BTreeMap::new();
+    for t in flat {
+        if let Expr::Number(n) = t { constant += n; continue; }
+        let mut factors = Vec::new();
+        flatten_into(t, false, &mut factors);
+        let coeff: f64 = factors.iter().filter_map(|f| if let Expr::Number(n) = f { Some(*n) } else { None }).product();
+        let rest = left_fold(factors.into_iter().filter(|f| !matches!(f, Expr::Number(_))).collect(), Expr::Mul, 1.0);
+        groups.entry(rest.to_string_internal()).or_insert((rest, 0.0)).1 += coeff;
+    }
+
+    let mut out = Vec::new();
+    if constant != 0.0 { out.push(Expr::Number(constant)); }
+    for (_, (rest, coeff)) in groups {
+        if coeff != 0.0 { out.push(canonical_product(vec![Expr::Number(coeff), rest])); }
+    }
+    left_fold(out, Expr::Add, 0.0)
+}
+
+/// Product of canonical factors: constants folded, powers of like bases merged, factors sorted.
+fn canonical_product(factors: Vec<Expr>) -> Expr {
+    let mut flat = Vec::new();
+    for f in factors { flatten_into(f, false, &mut flat); }
+
+    let mut coeff = 1.0;
+    let mut groups: BTreeMap<String, (Expr, f64)> = BTreeMap::new();
+    for f in flat {
+        let (base, exponent) = match f {
+            Expr::Number(n) => { coeff *= n; continue; }
+            Expr::Pow(b, e) => match *e {
+                Expr::Number(k) => (*b, k),
+                e => (Expr::Pow(b, Box::new(e)), 1.0),
+            },
+            other => (other, 1.0),
+        };
+        groups.entry(base.to_string_internal()).or_insert((base, 0.0)).1 += exponent;
+    }
+    if coeff == 0.0 { return Expr::Number(0.0); }
+
+    let mut out = Vec::new();
+    if coeff != 1.0 { out.push(Expr::Number(coeff)); }
+    for (_, (base, exponent)) in groups {
+        if exponent == 0.0 { continue; }
+        out.push(if exponent == 1.0 { base } else { Expr::Pow(Box::new(base), Box::new(Expr::Number(exponent))) });
+    }
+    left_fold(out, Expr::Mul, 1.0)
+}
+
 #[wasm_bindgen]
 pub struct SymbolicExpr {
     inner: Expr,
@@ -169,17 +453,42 @@ impl SymbolicExpr {
         SymbolicExpr { inner: self.inner.integrate(var) }
     }
 
+    /// Taylor polynomial of the given order about `var = about`.
+    pub fn taylor(&self, var: &str, about: f64, order: usize) -> SymbolicExpr {
+        SymbolicExpr { inner: self.inner.taylor(var, about, order) }
+    }
+
+    /// Canonical form used for structural comparison (see `Expr::canonicalize`).
+    pub fn canonicalize(&self) -> SymbolicExpr {
+        SymbolicExpr { inner: self.inner.canonicalize() }
+    }
+
+    /// Best-effort equivalence check: canonical forms, then numeric sampling.
+    pub fn equals(&self, other: &SymbolicExpr) -> bool {
+        self.inner.equivalent(&other.inner)
+    }
+
     pub fn eval(&self, var_name: &str, val: f64) -> f64 {
         let mut vars = HashMap::new();
         vars.insert(var_name.to_string(), val);
         self.inner.eval(&vars)
     }
 
+    /// Evaluates the expression at every value of `var_name` in one call - Parallel
+    pub fn eval_array(&self, var_name: &str, values: &[f64]) -> Vec<f64> {
+        values.par_iter()
+            .with_min_len(8192)
+            .map(|&x| self.inner.eval_at(var_name, x))
+            .collect()
+    }
+
     pub fn compile(&self, var_name: &str) -> js_sys::Function {
-        let expr_str = self.inner.to_string_internal();
-        // Simple replacement of ^ with ** for JS
-        let js_expr = expr_str.replace("^", "**");
-        js_sys::Function::new_with_args(var_name, &format!("return {};", js_expr))
+        js_sys::Function::new_with_args(var_name, &self.inner.to_js_body(&[var_name]))
+    }
+
+    /// Body of the function generated by `compile`, with shared subexpressions computed once.
+    pub fn to_js_expr(&self) -> String {
+        self.inner.to_js_expr()
     }
 
     pub fn to_latex(&self) -> String {
@@ -190,3 +499,114 @@ impl SymbolicExpr {
         self.inner.to_string_internal()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn x() -> Box<Expr> {
+        Box::new(Expr::Variable("x".into()))
+    }
+
+    #[test]
+    fn test_taylor_sin_order_5() {
+        let sin = SymbolicExpr { inner: Expr::Sin(x()) };
+        let poly = sin.taylor("x", 0.0, 5);
+        for &v in &[-0.7f64, -0.1, 0.0, 0.3, 1.2] {
+            let series = v - v.powi(3) / 6.0 + v.powi(5) / 120.0;
+            assert!((poly.eval("x", v) - series).abs() < 1e-12);
+        }
+        // Even-order coefficients vanish and are simplified away
+        assert_eq!(poly.to_js_string().matches('^').count(), 2);
+    }
+
+    #[test]
+    fn test_taylor_exp_about_one() {
+        let exp = Expr::Exp(x());
+        let poly = exp.taylor("x", 1.0, 8);
+        let mut vars = HashMap::new();
+        vars.insert("x".to_string(), 1.3);
+        assert!((poly.eval(&vars) - 1.3f64.exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_canonicalize_orders_and_merges_terms() {
+        let y = || Box::new(Expr::Variable("y".into()));
+        let num = |n: f64| Box::new(Expr::Number(n));
+        // (y * x) + (2 * (x * y)) - x*x  vs  x^2 * -1 + 3 * (x * y)
+        let a = Expr::Sub(
+            Box::new(Expr::Add(
+                Box::new(Expr::Mul(y(), x())),
+                Box::new(Expr::Mul(num(2.0), Box::new(Expr::Mul(x(), y())))),
+            )),
+            Box::new(Expr::Mul(x(), x())),
+        );
+        let b = Expr::Add(
+            Box::new(Expr::Mul(Box::new(Expr::Pow(x(), num(2.0))), num(-1.0))),
+            Box::new(Expr::Mul(num(3.0), Box::new(Expr::Mul(x(), y())))),
+        );
+        assert_eq!(a.canonicalize(), b.canonicalize());
+        assert!(a.equivalent(&b));
+        assert_eq!(Expr::Sub(x(), x()).canonicalize(), Expr::Number(0.0));
+    }
+
+    #[test]
+    fn test_equals_falls_back_to_sampling() {
+        // d/dx sin(x)^2 = 2 sin(x) cos(x), which canonical forms alone do not prove
+        let sq = SymbolicExpr { inner: Expr::Pow(Box::new(Expr::Sin(x())), Box::new(Expr::Number(2.0))) };
+        let expected = SymbolicExpr { inner: Expr::Mul(
+            Box::new(Expr::Number(2.0)),
+            Box::new(Expr::Mul(Box::new(Expr::Sin(x())), Box::new(Expr::Cos(x())))),
+        ) };
+        assert!(sq.diff("x").equals(&expected));
+        assert!(!sq.equals(&expected));
+    }
+
+    #[test]
+    fn test_eval_array_matches_eval() {
+        let expr = SymbolicExpr { inner: Expr::Add(
+            Box::new(Expr::Sin(x())),
+            Box::new(Expr::Mul(Box::new(Expr::Number(2.0)), Box::new(Expr::Pow(x(), Box::new(Expr::Number(2.0)))))),
+        ) };
+        let values: Vec<f64> = (0..20_000).map(|i| i as f64 * 1e-3 - 10.0).collect();
+        let out = expr.eval_array("x", &values);
+        assert_eq!(out.len(), values.len());
+        for (i, &v) in values.iter().enumerate().step_by(997) {
+            assert_eq!(out[i], expr.eval("x", v));
+        }
+    }
+
+    #[test]
+    fn test_to_js_expr_shares_subexpressions() {
+        // sin(x)^2 + sin(x)^2 * (-2): sin(x) and its square are each computed once
+        let sq = || Box::new(Expr::Pow(Box::new(Expr::Sin(x())), Box::new(Expr::Number(2.0))));
+        let expr = Expr::Add(sq(), Box::new(Expr::Mul(sq(), Box::new(Expr::Number(-2.0)))));
+        let js = expr.to_js_expr();
+        assert_eq!(
+            js,
+            "const t0 = Math.sin(x);\n\
+             const t1 = Math.pow(t0, 2);\n\
+             const t2 = (t1 * (-2));\n\
+             const t3 = (t1 + t2);\n\
+             return t3;"
+        );
+        assert_eq!(Expr::Variable("x".into()).to_js_expr(), "return x;");
+    }
+
+    #[test]
+    fn test_to_js_temporaries_skip_variable_names() {
+        // sin(t1) * sin(t1) + t0 must not shadow t0 or t1 with a temporary
+        let v = |name: &str| Box::new(Expr::Variable(name.into()));
+        let s = || Box::new(Expr::Sin(v("t1")));
+        let expr = Expr::Add(Box::new(Expr::Mul(s(), s())), v("t0"));
+        assert_eq!(
+            expr.to_js_expr(),
+            "const t2 = Math.sin(t1);\n\
+             const t3 = (t2 * t2);\n\
+             const t4 = (t3 + t0);\n\
+             return t4;"
+        );
+        // A reserved parameter name is skipped as well
+        assert_eq!(Expr::Sin(x()).to_js_body(&["t0"]), "const t1 = Math.sin(x);\nreturn t1;");
+    }
+}
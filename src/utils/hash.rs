@@ -0,0 +1,66 @@
+//! # Hashing
+//!
+//! Fast non-cryptographic fingerprints for content-addressed caches of computed results.
+
+use wasm_bindgen::prelude::*;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Reflected CRC-32 (IEEE 802.3) lookup table.
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+/// 64-bit FNV-1a hash of the little-endian bytes of `data`, as 16 lowercase hex digits.
+///
+/// The hash is bit-exact: `-0.0` and `0.0` hash differently, as do NaNs with different
+/// payloads, even though they compare equal (or unequal) as numbers.
+#[wasm_bindgen(js_name = hashF64)]
+pub fn hash_f64(data: &[f64]) -> String {
+    let mut h = FNV_OFFSET;
+    for v in data {
+        for b in v.to_le_bytes() {
+            h = (h ^ b as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{:016x}", h)
+}
+
+/// CRC-32 (IEEE 802.3, as used by zlib and PNG) of a byte array.
+#[wasm_bindgen(js_name = checksumCRC32)]
+pub fn checksum_crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |c, &b| CRC32_TABLE[((c ^ b as u32) & 0xFF) as usize] ^ (c >> 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_vectors() {
+        assert_eq!(checksum_crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(checksum_crc32(b""), 0);
+        assert_eq!(hash_f64(&[]), "cbf29ce484222325");
+    }
+
+    #[test]
+    fn test_hash_f64_is_bit_exact() {
+        let a = [1.0, 2.5, -3.0];
+        assert_eq!(hash_f64(&a), hash_f64(&a.to_vec()));
+        assert_ne!(hash_f64(&a), hash_f64(&[1.0, 2.5, -3.0000000000000004]));
+        assert_ne!(hash_f64(&[0.0]), hash_f64(&[-0.0]));
+    }
+}
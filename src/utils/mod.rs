@@ -0,0 +1,139 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+pub mod hash;
+pub mod rng;
+
+#[wasm_bindgen]
+pub fn set_panic_hook() {
+    // When the `console_error_panic_hook` feature is enabled, we can call the
+    // `set_panic_hook` function at least once during initialization, and then
+    // we will get better error messages if our code ever panics.
+    //
+    // For more details see
+    // https://github.com/rustwasm/console_error_panic_hook#readme
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
+const WASM_PAGE_BYTES: usize = 65_536;
+
+/// Bytes currently reserved by `SciEngine` vectors (including pooled slabs) and their peak.
+static ENGINE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static ENGINE_PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Records bytes newly reserved by the engine and updates the high-water mark.
+pub fn track_engine_alloc(bytes: usize) {
+    let now = ENGINE_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    ENGINE_PEAK_BYTES.fetch_max(now, Ordering::Relaxed);
+}
+
+/// Records bytes returned to the allocator by the engine.
+pub fn track_engine_free(bytes: usize) {
+    ENGINE_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HeapStats {
+    memory_pages: usize,
+    memory_bytes: usize,
+    engine_bytes: usize,
+    engine_high_water_bytes: usize,
+}
+
+/// Reports WASM linear-memory usage for sizing chunked workloads.
+///
+/// Returns `{ memoryPages, memoryBytes, engineBytes, engineHighWaterBytes }`. Linear memory
+/// only ever grows, so `memoryBytes` is itself the process-wide high-water mark; the engine
+/// counters cover vectors owned by `SciEngine` instances. The default allocator is not
+/// instrumented, so per-allocation totals are not available. On non-WASM targets the
+/// memory fields are 0.
+#[wasm_bindgen(js_name = heapStats)]
+pub fn heap_stats() -> Result<JsValue, JsValue> {
+    #[cfg(target_arch = "wasm32")]
+    let memory_pages = core::arch::wasm32::memory_size(0);
+    #[cfg(not(target_arch = "wasm32"))]
+    let memory_pages = 0;
+
+    let stats = HeapStats {
+        memory_pages,
+        memory_bytes: memory_pages * WASM_PAGE_BYTES,
+        engine_bytes: ENGINE_BYTES.load(Ordering::Relaxed),
+        engine_high_water_bytes: ENGINE_PEAK_BYTES.load(Ordering::Relaxed),
+    };
+    serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Builds a callback that reports `done / total` to an optional JS `progress` function,
+/// at most ~100 times per run (plus once on completion).
+///
+/// Everything here runs synchronously on the calling thread: the host can log progress or
+/// abort by throwing from `progress` (the error propagates out of the routine), but the page
+/// will not repaint until the call returns. Run long jobs in a Web Worker to keep the UI live.
+pub fn progress_reporter(progress: Option<&js_sys::Function>, total: usize) -> impl FnMut(usize) -> Result<(), JsValue> + '_ {
+    let stride = (total / 100).max(1);
+    move |done| {
+        if let Some(cb) = progress {
+            if done % stride == 0 || done == total {
+                cb.call1(&JsValue::NULL, &JsValue::from_f64(done as f64 / total.max(1) as f64))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cooperative cancellation flag for long computations.
+///
+/// Routines that accept one check it once per iteration and stop early, returning their best
+/// partial result. Routines take ownership of the flag they are given, so pass
+/// `flag.handle()` and keep the original to call `abort()` on — e.g. from a `progress`
+/// callback, or from the objective function after reading a `SharedArrayBuffer` that
+/// another thread writes to.
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct AbortFlag {
+    flag: Arc<AtomicBool>,
+}
+
+#[wasm_bindgen]
+impl AbortFlag {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> AbortFlag {
+        AbortFlag::default()
+    }
+
+    pub fn abort(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.flag.store(false, Ordering::Relaxed);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn aborted(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    /// Another handle to the same flag.
+    pub fn handle(&self) -> AbortFlag {
+        self.clone()
+    }
+}
+
+/// Per-iteration hook combining `progress_reporter` with an optional abort flag:
+/// reports `done / total` and returns whether the computation should continue.
+pub fn iteration_control<'a>(
+    progress: Option<&'a js_sys::Function>,
+    abort: Option<&'a AbortFlag>,
+    total: usize,
+) -> impl FnMut(usize) -> Result<bool, JsValue> + 'a {
+    let mut report = progress_reporter(progress, total);
+    move |done| {
+        report(done)?;
+        Ok(!abort.is_some_and(|a| a.aborted()))
+    }
+}
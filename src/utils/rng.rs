@@ -0,0 +1,105 @@
+//! # Seeded Random Numbers
+//!
+//! Reproducible bulk generators backed by `ChaCha8Rng`. The parallel generators split their
+//! output into chunks that seek to their own offset in the ChaCha stream, so a given seed
+//! yields the same values regardless of the number of threads.
+
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use wasm_bindgen::prelude::*;
+
+/// Samples per parallel chunk; even, so normal chunks start on a Box-Muller pair.
+const CHUNK: usize = 8192;
+
+/// Generator positioned at the 32-bit word `word` of the stream for `seed`.
+fn stream_at(seed: u32, word: usize) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+    rng.set_word_pos(word as u128);
+    rng
+}
+
+/// `n` uniform samples in `[lo, hi)` - Parallel
+#[wasm_bindgen(js_name = randomUniform)]
+pub fn random_uniform(n: usize, lo: f64, hi: f64, seed: u32) -> Result<Vec<f64>, JsValue> {
+    if !(lo.is_finite() && hi.is_finite() && lo <= hi) {
+        return Err(JsValue::from_str("Bounds must be finite with lo <= hi"));
+    }
+    let mut out = vec![0.0; n];
+    fill_uniform(&mut out, lo, hi, seed, 0);
+    Ok(out)
+}
+
+/// Fills `out` with samples `offset..offset + out.len()` of the `randomUniform` sequence for `seed`.
+pub(crate) fn fill_uniform(out: &mut [f64], lo: f64, hi: f64, seed: u32, offset: usize) {
+    out.par_chunks_mut(CHUNK).enumerate().for_each(|(c, chunk)| {
+        // One u64 (two words) per sample
+        let mut rng = stream_at(seed, (offset + c * CHUNK) * 2);
+        for v in chunk {
+            *v = lo + (hi - lo) * rng.gen::<f64>();
+        }
+    });
+}
+
+/// `n` normal samples with the given mean and standard deviation (Box-Muller) - Parallel
+#[wasm_bindgen(js_name = randomNormal)]
+pub fn random_normal(n: usize, mean: f64, std: f64, seed: u32) -> Vec<f64> {
+    let mut out = vec![0.0; n];
+    out.par_chunks_mut(CHUNK).enumerate().for_each(|(c, chunk)| {
+        // Each pair consumes two u64 draws, i.e. four words
+        let mut rng = stream_at(seed, c * CHUNK * 2);
+        for pair in chunk.chunks_mut(2) {
+            let u1: f64 = 1.0 - rng.gen::<f64>();
+            let u2: f64 = rng.gen();
+            let r = std * (-2.0 * u1.ln()).sqrt();
+            let theta = 2.0 * std::f64::consts::PI * u2;
+            pair[0] = mean + r * theta.cos();
+            if let Some(second) = pair.get_mut(1) {
+                *second = mean + r * theta.sin();
+            }
+        }
+    });
+    out
+}
+
+/// Random permutation of `0..n` (Fisher-Yates).
+#[wasm_bindgen(js_name = shuffleIndices)]
+pub fn shuffle_indices(n: usize, seed: u32) -> Vec<u32> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed as u64);
+    let mut idx: Vec<u32> = (0..n as u32).collect();
+    idx.shuffle(&mut rng);
+    idx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uniform_matches_sequential_stream() {
+        let n = 3 * CHUNK + 5;
+        let u = random_uniform(n, -2.0, 3.0, 11).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        for v in &u {
+            assert_eq!(*v, -2.0 + 5.0 * rng.gen::<f64>());
+        }
+        assert!(u.iter().all(|v| (-2.0..3.0).contains(v)));
+
+        let mut tail = vec![0.0; 100];
+        fill_uniform(&mut tail, -2.0, 3.0, 11, CHUNK - 50);
+        assert_eq!(tail[..], u[CHUNK - 50..CHUNK + 50]);
+    }
+
+    #[test]
+    fn test_normal_moments_and_permutation() {
+        let z = random_normal(50_000, 3.0, 0.5, 1);
+        let mean = z.iter().sum::<f64>() / z.len() as f64;
+        let std = (z.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / z.len() as f64).sqrt();
+        assert!((mean - 3.0).abs() < 0.01 && (std - 0.5).abs() < 0.01);
+
+        let mut p = shuffle_indices(100, 5);
+        assert_eq!(p, shuffle_indices(100, 5));
+        p.sort_unstable();
+        assert!(p.iter().enumerate().all(|(i, &v)| v == i as u32));
+    }
+}
@@ -1,6 +1,7 @@
 import { describe, it, expect, beforeAll } from 'vitest';
 import * as wasm from '../pkg/node/sci_math_wasm.js';
 import { SciMathJS } from '../src/sci-math';
+import { estimateSNR } from '../src/js-mirror/analysis';
 import { Worker } from 'node:worker_threads';
 
 // Polyfill Worker for wasm-bindgen-rayon in Node.js
@@ -46,6 +47,11 @@ describe('Cross-Validation: WASM vs JS', () => {
     it('should match Signal: Moving Average & SNR', () => {
         const data = new Float64Array([1, 2, 1, 2, 1, 10, 1, 2, 1]);
         assertNear(SciMathJS.movingAverage(data, 3), wasm.movingAverage(data, 3));
+        assertNear(estimateSNR(data), wasm.estimateSNR(data));
+
+        const noisy = new Float64Array(256);
+        for (let i = 0; i < noisy.length; i++) noisy[i] = Math.sin(i / 10) + 0.1 * Math.sin(i * 2.3);
+        assertNear(estimateSNR(noisy), wasm.estimateSNR(noisy));
     });
 
     it('should match FFT', () => {
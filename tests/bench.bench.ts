@@ -1,5 +1,5 @@
 import { bench, describe } from 'vitest';
-import { mean, matrix_multiply, fft, moving_average, derivative, poly_eval, linear_regression } from '../pkg/node';
+import { mean, matrix_multiply, fft, moving_average, derivative, poly_eval, linear_regression, fftC2C, FftPlan, SymbolicExpr } from '../pkg/node';
 
 // Simple pure JS versions for comparison
 const jsMean = (data: Float64Array) => data.reduce((a, b) => a + b, 0) / data.length;
@@ -54,6 +54,21 @@ describe('benchmarks wasm vs js', () => {
     fft(signalInput);
   });
 
+  // Repeated complex transforms: stateless call vs precomputed plan
+  const planRe = new Float64Array(4096);
+  const planIm = new Float64Array(4096);
+  const fftPlan = new FftPlan(4096);
+
+  bench('fftC2C wasm 4096', () => {
+    fftC2C(signalInput, planIm, false);
+  });
+
+  bench('fft plan wasm 4096', () => {
+    planRe.set(signalInput);
+    planIm.fill(0);
+    fftPlan.forward(planRe, planIm);
+  });
+
   // Simple JS Moving Average for comparison
   const jsMovingAverage = (data: Float64Array, window: number) => {
     const res = new Float64Array(data.length);
@@ -154,3 +169,19 @@ describe('benchmarks wasm vs js', () => {
     jsLinearRegression(regX, regY);
   });
 });
+
+describe('symbolic evaluation', () => {
+  // 0.5 * x^2, which `compile` can express in plain JS
+  const expr = SymbolicExpr.parse('x').integrate('x');
+  const compiled = expr.compile('x');
+  const points = Float64Array.from({ length: 100_000 }, (_, i) => i * 1e-3);
+
+  bench('symbolic eval_array wasm 100k', () => {
+    expr.eval_array('x', points);
+  });
+
+  bench('symbolic compiled fn 100k', () => {
+    const out = new Float64Array(points.length);
+    for (let i = 0; i < points.length; i++) out[i] = compiled(points[i]);
+  });
+});
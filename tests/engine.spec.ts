@@ -2,7 +2,7 @@ import os from 'os';
 import { beforeAll, describe, expect, it } from 'vitest';
 import * as wasm from '../pkg/node/sci_math_wasm.js';
 
-const { SciEngine, initThreadPool, get_wasm_memory } = wasm as any;
+const { SciEngine, initThreadPool, get_wasm_memory, heapStats } = wasm as any;
 
 beforeAll(async () => {
     const maybeInit = (wasm as any).default;
@@ -31,6 +31,46 @@ describe('SciEngine', () => {
         expect(ptr).toBeDefined();
     });
 
+    it('should recycle freed vectors when pooling', () => {
+        const engine = new SciEngine();
+        engine.set_pooling(true);
+        const id = engine.create_vector(1000);
+        expect(engine.free_vector(id)).toBe(true);
+        expect(engine.free_vector(id)).toBe(false);
+
+        let stats = engine.memory_stats();
+        expect(stats.freeSlabs).toBe(1);
+        expect(stats.usedBytes).toBe(0);
+
+        engine.create_vector(500);
+        stats = engine.memory_stats();
+        expect(stats.freeSlabs).toBe(0);
+        expect(stats.usedBytes).toBe(500 * 8);
+        expect(stats.allocatedBytes).toBeGreaterThanOrEqual(1000 * 8);
+    });
+
+    it('should keep pooled slabs across a CSV import', () => {
+        const engine = new SciEngine();
+        engine.set_pooling(true);
+        engine.free_vector(engine.create_vector(1000));
+        expect(engine.memory_stats().freeSlabs).toBe(1);
+
+        const ids = engine.import_csv(new TextEncoder().encode('1,2\n3,4\n'), 44, 0);
+        expect(ids.length).toBe(1);
+        expect(engine.memory_stats().freeSlabs).toBe(1);
+    });
+
+    it('should report engine allocations in heap stats', () => {
+        const before = heapStats();
+        const engine = new SciEngine();
+        engine.create_vector(4096);
+        const after = heapStats();
+        expect(after.memoryBytes).toBe(after.memoryPages * 65536);
+        expect(after.engineBytes).toBe(before.engineBytes + 4096 * 8);
+        expect(after.engineHighWaterBytes).toBeGreaterThanOrEqual(after.engineBytes);
+        engine.free();
+    });
+
     it('should perform signal processing', () => {
         const engine = new SciEngine();
         const n = 100;
@@ -183,11 +183,13 @@ describe('optimization', () => {
         // f(x) = x^2, min at 0
         const f = (input: number[]) => input[0] * input[0];
         const bounds = new Float64Array([-10, 10]);
-        // pop=100, gens=100, mut=0.1
-        const res = genetic_algorithm(f, bounds, 100, 100, 0.1);
+        // pop=100, gens=100, mut=0.1, seed=42
+        const res = genetic_algorithm(f, bounds, 100, 100, 0.1, 42);
         expect(res.length).toBe(1);
         // Relax check as it is stochastic. < 4.0 is reasonable for -10..10 range reduction in a few gens
         expect(Math.abs(res[0])).toBeLessThan(4.0);
+        // Same seed reproduces the same run
+        expect(Array.from(genetic_algorithm(f, bounds, 100, 100, 0.1, 42))).toEqual(Array.from(res));
     });
 });
 